@@ -1,30 +1,86 @@
-use crate::{log_on_error, ui::popup::PopUp};
+use crate::{
+    log_on_error,
+    ui::popup::{help::HelpPopUpBuilder, PopUp},
+};
 use ansi_to_tui::IntoText;
 use color_eyre::eyre::bail;
 use config::Config;
-use cover_renderer::render_cover;
+use cover_renderer::{render_cover, CoverRenderer};
+use jobs::{JobContext, JobId, JobManager};
 use logging::Logger;
-use patch_hub::lore::{lore_api_client::BlockingLoreAPIClient, lore_session, patch::Patch};
+use patch_hub::lore::{
+    http_agent,
+    http_cache::HttpCache,
+    instance::{find_instance, Instance},
+    lei::{self, LEI_LIST_PREFIX},
+    local_source::LocalSource,
+    lore_api_client::{BlockingLoreAPIClient, ClientError, RequestEvent, ThreadRequest},
+    lore_session::{self, LoreSessionError},
+    mailing_list::MailingListMatch,
+    patch::{Patch, PatchFilter, PatchOrder},
+    query::LoreQuery,
+    range_diff,
+    request_policy::{RateLimiter, RetryPolicy},
+    seen::{self, SeenPatchsets},
+    series::{self, SeriesHistory, SeriesMetadata, SeriesParts},
+    thread::Thread,
+    trailers::PatchsetTrailers,
+    watch::{self, NewPatchsets},
+};
 use patch_renderer::{render_patch_preview, PatchRenderer};
+use prefetch::Prefetcher;
 use ratatui::text::Text;
 use screens::{
     bookmarked::BookmarkedPatchsets,
-    details_actions::{DetailsActions, PatchsetAction},
+    details_actions::{DetailsActions, PatchsetAction, RangeDiffPreview},
     edit_config::EditConfig,
-    latest::LatestPatchsets,
+    latest::{FetchJob, FetchedFeed, LatestPatchsets},
     mail_list::MailingListSelection,
+    search::Search,
+    thread::ThreadView,
+    watches::Watches,
     CurrentScreen,
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::utils;
 
+/// Most new patchsets counted for each watch
+const MAX_NEW_WATCHED_PATCHSETS: usize = 50;
+
 mod config;
 pub mod cover_renderer;
+pub mod jobs;
 pub mod logging;
 pub mod patch_renderer;
+pub mod prefetch;
 pub mod screens;
 
+/// What a background job hands back to be applied to the [App], in the main
+/// thread, once it finishes.
+pub type JobCompletion = Box<dyn FnOnce(&mut App) -> color_eyre::Result<()> + Send>;
+
+/// Patchset downloaded and rendered, ready to be shown in [DetailsActions]
+#[derive(Clone)]
+pub struct LoadedPatchset {
+    /// Raw patches as plain text files
+    raw_patches: Vec<String>,
+    /// Patches in the format to be displayed as preview
+    patches_preview: Vec<Text<'static>>,
+    /// Metadata of the series, if it was sent with `b4`
+    series_metadata: SeriesMetadata,
+    /// Trailers given in the replies to the patches, if the thread could be
+    /// fetched
+    trailers: Option<PatchsetTrailers>,
+    /// Parts of the series that were downloaded
+    series_parts: SeriesParts,
+}
+
 /// Type that represents the overall state of the application. It can be viewed
 /// as the **Model** component of `patch-hub`.
 pub struct App {
@@ -38,15 +94,38 @@ pub struct App {
     pub latest_patchsets: Option<LatestPatchsets>,
     /// Screen with details (metadata and previewing) and runnable actions of individual patchset
     pub details_actions: Option<DetailsActions>,
+    /// Screen with the threaded conversation of the patchset in `details_actions`
+    pub thread_view: Option<ThreadView>,
     /// Screen to edit configurations of the app
     pub edit_config: Option<EditConfig>,
+    /// Screen to build a structured search query over a mailing list
+    pub search: Option<Search>,
+    /// Screen with the saved searches whose new patchsets are tracked
+    pub watches: Watches,
     /// Database to track patchsets `Reviewed-by` state
     pub reviewed_patchsets: HashMap<String, Vec<usize>>,
+    /// Patchsets already opened, whose entries aren't shown as unread
+    pub seen_patchsets: SeenPatchsets,
     /// Configurations of the app
     pub config: Config,
     /// Client to handle Lore API requests and responses
     pub lore_api_client: BlockingLoreAPIClient,
+    /// Whether no requests are sent, with feeds and patchsets coming only
+    /// from what was saved and cached before
+    pub is_offline: bool,
     pub popup: Option<Box<dyn PopUp>>,
+    /// Jobs running in background, like fetching feeds and loading patchsets
+    pub jobs: JobManager<JobCompletion>,
+    /// Job loading the patchset to be shown in [App::details_actions], if any
+    pub details_actions_job: Option<JobId>,
+    /// Patchsets loaded in advance or previously opened
+    pub prefetcher: Prefetcher<LoadedPatchset>,
+    /// Trailers harvested from the threads of the patchsets loaded so far, by
+    /// the URL of their message-id
+    pub patchset_trailers: HashMap<String, PatchsetTrailers>,
+    /// Parts of the patchsets loaded so far, as downloaded, by the URL of
+    /// their message-id
+    pub patchset_parts: HashMap<String, SeriesParts>,
 }
 
 impl App {
@@ -62,8 +141,12 @@ impl App {
         let config: Config = Config::build();
         config.create_dirs();
 
-        let mailing_lists =
-            lore_session::load_available_lists(config.mailing_lists_path()).unwrap_or_default();
+        // Lists of instances that are no longer configured are left out
+        let mailing_lists: Vec<_> = lore_session::load_available_lists(config.mailing_lists_path())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|mailing_list| config.instance(mailing_list.instance()).is_some())
+            .collect();
 
         let bookmarked_patchsets =
             lore_session::load_bookmarked_patchsets(config.bookmarked_patchsets_path())
@@ -73,34 +156,107 @@ impl App {
             lore_session::load_reviewed_patchsets(config.reviewed_patchsets_path())
                 .unwrap_or_default();
 
-        let lore_api_client = BlockingLoreAPIClient::default();
+        let list_preferences = lore_session::load_list_preferences(&config.list_preferences_path())
+            .unwrap_or_default();
+
+        let watches = watch::load_watches(&config.watches_path()).unwrap_or_default();
+
+        let seen_patchsets =
+            seen::load_seen_patchsets(&config.seen_patchsets_path()).unwrap_or_default();
 
         // Initialize the logger before the app starts
         Logger::init_log_file(&config);
         Logger::info("patch-hub started");
+        if let Err(io_error) = config.migrate_patchsets_cache() {
+            Logger::warn(format!(
+                "Failed to migrate downloaded patchsets: {io_error}"
+            ));
+        }
         logging::garbage_collector::collect_garbage(&config);
 
-        App {
+        let lore_api_client = build_lore_api_client(&config);
+
+        let mailing_list_selection = MailingListSelection {
+            mailing_lists: mailing_lists.clone(),
+            target_list: String::new(),
+            possible_mailing_list_matches: vec![MailingListMatch::default(); mailing_lists.len()],
+            possible_mailing_lists: mailing_lists,
+            highlighted_list_index: 0,
+            mailing_lists_path: config.mailing_lists_path().to_string(),
+            instance_clients: build_instance_clients(&config, &lore_api_client),
+            local_inboxes: config.all_local_inboxes(),
+            lei_searches: Vec::new(),
+            list_preferences,
+            list_preferences_path: config.list_preferences_path(),
+            marked_lists: Vec::new(),
+        };
+
+        let mut app = App {
             current_screen: CurrentScreen::MailingListSelection,
-            mailing_list_selection: MailingListSelection {
-                mailing_lists: mailing_lists.clone(),
-                target_list: String::new(),
-                possible_mailing_lists: mailing_lists,
-                highlighted_list_index: 0,
-                mailing_lists_path: config.mailing_lists_path().to_string(),
-                lore_api_client: lore_api_client.clone(),
-            },
+            mailing_list_selection,
             latest_patchsets: None,
             details_actions: None,
+            thread_view: None,
             edit_config: None,
+            search: None,
+            watches: Watches {
+                watches,
+                watch_index: 0,
+                watches_path: config.watches_path(),
+                new_patchsets: HashMap::new(),
+                count_job: None,
+                is_watch_open: false,
+            },
             bookmarked_patchsets: BookmarkedPatchsets {
                 bookmarked_patchsets,
                 patchset_index: 0,
+                patch_filter: PatchFilter::default(),
+                order: PatchOrder::default(),
             },
             reviewed_patchsets,
-            config,
+            seen_patchsets,
             lore_api_client,
+            is_offline: false,
             popup: None,
+            jobs: JobManager::new(),
+            details_actions_job: None,
+            prefetcher: Prefetcher::new(config.prefetch_budget()),
+            patchset_trailers: HashMap::new(),
+            patchset_parts: HashMap::new(),
+            config,
+        };
+        // Local lists are available even if they were never fetched
+        app.load_lei_searches();
+        app.mailing_list_selection.add_local_lists();
+        app.mailing_list_selection.clear_target_list();
+
+        app
+    }
+
+    /// Lists the searches saved in `lei`, if enabled, to be offered as pseudo
+    /// lists in [App::mailing_list_selection]. If `lei` fails, the error is
+    /// logged and no searches are offered.
+    fn load_lei_searches(&mut self) {
+        self.mailing_list_selection.lei_searches = if self.config.use_lei() {
+            match lei::saved_searches(self.config.default_instance().url()) {
+                Ok(lei_searches) => lei_searches,
+                Err(client_error) => {
+                    Logger::error(format!("Failed to list lei searches: {client_error}"));
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Opens the last used list, if set to in the config and it's still
+    /// available.
+    pub fn open_last_used_list(&mut self) {
+        if self.config.open_last_list() && self.mailing_list_selection.highlight_last_used_list() {
+            self.init_latest_patchsets();
+            self.fetch_latest_patchsets_page();
+            self.set_current_screen(CurrentScreen::LatestPatchsets);
         }
     }
 
@@ -110,106 +266,1154 @@ impl App {
         // the target mailing list for "latest patchsets" is the highlighted
         // entry in the possible lists of "mailing list selection"
         let list_index = self.mailing_list_selection.highlighted_list_index;
-        let target_list = self.mailing_list_selection.possible_mailing_lists[list_index]
-            .name()
-            .to_string();
+        let target_list = self.mailing_list_selection.possible_mailing_lists[list_index].clone();
+        if let Err(io_error) = self.mailing_list_selection.record_list_use(&target_list) {
+            Logger::warn(format!("Failed to save list preferences: {io_error}"));
+        }
+        let instance = self
+            .config
+            .instance(target_list.instance())
+            .unwrap_or_else(|| self.config.default_instance());
+        let mut latest_patchsets = LatestPatchsets::new(
+            target_list.name().to_string(),
+            LoreQuery::default(),
+            self.config.page_size(),
+            self.lore_api_client.for_instance(instance),
+        )
+        .with_snapshot_path(self.config.feed_snapshot_path(instance, target_list.name()));
+        if let Some(local_source) = self.local_source_of_list(instance, target_list.name()) {
+            latest_patchsets = latest_patchsets.with_local_source(local_source);
+        } else if self.is_offline {
+            latest_patchsets.restore_snapshot();
+        }
+        self.latest_patchsets = Some(latest_patchsets);
+    }
+
+    /// Marks the highlighted list of [App::mailing_list_selection] to have
+    /// its feed merged or unmarks it (see
+    /// [MailingListSelection::toggle_mark_highlighted_list]), telling in a
+    /// popup why if it can't be marked. Lists read locally, like `lei`
+    /// searches, can't be marked, as only feeds requested from an instance
+    /// are merged.
+    pub fn toggle_mark_highlighted_list(&mut self) {
+        let mailing_list_selection = &self.mailing_list_selection;
+        let is_local = mailing_list_selection
+            .possible_mailing_lists
+            .get(mailing_list_selection.highlighted_list_index)
+            .is_some_and(|mailing_list| {
+                let instance = self
+                    .config
+                    .instance(mailing_list.instance())
+                    .unwrap_or_else(|| self.config.default_instance());
+                self.local_source_of_list(instance, mailing_list.name())
+                    .is_some()
+            });
+        let refusal = if is_local {
+            Some("Lists read locally can't be merged")
+        } else if !self.mailing_list_selection.toggle_mark_highlighted_list() {
+            Some("Only lists of the same instance can be merged")
+        } else {
+            None
+        };
+
+        if let Some(refusal) = refusal {
+            self.popup = Some(Box::new(
+                HelpPopUpBuilder::new()
+                    .title("Merged feed")
+                    .description(refusal)
+                    .build(),
+            ));
+        }
+    }
+
+    /// Initializes field [App::latest_patchsets] with the feeds of the lists
+    /// marked in [App::mailing_list_selection] merged, clearing the marks.
+    pub fn init_merged_latest_patchsets(&mut self) {
+        let marked_lists = std::mem::take(&mut self.mailing_list_selection.marked_lists);
+        let instance = marked_lists
+            .first()
+            .and_then(|marked_list| self.config.instance(marked_list.instance()))
+            .unwrap_or_else(|| self.config.default_instance());
+        let target_lists = marked_lists
+            .iter()
+            .map(|marked_list| marked_list.name().to_string())
+            .collect();
+
+        let mut latest_patchsets = LatestPatchsets::merged(
+            target_lists,
+            LoreQuery::default(),
+            self.config.page_size(),
+            self.lore_api_client.for_instance(instance),
+        );
+        let snapshot_path = self
+            .config
+            .feed_snapshot_path(instance, latest_patchsets.target_list());
+        latest_patchsets = latest_patchsets.with_snapshot_path(snapshot_path);
+        if self.is_offline {
+            latest_patchsets.restore_snapshot();
+        }
+        self.latest_patchsets = Some(latest_patchsets);
+    }
+
+    /// Initializes field [App::latest_patchsets] with the results of the
+    /// query built in [App::search], over the instance chosen in it or the
+    /// default one.
+    ///
+    /// Returns `false`, setting the status of the search, if the chosen
+    /// instance isn't configured.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `search` is `None`.
+    pub fn init_latest_patchsets_from_search(&mut self) -> bool {
+        let search = self.search.as_mut().unwrap();
+        let instance = match search.instance() {
+            Some(instance_name) => match self.config.instance(&instance_name) {
+                Some(instance) => instance,
+                None => {
+                    search.set_status(Some(format!("Unknown instance {instance_name}")));
+                    return false;
+                }
+            },
+            None => self.config.default_instance(),
+        };
+
         self.latest_patchsets = Some(LatestPatchsets::new(
-            target_list,
+            search.target_list(),
+            search.build_query(),
             self.config.page_size(),
-            self.lore_api_client.clone(),
+            self.lore_api_client.for_instance(instance),
         ));
+        true
     }
 
-    /// Sets field [App::latest_patchsets] to `None`.
+    /// Counts, in background, the new patchsets of each watch in
+    /// [App::watches], replacing a count still running. Watches of instances
+    /// that are no longer configured aren't counted.
+    pub fn count_new_watched_patchsets(&mut self) {
+        if let Some(count_job) = self.watches.count_job.take() {
+            self.jobs.cancel(count_job);
+        }
+
+        let watched: Vec<_> = self
+            .watches
+            .watches
+            .iter()
+            .filter_map(|watch| {
+                let instance = self.config.instance(watch.instance())?;
+                Some((watch.clone(), self.lore_api_client.for_instance(instance)))
+            })
+            .collect();
+        if watched.is_empty() {
+            return;
+        }
+
+        let count_job = self.jobs.spawn(
+            "Counting new watched patchsets".to_string(),
+            move |context| {
+                let count_job = context.id();
+                let mut counts = Vec::new();
+                for (watch, lore_api_client) in &watched {
+                    // The completion of cancelled jobs is ignored
+                    if context.is_cancelled() {
+                        break;
+                    }
+                    let new_patchsets = watch::count_new_patchsets(
+                        lore_api_client,
+                        watch,
+                        MAX_NEW_WATCHED_PATCHSETS,
+                    )
+                    .map_err(|lore_session_error| {
+                        color_eyre::Report::new(lore_session_error)
+                            .wrap_err(format!("Failed to count new patchsets of {}", watch.name()))
+                    });
+                    counts.push((watch.name().clone(), new_patchsets));
+                }
+                let completion: JobCompletion = Box::new(move |app: &mut App| {
+                    app.finish_watches_count(count_job, counts);
+                    Ok(())
+                });
+                Ok(completion)
+            },
+        );
+        self.watches.count_job = Some(count_job);
+    }
+
+    /// Applies the counts of `count_job`, if it's still the current one.
+    /// Failures are logged and leave the watch without a count.
+    fn finish_watches_count(
+        &mut self,
+        count_job: JobId,
+        counts: Vec<(String, color_eyre::Result<NewPatchsets>)>,
+    ) {
+        if self.watches.count_job != Some(count_job) {
+            return;
+        }
+        self.watches.count_job = None;
+
+        for (name, new_patchsets) in counts {
+            match new_patchsets {
+                Ok(new_patchsets) => {
+                    self.watches.new_patchsets.insert(name, new_patchsets);
+                }
+                Err(error) => {
+                    Logger::error(format!("{error:#}"));
+                    self.watches.new_patchsets.remove(&name);
+                }
+            }
+        }
+    }
+
+    /// Saves the search built in [App::search] as a watch, over the instance
+    /// chosen in it or the default one, setting the status of the search.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `search` is `None`.
+    pub fn save_search_as_watch(&mut self) {
+        let search = self.search.as_mut().unwrap();
+        let instance = search
+            .instance()
+            .unwrap_or_else(|| self.config.default_instance().name().to_string());
+        if self.config.instance(&instance).is_none() {
+            search.set_status(Some(format!("Unknown instance {instance}")));
+            return;
+        }
+
+        let new_watch = search.watch(&instance);
+        let status = match self.watches.add_watch(new_watch.clone()) {
+            Ok(()) => format!("Saved watch {}", new_watch.name()),
+            Err(io_error) => {
+                Logger::error(format!("Failed to save watches: {io_error}"));
+                format!("Failed to save watch {}", new_watch.name())
+            }
+        };
+        search.set_status(Some(status));
+    }
+
+    /// Initializes field [App::latest_patchsets] with the results of the
+    /// watch selected in [App::watches], marking them as seen. Returns
+    /// `false` if there is no watch selected or its instance isn't
+    /// configured.
+    pub fn init_latest_patchsets_from_watch(&mut self) -> bool {
+        let Some(watch) = self.watches.selected_watch() else {
+            return false;
+        };
+        let Some(instance) = self.config.instance(watch.instance()) else {
+            return false;
+        };
+
+        self.latest_patchsets = Some(LatestPatchsets::new(
+            watch.target_list().clone(),
+            watch.query().clone(),
+            self.config.page_size(),
+            self.lore_api_client.for_instance(instance),
+        ));
+        if let Err(io_error) = self.watches.mark_selected_watch_seen() {
+            Logger::error(format!("Failed to save watches: {io_error}"));
+        }
+        self.watches.is_watch_open = true;
+        true
+    }
+
+    /// Sets field [App::latest_patchsets] to `None`, cancelling its fetch, if
+    /// any.
     pub fn reset_latest_patchsets(&mut self) {
-        self.latest_patchsets = None;
+        if let Some(latest_patchsets) = self.latest_patchsets.take() {
+            if let Some(fetch_job) = latest_patchsets.fetch_job() {
+                self.jobs.cancel(*fetch_job);
+            }
+        }
+    }
+
+    /// Replaces field [App::latest_patchsets] with a copy that fetches its
+    /// patchsets again from the first page, bypassing cached responses that
+    /// are still considered fresh. If offline, the app tries to go back
+    /// online.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `latest_patchsets` is `None`.
+    pub fn refresh_latest_patchsets(&mut self) {
+        self.set_offline(false);
+
+        let latest_patchsets = self.latest_patchsets.as_ref().unwrap();
+        let mut refreshed_latest_patchsets = LatestPatchsets::merged(
+            latest_patchsets.target_lists().clone(),
+            latest_patchsets.query().clone(),
+            self.config.page_size(),
+            latest_patchsets
+                .lore_api_client()
+                .clone()
+                .with_offline(false)
+                .force_refresh(),
+        );
+        if let Some(snapshot_path) = latest_patchsets.snapshot_path() {
+            refreshed_latest_patchsets =
+                refreshed_latest_patchsets.with_snapshot_path(snapshot_path.clone());
+        }
+        if let Some(local_source) = latest_patchsets.local_source() {
+            refreshed_latest_patchsets =
+                refreshed_latest_patchsets.with_local_source(local_source.clone());
+        }
+
+        self.reset_latest_patchsets();
+        self.latest_patchsets = Some(refreshed_latest_patchsets);
+        self.fetch_latest_patchsets_page();
+    }
+
+    /// Fetches, in background, the patchsets of the current page of
+    /// [App::latest_patchsets], unless they are already fetched or being
+    /// fetched.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `latest_patchsets` is `None`.
+    pub fn fetch_latest_patchsets_page(&mut self) {
+        let latest_patchsets = self.latest_patchsets.as_ref().unwrap();
+        if latest_patchsets.fetch_job().is_some() || !latest_patchsets.is_current_page_missing() {
+            return;
+        }
+
+        let fetch = latest_patchsets.fetch_current_page_job();
+        self.spawn_latest_patchsets_fetch(fetch);
+    }
+
+    fn spawn_latest_patchsets_fetch(&mut self, fetch: impl FetchJob) {
+        let latest_patchsets = self.latest_patchsets.as_mut().unwrap();
+        let fetch_job = self.jobs.spawn(
+            format!("Fetching patchsets from {}", latest_patchsets.target_list()),
+            move |context| {
+                let fetch_job = context.id();
+                let fetched_feed = fetch(context);
+                let completion: JobCompletion = Box::new(move |app: &mut App| {
+                    app.finish_latest_patchsets_fetch(fetch_job, fetched_feed)
+                });
+                Ok(completion)
+            },
+        );
+        latest_patchsets.set_fetch_job(fetch_job);
+    }
+
+    fn finish_latest_patchsets_fetch(
+        &mut self,
+        fetch_job: JobId,
+        fetched_feed: color_eyre::Result<FetchedFeed>,
+    ) -> color_eyre::Result<()> {
+        let Some(latest_patchsets) = self.latest_patchsets.as_mut() else {
+            return Ok(());
+        };
+        if latest_patchsets.fetch_job() != &Some(fetch_job) {
+            return Ok(());
+        }
+        let fetched_feed = match fetched_feed {
+            Ok(fetched_feed) => fetched_feed,
+            Err(error) if is_unreachable(&error) => {
+                self.fail_latest_patchsets_fetch(&error);
+                return Ok(());
+            }
+            Err(error) => {
+                self.fail_job(fetch_job, error);
+                return Ok(());
+            }
+        };
+        latest_patchsets.finish_fetch(fetch_job, fetched_feed);
+
+        // Searches wait in the search screen for their first page, so the
+        // user can refine the query if nothing matches
+        if self.current_screen == CurrentScreen::Search {
+            let processed_patchsets_count = latest_patchsets.processed_patchsets_count();
+            let search = self.search.as_mut().unwrap();
+            if processed_patchsets_count == 0 {
+                search.set_status(Some("No patchsets match the query".to_string()));
+                self.reset_latest_patchsets();
+            } else {
+                search.set_status(None);
+                self.set_current_screen(CurrentScreen::LatestPatchsets);
+            }
+        } else if self.current_screen == CurrentScreen::LatestPatchsets {
+            // Patchsets filtered out may have left the page short
+            self.fetch_latest_patchsets_page();
+        }
+        Ok(())
+    }
+
+    /// Goes offline after a fetch of [App::latest_patchsets] failed because
+    /// its instance couldn't be reached, falling back to the last snapshot
+    /// of the feed, if nothing was fetched yet.
+    fn fail_latest_patchsets_fetch(&mut self, error: &color_eyre::Report) {
+        if !self.is_offline {
+            Logger::warn(format!("Going offline: {error:#}"));
+            self.set_offline(true);
+        }
+
+        let latest_patchsets = self.latest_patchsets.as_mut().unwrap();
+        let lore_api_client = latest_patchsets
+            .lore_api_client()
+            .clone()
+            .with_offline(true);
+        latest_patchsets.go_offline(lore_api_client);
+
+        if self.current_screen == CurrentScreen::Search {
+            let processed_patchsets_count = latest_patchsets.processed_patchsets_count();
+            let search = self.search.as_mut().unwrap();
+            if processed_patchsets_count == 0 {
+                search.set_status(Some("Search isn't available offline".to_string()));
+                self.reset_latest_patchsets();
+            } else {
+                search.set_status(None);
+                self.set_current_screen(CurrentScreen::LatestPatchsets);
+            }
+        }
+    }
+
+    /// Turns offline mode on or off. While offline, no requests are sent and
+    /// feeds, patchsets and lists come only from what was saved and cached.
+    pub fn set_offline(&mut self, offline: bool) {
+        if self.is_offline == offline {
+            return;
+        }
+        self.is_offline = offline;
+        self.lore_api_client = self.lore_api_client.clone().with_offline(offline);
+        self.mailing_list_selection.instance_clients =
+            build_instance_clients(&self.config, &self.lore_api_client);
+    }
+
+    /// Fetches the lists of every instance again. If offline, the app tries
+    /// to go back online and, if the instances still can't be reached, stays
+    /// offline with the lists it already had.
+    pub fn refresh_available_mailing_lists(&mut self) -> color_eyre::Result<()> {
+        self.set_offline(false);
+        self.load_lei_searches();
+
+        match self
+            .mailing_list_selection
+            .refresh_available_mailing_lists()
+        {
+            Err(error) if is_unreachable(&error) => {
+                Logger::warn(format!("Going offline: {error:#}"));
+                self.set_offline(true);
+                self.show_offline_notice("Mailing lists can't be fetched while offline");
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn show_offline_notice(&mut self, description: &str) {
+        self.popup = Some(Box::new(
+            HelpPopUpBuilder::new()
+                .title("Offline")
+                .description(description)
+                .build(),
+        ));
+    }
+
+    /// Applies the results of the background jobs that finished. Failed jobs
+    /// are handled by [App::fail_job], so they never take the app down.
+    pub fn apply_finished_jobs(&mut self) {
+        for (job, result) in self.jobs.poll() {
+            if let Err(error) = result.and_then(|completion| completion(self)) {
+                self.fail_job(job, error);
+            }
+        }
+    }
+
+    /// Handles the failure of `job`, like a panic. The user is told in a
+    /// popup if they were waiting on it, like when opening a patchset or
+    /// fetching the current page. Failures of jobs nobody waits on, like
+    /// prefetches, are only logged.
+    fn fail_job(&mut self, job: JobId, error: color_eyre::Report) {
+        self.prefetcher.forget_job(job);
+        let mut is_awaited = false;
+        if self.details_actions_job == Some(job) {
+            self.details_actions_job = None;
+            is_awaited = true;
+        }
+        if let Some(latest_patchsets) = self.latest_patchsets.as_mut() {
+            if latest_patchsets.fetch_job() == &Some(job) {
+                // Pages fetched in advance aren't waited on
+                is_awaited |= latest_patchsets.is_current_page_missing();
+                latest_patchsets.fail_fetch(job);
+            }
+        }
+
+        if !is_awaited {
+            Logger::warn(format!("Background job failed: {error:#}"));
+            return;
+        }
+        // Searches wait in the search screen for their first page
+        if self.current_screen == CurrentScreen::Search {
+            Logger::error(format!("{error:#}"));
+            let search = self.search.as_mut().unwrap();
+            search.set_status(Some(format!("Failed to fetch patchsets: {error}")));
+            self.reset_latest_patchsets();
+            return;
+        }
+        self.show_error(&error);
+    }
+
+    /// Logs `error` and tells the user about it in a popup.
+    fn show_error(&mut self, error: &color_eyre::Report) {
+        Logger::error(format!("{error:#}"));
+        self.popup = Some(Box::new(
+            HelpPopUpBuilder::new()
+                .title("Error")
+                .description(&format!("{error:#}"))
+                .build(),
+        ));
+    }
+
+    /// Speculatively loads, in background, what the user is likely to open
+    /// next: the next page of [App::latest_patchsets], once the selection gets
+    /// close to the end of the current one, and the highlighted patchset, once
+    /// the cursor rests on it for [prefetch::PREFETCH_DELAY].
+    pub fn prefetch(&mut self) {
+        if !self.prefetcher.is_enabled() {
+            return;
+        }
+
+        if self.current_screen == CurrentScreen::LatestPatchsets {
+            let latest_patchsets = self.latest_patchsets.as_ref().unwrap();
+            if latest_patchsets.should_prefetch_next_page() {
+                let fetch = latest_patchsets.fetch_next_page_job();
+                self.spawn_latest_patchsets_fetch(fetch);
+            }
+        }
+
+        let Some(highlighted_patch) = self.highlighted_patchset() else {
+            self.prefetcher.clear_highlight();
+            return;
+        };
+        let now = Instant::now();
+        self.prefetcher
+            .highlight(&highlighted_patch.message_id().href, now);
+        if self.prefetcher.due(now).is_some() {
+            let description = format!("Prefetching patchset {}", highlighted_patch.title());
+            self.spawn_patchset_load(description, highlighted_patch);
+        }
+    }
+
+    /// Whether there is work happening (or about to happen) in background, so
+    /// the UI should keep refreshing without waiting for a key press.
+    pub fn has_pending_work(&self) -> bool {
+        self.jobs.has_running_jobs() || self.prefetcher.is_pending()
+    }
+
+    /// Patchset currently highlighted in [App::bookmarked_patchsets] or
+    /// [App::latest_patchsets], depending on the value of
+    /// [App::current_screen], if it's loaded.
+    fn highlighted_patchset(&self) -> Option<Patch> {
+        match &self.current_screen {
+            CurrentScreen::BookmarkedPatchsets => self.bookmarked_patchsets.get_selected_patchset(),
+            CurrentScreen::LatestPatchsets => self
+                .latest_patchsets
+                .as_ref()
+                .unwrap()
+                .get_selected_patchset(),
+            _ => None,
+        }
     }
 
     /// Initializes field [App::details_actions], from currently selected
     /// patchset in [App::bookmarked_patchsets] or [App::latest_patchsets],
-    /// depending on the value of [App::current_screen].
+    /// depending on the value of [App::current_screen]. Does nothing if no
+    /// patchset is selected, like while the current page is being fetched.
+    ///
+    /// Unless it was prefetched, the patchset is downloaded and rendered in
+    /// background. Once done, the app moves to the details screen, unless the
+    /// user left the screen the patchset was selected from.
     pub fn init_details_actions(&mut self) -> color_eyre::Result<()> {
-        let representative_patch: Patch;
-        let mut is_patchset_bookmarked = true;
+        let representative_patch = match &self.current_screen {
+            CurrentScreen::BookmarkedPatchsets => self.bookmarked_patchsets.get_selected_patchset(),
+            CurrentScreen::LatestPatchsets => self
+                .latest_patchsets
+                .as_ref()
+                .unwrap()
+                .get_selected_patchset(),
+            screen => bail!(format!("Invalid screen passed as argument {screen:?}")),
+        };
+        let Some(representative_patch) = representative_patch else {
+            return Ok(());
+        };
 
-        match &self.current_screen {
-            CurrentScreen::BookmarkedPatchsets => {
-                representative_patch = self.bookmarked_patchsets.get_selected_patchset();
-            }
-            CurrentScreen::LatestPatchsets => {
-                representative_patch = self
-                    .latest_patchsets
-                    .as_ref()
-                    .unwrap()
-                    .get_selected_patchset();
-                if !self
-                    .bookmarked_patchsets
-                    .bookmarked_patchsets
-                    .contains(&representative_patch)
-                {
-                    is_patchset_bookmarked = false;
+        if self.seen_patchsets.mark_seen(&representative_patch) {
+            self.save_seen_patchsets();
+        }
+        self.load_details_actions(representative_patch);
+        Ok(())
+    }
+
+    /// Marks every patchset listed in [App::bookmarked_patchsets] or
+    /// [App::latest_patchsets], depending on the value of
+    /// [App::current_screen], as seen. Only the fetched patchsets of the
+    /// latest ones are listed.
+    pub fn mark_listed_patchsets_seen(&mut self) {
+        let is_changed = match &self.current_screen {
+            CurrentScreen::BookmarkedPatchsets => self
+                .seen_patchsets
+                .mark_all_seen(self.bookmarked_patchsets.listed_patchsets()),
+            CurrentScreen::LatestPatchsets => match &self.latest_patchsets {
+                Some(latest_patchsets) => self
+                    .seen_patchsets
+                    .mark_all_seen(latest_patchsets.listed_patchsets()),
+                None => false,
+            },
+            _ => false,
+        };
+        if is_changed {
+            self.save_seen_patchsets();
+        }
+    }
+
+    /// Selects the next patchset not seen yet listed in
+    /// [App::bookmarked_patchsets] or [App::latest_patchsets], depending on
+    /// the value of [App::current_screen], telling in a popup if there is
+    /// none. Only the fetched patchsets of the latest ones are looked at.
+    pub fn select_next_unseen_patchset(&mut self) {
+        let is_selected = match &self.current_screen {
+            CurrentScreen::BookmarkedPatchsets => self
+                .bookmarked_patchsets
+                .select_next_unseen_patchset(&self.seen_patchsets),
+            CurrentScreen::LatestPatchsets => match self.latest_patchsets.as_mut() {
+                Some(latest_patchsets) => {
+                    latest_patchsets.select_next_unseen_patchset(&self.seen_patchsets)
+                }
+                None => false,
+            },
+            _ => false,
+        };
+        if !is_selected {
+            self.popup = Some(Box::new(
+                HelpPopUpBuilder::new()
+                    .title("Unread patchsets")
+                    .description("There are no unread patchsets after the selected one")
+                    .build(),
+            ));
+        }
+    }
+
+    fn save_seen_patchsets(&self) {
+        if let Err(io_error) =
+            seen::save_seen_patchsets(&self.seen_patchsets, &self.config.seen_patchsets_path())
+        {
+            Logger::error(format!("Failed to save seen patchsets: {io_error}"));
+        }
+    }
+
+    /// Opens, in the details screen, the version of the series in
+    /// [App::details_actions] that comes right after (if `newer` is set) or
+    /// right before the one being shown, if it's known. Like
+    /// [App::init_details_actions], the version is loaded in background.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `details_actions` is `None`.
+    pub fn jump_to_series_version(&mut self, newer: bool) {
+        let details_actions = self.details_actions.as_ref().unwrap();
+        let Some(series_history) = &details_actions.series_history else {
+            return;
+        };
+        let current_patch = &details_actions.representative_patch;
+        let version = if newer {
+            series_history.next(current_patch)
+        } else {
+            series_history.previous(current_patch)
+        };
+
+        if let Some(version) = version.cloned() {
+            self.load_details_actions(version);
+        }
+    }
+
+    /// Loads `representative_patch` to be shown in the details screen, unless
+    /// it was prefetched.
+    fn load_details_actions(&mut self, representative_patch: Patch) {
+        self.cancel_details_actions_job();
+
+        let key = &representative_patch.message_id().href;
+        if let Some(loaded_patchset) = self.prefetcher.get(key).cloned() {
+            self.open_details_actions(representative_patch, loaded_patchset);
+        } else if let Some(prefetch_job) = self.prefetcher.job_for(key) {
+            self.details_actions_job = Some(prefetch_job);
+        } else {
+            let description = format!("Loading patchset {}", representative_patch.title());
+            let details_actions_job = self.spawn_patchset_load(description, representative_patch);
+            self.details_actions_job = Some(details_actions_job);
+        }
+    }
+
+    /// Spawns a job that downloads and renders `representative_patch`, to be
+    /// handled by [App::finish_patchset_load].
+    fn spawn_patchset_load(&mut self, description: String, representative_patch: Patch) -> JobId {
+        let key = representative_patch.message_id().href.clone();
+        let instance = self.instance_of(&representative_patch);
+        let lore_api_client = self.lore_api_client.for_instance(&instance);
+        let local_source = self.local_source_of_patch(&instance, &representative_patch);
+        let patchsets_cache_dir = self.config.instance_patchsets_cache_dir(&instance);
+        // b4 only knows how to reach lore.kernel.org and would try to reach it
+        // even when offline or when the list is read locally
+        let use_b4 = instance.is_default() && !self.is_offline && local_source.is_none();
+        let cover_renderer = *self.config.cover_renderer();
+        let patch_renderer = *self.config.patch_renderer();
+
+        let job = self.jobs.spawn(description, move |context| {
+            let job = context.id();
+            let loaded_patchset = match &local_source {
+                Some(local_source) => load_patchset(
+                    context,
+                    local_source,
+                    &patchsets_cache_dir,
+                    use_b4,
+                    &representative_patch,
+                    &cover_renderer,
+                    &patch_renderer,
+                ),
+                None => load_patchset(
+                    context,
+                    &lore_api_client,
+                    &patchsets_cache_dir,
+                    use_b4,
+                    &representative_patch,
+                    &cover_renderer,
+                    &patch_renderer,
+                ),
+            };
+            let completion: JobCompletion = Box::new(move |app: &mut App| {
+                app.finish_patchset_load(job, representative_patch, loaded_patchset)
+            });
+            Ok(completion)
+        });
+
+        // Only one patchset is loaded at a time, unless the user is waiting
+        // for the previous one
+        if let Some(previous_job) = self.prefetcher.start(&key, job) {
+            if self.details_actions_job != Some(previous_job) {
+                self.jobs.cancel(previous_job);
+            }
+        }
+
+        job
+    }
+
+    fn finish_patchset_load(
+        &mut self,
+        job: JobId,
+        representative_patch: Patch,
+        loaded_patchset: color_eyre::Result<LoadedPatchset>,
+    ) -> color_eyre::Result<()> {
+        let key = &representative_patch.message_id().href;
+        let is_awaited = self.details_actions_job == Some(job);
+        if is_awaited {
+            self.details_actions_job = None;
+        }
+
+        let loaded_patchset = match loaded_patchset {
+            Ok(loaded_patchset) => loaded_patchset,
+            Err(error) => {
+                self.prefetcher.fail(job, key);
+                if is_unreachable(&error) {
+                    if !self.is_offline {
+                        Logger::warn(format!("Going offline: {error:#}"));
+                        self.set_offline(true);
+                    }
+                    if is_awaited {
+                        self.show_offline_notice("This patchset wasn't downloaded before");
+                    }
+                    return Ok(());
+                }
+                if is_awaited {
+                    self.show_error(&error);
+                    return Ok(());
                 }
+                Logger::warn(format!("Failed to prefetch patchset: {error}"));
+                return Ok(());
             }
-            screen => bail!(format!("Invalid screen passed as argument {screen:?}")),
         };
+        self.prefetcher.finish(job, key, loaded_patchset.clone());
+        if let Some(trailers) = &loaded_patchset.trailers {
+            self.patchset_trailers.insert(key.clone(), trailers.clone());
+        }
+        self.patchset_parts
+            .insert(key.clone(), loaded_patchset.series_parts.clone());
+
+        // Other versions of a series are opened from the details screen
+        let is_selection_screen = matches!(
+            self.current_screen,
+            CurrentScreen::BookmarkedPatchsets
+                | CurrentScreen::LatestPatchsets
+                | CurrentScreen::PatchsetDetails
+        );
+        if is_awaited && is_selection_screen {
+            self.open_details_actions(representative_patch, loaded_patchset);
+        }
+        Ok(())
+    }
+
+    /// Shows `loaded_patchset` in the details screen, coming from the current
+    /// screen. When coming from another version of the same series, the
+    /// screen to return to and the versions of the series are kept, otherwise
+    /// the versions are looked up in background. If parts of the series are
+    /// missing, a warning is shown before it's reviewed.
+    fn open_details_actions(
+        &mut self,
+        representative_patch: Patch,
+        loaded_patchset: LoadedPatchset,
+    ) {
+        let is_patchset_bookmarked = self
+            .bookmarked_patchsets
+            .is_bookmarked(&representative_patch);
+        let lore_api_client = self
+            .lore_api_client
+            .for_instance(&self.instance_of(&representative_patch));
+
+        let previous_details_actions = self
+            .details_actions
+            .take()
+            .filter(|_| self.current_screen == CurrentScreen::PatchsetDetails);
+        let last_screen = match &previous_details_actions {
+            Some(previous_details_actions) => previous_details_actions.last_screen.clone(),
+            None => self.current_screen.clone(),
+        };
+        let series_history = previous_details_actions
+            .and_then(|previous_details_actions| previous_details_actions.series_history)
+            .filter(|series_history| series_history.contains(&representative_patch));
+        if series_history.is_none() {
+            self.spawn_series_history_lookup(
+                representative_patch.clone(),
+                loaded_patchset.series_metadata.change_id().clone(),
+            );
+        }
+
+        self.details_actions = Some(DetailsActions {
+            representative_patch,
+            raw_patches: loaded_patchset.raw_patches,
+            patches_preview: loaded_patchset.patches_preview,
+            preview_index: 0,
+            preview_scroll_offset: 0,
+            preview_pan: 0,
+            preview_fullscreen: false,
+            patchset_actions: HashMap::from([
+                (PatchsetAction::Bookmark, is_patchset_bookmarked),
+                (PatchsetAction::ReplyWithReviewedBy, false),
+                (PatchsetAction::Apply, false),
+            ]),
+            last_screen,
+            lore_api_client,
+            series_history,
+            range_diff: None,
+            trailers: loaded_patchset.trailers,
+            series_parts: loaded_patchset.series_parts.clone(),
+            series_metadata: loaded_patchset.series_metadata,
+        });
+        self.set_current_screen(CurrentScreen::PatchsetDetails);
+
+        if let Some(problems) = loaded_patchset.series_parts.problems() {
+            self.popup = Some(Box::new(
+                HelpPopUpBuilder::new()
+                    .title("Incomplete series")
+                    .description(&format!(
+                        "This patchset doesn't have all of its parts ({problems}), so reviewing it may miss changes"
+                    ))
+                    .build(),
+            ));
+        }
+    }
+
+    /// Parts of the series of the patch `message_id`, as downloaded if it was
+    /// loaded or as seen in the feed of [App::latest_patchsets].
+    pub fn series_parts_of(&self, message_id: &str) -> Option<&SeriesParts> {
+        self.patchset_parts.get(message_id).or_else(|| {
+            self.latest_patchsets
+                .as_ref()
+                .and_then(|latest_patchsets| latest_patchsets.series_parts(message_id))
+        })
+    }
+
+    /// Toggles, in the preview of [App::details_actions], the range-diff
+    /// between the patchset and the version of the series right before it
+    /// (or right after it, for the first version). The other version is
+    /// downloaded and compared in background.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `details_actions` is `None`.
+    pub fn toggle_range_diff(&mut self) {
+        let details_actions = self.details_actions.as_mut().unwrap();
+        if details_actions.range_diff.is_some() {
+            details_actions.set_range_diff(None);
+            return;
+        }
+
+        let representative_patch = details_actions.representative_patch.clone();
+        let other_version = details_actions
+            .series_history
+            .as_ref()
+            .and_then(|series_history| {
+                series_history
+                    .previous(&representative_patch)
+                    .or_else(|| series_history.next(&representative_patch))
+            })
+            .cloned();
+        let Some(other_version) = other_version else {
+            self.popup = Some(Box::new(
+                HelpPopUpBuilder::new()
+                    .title("Range-diff")
+                    .description("No other version of this series is known")
+                    .build(),
+            ));
+            return;
+        };
+        let raw_patches = details_actions.raw_patches.clone();
+        let other_raw_patches = self
+            .prefetcher
+            .get(&other_version.message_id().href)
+            .map(|loaded_patchset| loaded_patchset.raw_patches.clone());
+
+        let instance = self.instance_of(&other_version);
+        let lore_api_client = self.lore_api_client.for_instance(&instance);
+        let local_source = self.local_source_of_patch(&instance, &other_version);
+        let patchsets_cache_dir = self.config.instance_patchsets_cache_dir(&instance);
+        let use_b4 = instance.is_default() && !self.is_offline && local_source.is_none();
+        let patch_renderer = *self.config.patch_renderer();
 
-        let patchset_path: String = match log_on_error!(lore_session::download_patchset(
-            self.config.patchsets_cache_dir(),
-            &representative_patch,
-        )) {
-            Ok(result) => result,
-            Err(io_error) => bail!("{io_error}"),
+        let description = format!(
+            "Comparing v{} and v{} of {}",
+            other_version.version(),
+            representative_patch.version(),
+            representative_patch.title()
+        );
+        self.jobs.spawn(description, move |context| {
+            let range_diff = compare_versions(
+                context,
+                local_source.as_ref(),
+                &lore_api_client,
+                &patchsets_cache_dir,
+                use_b4,
+                (&representative_patch, raw_patches),
+                (&other_version, other_raw_patches),
+                &patch_renderer,
+            );
+            let completion: JobCompletion = Box::new(move |app: &mut App| {
+                app.finish_range_diff(representative_patch, range_diff);
+                Ok(())
+            });
+            Ok(completion)
+        });
+    }
+
+    /// Previews `range_diff` in [App::details_actions], if it still shows
+    /// `representative_patch`. Failures are reported in a popup.
+    fn finish_range_diff(
+        &mut self,
+        representative_patch: Patch,
+        range_diff: color_eyre::Result<RangeDiffPreview>,
+    ) {
+        let range_diff = match range_diff {
+            Ok(range_diff) => range_diff,
+            Err(error) if is_unreachable(&error) => {
+                if !self.is_offline {
+                    Logger::warn(format!("Going offline: {error:#}"));
+                    self.set_offline(true);
+                }
+                self.show_offline_notice("The other version wasn't downloaded before");
+                return;
+            }
+            Err(error) => {
+                Logger::error(format!("Failed to compare versions: {error:#}"));
+                self.popup = Some(Box::new(
+                    HelpPopUpBuilder::new()
+                        .title("Range-diff")
+                        .description(&format!("Failed to compare versions: {error}"))
+                        .build(),
+                ));
+                return;
+            }
         };
 
-        match log_on_error!(lore_session::split_patchset(&patchset_path)) {
-            Ok(raw_patches) => {
-                let mut patches_preview: Vec<Text> = Vec::new();
-                for raw_patch in &raw_patches {
-                    let raw_patch = raw_patch.replace('\t', "        ");
-
-                    let (raw_cover, raw_patch) = lore_session::split_cover(&raw_patch);
-
-                    let rendered_cover = match render_cover(raw_cover, self.config.cover_renderer())
-                    {
-                        Ok(render) => render,
-                        Err(_) => {
-                            Logger::error("Failed to render cover preview with external program");
-                            raw_cover.to_string()
-                        }
-                    };
-
-                    let rendered_patch =
-                        match render_patch_preview(raw_patch, self.config.patch_renderer()) {
-                            Ok(render) => render,
-                            Err(_) => {
-                                Logger::error(
-                                    "Failed to render patch preview with external program",
-                                );
-                                raw_patch.to_string()
-                            }
-                        };
-
-                    patches_preview
-                        .push(format!("{}---\n{}", rendered_cover, rendered_patch).into_text()?);
+        if let Some(details_actions) = self.details_actions.as_mut() {
+            if details_actions.representative_patch.message_id()
+                == representative_patch.message_id()
+            {
+                details_actions.set_range_diff(Some(range_diff));
+            }
+        }
+    }
+
+    /// Looks up, in background, the prerequisites of the series in
+    /// [App::details_actions], in the order they are listed, and opens the
+    /// first one found in the details screen, keeping the screen to return
+    /// to. Leaving the details screen cancels the lookup.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `details_actions` is `None`.
+    pub fn open_prerequisite(&mut self) {
+        let details_actions = self.details_actions.as_ref().unwrap();
+        let prerequisites = details_actions.series_metadata.prerequisites().clone();
+        if prerequisites.is_empty() {
+            self.popup = Some(Box::new(
+                HelpPopUpBuilder::new()
+                    .title("Prerequisites")
+                    .description("This series doesn't list any prerequisite")
+                    .build(),
+            ));
+            return;
+        }
+
+        let representative_patch = details_actions.representative_patch.clone();
+        let target_list = representative_patch.message_id().list().to_string();
+        let instance = self.instance_of(&representative_patch);
+        let lore_api_client = self.lore_api_client.for_instance(&instance);
+        let local_source = self.local_source_of_list(&instance, &target_list);
+
+        self.cancel_details_actions_job();
+        let description = format!(
+            "Looking up prerequisites of {}",
+            representative_patch.title()
+        );
+        let details_actions_job = self.jobs.spawn(description, move |context| {
+            let prerequisite_job = context.id();
+            let mut prerequisite = Ok(None);
+            for listed_prerequisite in &prerequisites {
+                // The completion of cancelled jobs is ignored
+                if context.is_cancelled() {
+                    break;
                 }
-                self.details_actions = Some(DetailsActions {
-                    representative_patch,
-                    raw_patches,
-                    patches_preview,
-                    preview_index: 0,
-                    preview_scroll_offset: 0,
-                    preview_pan: 0,
-                    preview_fullscreen: false,
-                    patchset_actions: HashMap::from([
-                        (PatchsetAction::Bookmark, is_patchset_bookmarked),
-                        (PatchsetAction::ReplyWithReviewedBy, false),
-                    ]),
-                    last_screen: self.current_screen.clone(),
-                    lore_api_client: self.lore_api_client.clone(),
-                });
+                prerequisite = match &local_source {
+                    Some(local_source) => {
+                        series::fetch_prerequisite(local_source, &target_list, listed_prerequisite)
+                    }
+                    None => series::fetch_prerequisite(
+                        &lore_api_client,
+                        &target_list,
+                        listed_prerequisite,
+                    ),
+                };
+                if !matches!(prerequisite, Ok(None)) {
+                    break;
+                }
+            }
+            let prerequisite = prerequisite.map_err(|lore_session_error| {
+                color_eyre::Report::new(lore_session_error)
+                    .wrap_err("Failed to look up prerequisites")
+            });
+            let completion: JobCompletion = Box::new(move |app: &mut App| {
+                app.finish_prerequisite_lookup(prerequisite_job, prerequisite);
                 Ok(())
+            });
+            Ok(completion)
+        });
+        self.details_actions_job = Some(details_actions_job);
+    }
+
+    /// Opens `prerequisite` in the details screen, if the lookup of
+    /// `prerequisite_job` wasn't cancelled. Failures are reported in a popup.
+    fn finish_prerequisite_lookup(
+        &mut self,
+        prerequisite_job: JobId,
+        prerequisite: color_eyre::Result<Option<Patch>>,
+    ) {
+        if self.details_actions_job != Some(prerequisite_job) {
+            return;
+        }
+        self.details_actions_job = None;
+
+        match prerequisite {
+            Ok(Some(prerequisite)) => self.load_details_actions(prerequisite),
+            Ok(None) => {
+                self.popup = Some(Box::new(
+                    HelpPopUpBuilder::new()
+                        .title("Prerequisites")
+                        .description(
+                            "None of the prerequisites of this series were found in its list",
+                        )
+                        .build(),
+                ));
             }
-            Err(message) => bail!(message),
+            Err(error) if is_unreachable(&error) => {
+                if !self.is_offline {
+                    Logger::warn(format!("Going offline: {error:#}"));
+                    self.set_offline(true);
+                }
+                self.show_offline_notice("The prerequisites weren't looked up before");
+            }
+            Err(error) => {
+                Logger::error(format!("{error:#}"));
+                self.popup = Some(Box::new(
+                    HelpPopUpBuilder::new()
+                        .title("Prerequisites")
+                        .description(&format!("{error:#}"))
+                        .build(),
+                ));
+            }
+        }
+    }
+
+    /// Spawns a job that looks up the other versions of the series of
+    /// `representative_patch`, to be shown in [App::details_actions].
+    fn spawn_series_history_lookup(
+        &mut self,
+        representative_patch: Patch,
+        change_id: Option<String>,
+    ) {
+        let instance = self.instance_of(&representative_patch);
+        let lore_api_client = self.lore_api_client.for_instance(&instance);
+        let local_source =
+            self.local_source_of_list(&instance, representative_patch.message_id().list());
+
+        let description = format!("Looking up versions of {}", representative_patch.title());
+        self.jobs.spawn(description, move |_| {
+            let series_history = match &local_source {
+                Some(local_source) => series::fetch_series_history(
+                    local_source,
+                    &representative_patch,
+                    change_id.as_deref(),
+                ),
+                None => series::fetch_series_history(
+                    &lore_api_client,
+                    &representative_patch,
+                    change_id.as_deref(),
+                ),
+            };
+            let completion: JobCompletion = Box::new(move |app: &mut App| {
+                app.finish_series_history_lookup(representative_patch, series_history);
+                Ok(())
+            });
+            Ok(completion)
+        });
+    }
+
+    /// Sets the versions of the series in [App::details_actions], if it still
+    /// shows `representative_patch`. If the lookup failed, the series is taken
+    /// as having a single version.
+    fn finish_series_history_lookup(
+        &mut self,
+        representative_patch: Patch,
+        series_history: Result<SeriesHistory, LoreSessionError>,
+    ) {
+        let series_history = series_history.unwrap_or_else(|error| {
+            Logger::warn(format!(
+                "Failed to look up the versions of the series: {error}"
+            ));
+            SeriesHistory::new(&representative_patch, [])
+        });
+
+        if let Some(details_actions) = self.details_actions.as_mut() {
+            if details_actions.representative_patch.message_id()
+                == representative_patch.message_id()
+            {
+                details_actions.series_history = Some(series_history);
+            }
+        }
+    }
+
+    /// Cancels the loading of a patchset started by
+    /// [App::init_details_actions], if any.
+    pub fn cancel_details_actions_job(&mut self) {
+        if let Some(details_actions_job) = self.details_actions_job.take() {
+            self.jobs.cancel(details_actions_job);
+            self.prefetcher.forget_job(details_actions_job);
         }
     }
 
@@ -218,6 +1422,51 @@ impl App {
         self.details_actions = None;
     }
 
+    /// Initializes field [App::thread_view], fetching the whole thread of the
+    /// patchset currently in [App::details_actions].
+    ///
+    /// Returns `false`, going offline, if the thread couldn't be fetched
+    /// because its instance is unreachable.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `details_actions` is `None`.
+    pub fn init_thread_view(&mut self) -> color_eyre::Result<bool> {
+        let representative_patch = &self.details_actions.as_ref().unwrap().representative_patch;
+        let message_id = representative_patch.message_id().id().to_string();
+        let list = representative_patch.message_id().list();
+        let instance = self.instance_of(representative_patch);
+        let thread_mbox_request = match self.local_source_of_patch(&instance, representative_patch)
+        {
+            Some(local_source) => local_source.request_thread_mbox(list, &message_id),
+            None => self
+                .lore_api_client
+                .for_instance(&instance)
+                .request_thread_mbox(list, &message_id),
+        };
+
+        let thread_mbox = match log_on_error!(thread_mbox_request) {
+            Ok(thread_mbox) => thread_mbox,
+            Err(client_error) if client_error.is_unreachable() => {
+                if !self.is_offline {
+                    Logger::warn(format!("Going offline: {client_error}"));
+                    self.set_offline(true);
+                }
+                self.show_offline_notice("Threads can't be fetched while offline");
+                return Ok(false);
+            }
+            Err(client_error) => bail!("Failed to request thread\n{client_error:#?}"),
+        };
+
+        self.thread_view = Some(ThreadView::new(Thread::from_mbox(&thread_mbox)));
+        Ok(true)
+    }
+
+    /// Sets field [App::thread_view] to `None`.
+    pub fn reset_thread_view(&mut self) {
+        self.thread_view = None;
+    }
+
     /// Determines and consolidates all actions (if any) to take for the current
     /// patchset stored in `details_actions`.
     ///
@@ -243,9 +1492,23 @@ impl App {
             self.config.bookmarked_patchsets_path(),
         )?;
 
+        if *actions.get(&PatchsetAction::Apply).unwrap() {
+            details_actions.apply_patchset()?;
+
+            self.details_actions
+                .as_mut()
+                .unwrap()
+                .toggle_action(PatchsetAction::Apply);
+        }
+
+        let details_actions = self.details_actions.as_ref().unwrap();
+        let representative_patch = &details_actions.representative_patch;
+        let actions = &details_actions.patchset_actions;
         if *actions.get(&PatchsetAction::ReplyWithReviewedBy).unwrap() {
-            let successful_indexes = details_actions
-                .reply_patchset_with_reviewed_by("all", self.config.git_send_email_options())?;
+            let successful_indexes = details_actions.reply_patchset_with_reviewed_by(
+                representative_patch.message_id().list(),
+                self.config.git_send_email_options(),
+            )?;
 
             if !successful_indexes.is_empty() {
                 self.reviewed_patchsets.insert(
@@ -278,6 +1541,26 @@ impl App {
         self.edit_config = None;
     }
 
+    /// Initializes field [App::search], targeting the list currently typed in
+    /// [App::mailing_list_selection], if any.
+    pub fn init_search(&mut self) {
+        let mail_list = &self.mailing_list_selection;
+        // The typed list is searched in the instance of the highlighted one
+        let instance = match mail_list
+            .possible_mailing_lists
+            .get(mail_list.highlighted_list_index)
+        {
+            Some(mailing_list) if !mail_list.target_list.is_empty() => mailing_list.instance(),
+            _ => self.config.default_instance().name(),
+        };
+        self.search = Some(Search::new(&mail_list.target_list, instance));
+    }
+
+    /// Sets field [App::search] to `None`.
+    pub fn reset_search(&mut self) {
+        self.search = None;
+    }
+
     /// Based on the edited config values from [App::edit_config], commit them
     /// to field [App::config].
     pub fn consolidate_edit_config(&mut self) {
@@ -304,7 +1587,119 @@ impl App {
             if let Ok(max_log_age) = edit_config.max_log_age() {
                 self.config.set_max_log_age(max_log_age)
             }
+            if let Ok(prefetch_budget) = edit_config.prefetch_budget() {
+                self.config.set_prefetch_budget(prefetch_budget)
+            }
+            if let Ok(http_cache_ttl) = edit_config.http_cache_ttl() {
+                self.config.set_http_cache_ttl(http_cache_ttl)
+            }
+            if let Ok(max_retries) = edit_config.max_retries() {
+                self.config.set_max_retries(max_retries)
+            }
+            if let Ok(max_requests_per_second) = edit_config.max_requests_per_second() {
+                self.config
+                    .set_max_requests_per_second(max_requests_per_second)
+            }
+            if let Ok(instances) = edit_config.instances() {
+                self.config.set_instances(instances);
+            }
+            if let Ok(proxy) = edit_config.proxy() {
+                self.config.set_proxy(proxy);
+            }
+            if let Ok(ca_certs_path) = edit_config.ca_certs_path() {
+                self.config.set_ca_certs_path(ca_certs_path);
+            }
+            if let Ok(client_cert_path) = edit_config.client_cert_path() {
+                self.config.set_client_cert_path(client_cert_path);
+            }
+            if let Ok(client_key_path) = edit_config.client_key_path() {
+                self.config.set_client_key_path(client_key_path);
+            }
+            if let Ok(connect_timeout) = edit_config.connect_timeout() {
+                self.config.set_connect_timeout(connect_timeout);
+            }
+            if let Ok(request_timeout) = edit_config.request_timeout() {
+                self.config.set_request_timeout(request_timeout);
+            }
+            if let Ok(local_inboxes) = edit_config.local_inboxes() {
+                self.config.set_local_inboxes(local_inboxes);
+            }
+            if let Ok(use_lei) = edit_config.use_lei() {
+                self.config.set_use_lei(use_lei);
+            }
+            if let Ok(timestamp_format) = edit_config.timestamp_format() {
+                self.config.set_timestamp_format(timestamp_format);
+            }
+            if let Ok(open_last_list) = edit_config.open_last_list() {
+                self.config.set_open_last_list(open_last_list);
+            }
+        }
+
+        // Lists of instances that are no longer configured are left out
+        let config = &self.config;
+        self.mailing_list_selection
+            .mailing_lists
+            .retain(|mailing_list| config.instance(mailing_list.instance()).is_some());
+
+        // The network, cache, retry, rate limit and instance configurations
+        // may have changed
+        self.lore_api_client = build_lore_api_client(&self.config).with_offline(self.is_offline);
+        self.mailing_list_selection.instance_clients =
+            build_instance_clients(&self.config, &self.lore_api_client);
+
+        // So may have the local lists
+        self.mailing_list_selection.local_inboxes = self.config.all_local_inboxes();
+        self.load_lei_searches();
+        self.mailing_list_selection.add_local_lists();
+        self.mailing_list_selection.clear_target_list();
+
+        // Prefetched patchsets may have been rendered with other renderers
+        self.prefetcher.clear();
+        self.prefetcher.set_budget(self.config.prefetch_budget());
+    }
+
+    /// The configured instance that `patch` is from, or the default one if
+    /// it's from none of them.
+    fn instance_of(&self, patch: &Patch) -> Instance {
+        find_instance(self.config.instances(), &patch.message_id().href)
+            .unwrap_or_else(|| self.config.default_instance())
+            .clone()
+    }
+
+    /// Where `list` of `instance` is read from locally, if anywhere: the
+    /// saved `lei` search of the pseudo list or the local inbox of the list.
+    /// Only lists of the default instance can be read locally.
+    fn local_source_of_list(&self, instance: &Instance, list: &str) -> Option<LocalSource> {
+        if instance != self.config.default_instance() {
+            return None;
+        }
+        if list.starts_with(LEI_LIST_PREFIX) {
+            return self
+                .mailing_list_selection
+                .lei_searches
+                .iter()
+                .find(|lei_search| lei_search.name() == list)
+                .cloned()
+                .map(LocalSource::LeiSearch);
         }
+        self.config.local_inbox(list).map(LocalSource::Inbox)
+    }
+
+    /// Where the thread of `patch` of `instance` is read from locally, if
+    /// anywhere: the one of its list (see [App::local_source_of_list]) or,
+    /// for the patches listed from a `lei` search, which are linked to the
+    /// `all` inbox of the instance, the search.
+    fn local_source_of_patch(&self, instance: &Instance, patch: &Patch) -> Option<LocalSource> {
+        let lei_search = self.latest_patchsets.as_ref().and_then(|latest_patchsets| {
+            let local_source = latest_patchsets.local_source().as_ref()?;
+            let is_listed = latest_patchsets
+                .lore_session()
+                .get_processed_patch(&patch.message_id().href)
+                .is_some();
+            (matches!(local_source, LocalSource::LeiSearch(_)) && is_listed)
+                .then(|| local_source.clone())
+        });
+        lei_search.or_else(|| self.local_source_of_list(instance, patch.message_id().list()))
     }
 
     /// Change the current active screen in [App::current_screen].
@@ -314,14 +1709,11 @@ impl App {
 
     /// Check if the external dependencies are installed
     ///
-    /// If soft dependencies are missing, the application can still run and
-    /// their absence will only be logged
-    pub fn check_external_deps(&self) -> bool {
-        let mut app_can_run = true;
-
+    /// Every dependency is soft, so the application can still run and their
+    /// absence will only be logged
+    pub fn check_external_deps(&self) {
         if !utils::binary_exists("b4") {
-            Logger::error("b4 is not installed, patchsets cannot be downloaded");
-            app_can_run = false;
+            Logger::warn("b4 is not installed, patchsets will be downloaded natively");
         }
 
         if !utils::binary_exists("git") {
@@ -350,7 +1742,263 @@ impl App {
             }
             _ => {}
         }
+    }
+}
+
+/// Builds the client for lore requests, sending them through the configured
+/// proxy and certificates, and caching, retrying and pacing them as
+/// configured. Retries and recoveries are logged.
+///
+/// If the network configurations are invalid, the error is logged and the
+/// default ones are used.
+fn build_lore_api_client(config: &Config) -> BlockingLoreAPIClient {
+    let lore_api_client = match http_agent::build_agent(&config.agent_options()) {
+        Ok(agent) => BlockingLoreAPIClient::new(agent),
+        Err(agent_error) => {
+            Logger::error(format!(
+                "Invalid network configurations, using the defaults: {agent_error}"
+            ));
+            BlockingLoreAPIClient::default()
+        }
+    };
+    let http_cache = HttpCache::new(
+        config.http_cache_dir(),
+        Duration::from_secs(config.http_cache_ttl()),
+    );
+    if let Err(io_error) = http_cache.remove_unknown_files() {
+        Logger::warn(format!("Failed to clean up the HTTP cache: {io_error}"));
+    }
+
+    lore_api_client
+        .with_http_cache(http_cache)
+        .with_retry_policy(RetryPolicy::new(config.max_retries()))
+        .with_rate_limiter(RateLimiter::new(config.max_requests_per_second()))
+        .with_observer(Arc::new(
+            |request_event: &RequestEvent| match request_event {
+                RequestEvent::Retrying {
+                    url,
+                    retry,
+                    delay,
+                    error,
+                } => Logger::warn(format!(
+                    "Request to {url} failed ({error}), retry {retry} in {delay:?}"
+                )),
+                RequestEvent::Recovered { url, retries } => Logger::info(format!(
+                    "Request to {url} succeeded after {retries} retries"
+                )),
+            },
+        ))
+}
+
+/// Whether `error` happened because an instance couldn't be reached.
+pub(crate) fn is_unreachable(error: &color_eyre::Report) -> bool {
+    error.chain().any(|cause| {
+        let client_error = match cause.downcast_ref::<LoreSessionError>() {
+            Some(LoreSessionError::FromLoreAPIClient(client_error)) => Some(client_error),
+            _ => cause.downcast_ref::<ClientError>(),
+        };
+        client_error.is_some_and(ClientError::is_unreachable)
+    })
+}
+
+/// Builds a client for each configured instance out of `lore_api_client`.
+fn build_instance_clients(
+    config: &Config,
+    lore_api_client: &BlockingLoreAPIClient,
+) -> Vec<(Instance, BlockingLoreAPIClient)> {
+    config
+        .instances()
+        .iter()
+        .map(|instance| (instance.clone(), lore_api_client.for_instance(instance)))
+        .collect()
+}
+
+/// Downloads `representative_patch` and renders each of its patches.
+fn load_patchset<T: ThreadRequest>(
+    context: &JobContext,
+    lore_api_client: &T,
+    patchsets_cache_dir: &str,
+    use_b4: bool,
+    representative_patch: &Patch,
+    cover_renderer: &CoverRenderer,
+    patch_renderer: &PatchRenderer,
+) -> color_eyre::Result<LoadedPatchset> {
+    context.report_progress("downloading");
+    let patchset_path = download_patchset(
+        lore_api_client,
+        patchsets_cache_dir,
+        use_b4,
+        representative_patch,
+    )?;
+
+    let raw_patches = match log_on_error!(lore_session::split_patchset(&patchset_path)) {
+        Ok(raw_patches) => raw_patches,
+        Err(message) => bail!(message),
+    };
+
+    let mut patches_preview: Vec<Text> = Vec::new();
+    for (index, raw_patch) in raw_patches.iter().enumerate() {
+        if context.is_cancelled() {
+            bail!("Loading patchset was cancelled");
+        }
+        context.report_progress(format!("rendering {}/{}", index + 1, raw_patches.len()));
+
+        let raw_patch = raw_patch.replace('\t', "        ");
+
+        let (raw_cover, raw_patch) = lore_session::split_cover(&raw_patch);
+
+        let rendered_cover = match render_cover(raw_cover, cover_renderer) {
+            Ok(render) => render,
+            Err(_) => {
+                Logger::error("Failed to render cover preview with external program");
+                raw_cover.to_string()
+            }
+        };
+
+        let rendered_patch = match render_patch_preview(raw_patch, patch_renderer) {
+            Ok(render) => render,
+            Err(_) => {
+                Logger::error("Failed to render patch preview with external program");
+                raw_patch.to_string()
+            }
+        };
+
+        patches_preview.push(format!("{}---\n{}", rendered_cover, rendered_patch).into_text()?);
+    }
+
+    let series_parts =
+        SeriesParts::from_raw_patches(representative_patch.total_in_series(), &raw_patches);
+    let series_metadata = SeriesMetadata::parse(&raw_patches);
 
-        app_can_run
+    // Unless the patchset was downloaded with `b4` or was already on disk,
+    // the thread was just fetched, so this is served from the HTTP cache,
+    // as long as its entries are kept fresh for a while
+    context.report_progress("harvesting trailers");
+    let trailers = match lore_api_client.request_thread_mbox(
+        representative_patch.message_id().list(),
+        representative_patch.message_id().id(),
+    ) {
+        Ok(thread_mbox) => Some(PatchsetTrailers::harvest(
+            &Thread::from_mbox(&thread_mbox),
+            representative_patch,
+        )),
+        Err(client_error) => {
+            Logger::warn(format!("Failed to harvest trailers: {client_error}"));
+            None
+        }
+    };
+
+    Ok(LoadedPatchset {
+        raw_patches,
+        patches_preview,
+        series_metadata,
+        trailers,
+        series_parts,
+    })
+}
+
+/// Compares the patchset of `current`, given with its raw patches, with the
+/// one of `other`, downloading it if its raw patches aren't given, and renders
+/// the pages of the range-diff from the older to the newer version.
+fn compare_versions(
+    context: &JobContext,
+    local_source: Option<&LocalSource>,
+    lore_api_client: &BlockingLoreAPIClient,
+    patchsets_cache_dir: &str,
+    use_b4: bool,
+    current: (&Patch, Vec<String>),
+    other: (&Patch, Option<Vec<String>>),
+    patch_renderer: &PatchRenderer,
+) -> color_eyre::Result<RangeDiffPreview> {
+    let (current_patch, raw_patches) = current;
+    let (other_patch, other_raw_patches) = other;
+
+    let other_raw_patches = match other_raw_patches {
+        Some(other_raw_patches) => other_raw_patches,
+        None => {
+            context.report_progress("downloading");
+            let patchset_path = match local_source {
+                Some(local_source) => {
+                    download_patchset(local_source, patchsets_cache_dir, use_b4, other_patch)?
+                }
+                None => {
+                    download_patchset(lore_api_client, patchsets_cache_dir, use_b4, other_patch)?
+                }
+            };
+            match log_on_error!(lore_session::split_patchset(&patchset_path)) {
+                Ok(other_raw_patches) => other_raw_patches,
+                Err(message) => bail!(message),
+            }
+        }
+    };
+
+    context.report_progress("comparing");
+    let ((old_patch, old_raw_patches), (new_patch, new_raw_patches)) =
+        if other_patch.version() < current_patch.version() {
+            (
+                (other_patch, other_raw_patches),
+                (current_patch, raw_patches),
+            )
+        } else {
+            (
+                (current_patch, raw_patches),
+                (other_patch, other_raw_patches),
+            )
+        };
+    let pages = range_diff::range_diff(
+        old_patch.version(),
+        &old_raw_patches,
+        new_patch.version(),
+        &new_raw_patches,
+    );
+
+    let mut rendered_pages = Vec::new();
+    for page in pages {
+        let rendered_page = match render_patch_preview(&page, patch_renderer) {
+            Ok(render) => render,
+            Err(_) => {
+                Logger::error("Failed to render range-diff with external program");
+                page
+            }
+        };
+        rendered_pages.push(rendered_page.into_text()?);
+    }
+
+    Ok(RangeDiffPreview {
+        old_version: old_patch.version(),
+        new_version: new_patch.version(),
+        pages: rendered_pages,
+    })
+}
+
+/// Downloads the patchset with `b4`, if it is installed and `use_b4` is set,
+/// falling back to the native downloader when `b4` isn't available or fails.
+fn download_patchset<T: ThreadRequest>(
+    lore_api_client: &T,
+    patchsets_cache_dir: &str,
+    use_b4: bool,
+    representative_patch: &Patch,
+) -> color_eyre::Result<String> {
+    if use_b4 && utils::binary_exists("b4") {
+        match lore_session::download_patchset_with_b4(patchsets_cache_dir, representative_patch) {
+            Ok(patchset_path) if Path::new(&patchset_path).exists() => {
+                return Ok(patchset_path);
+            }
+            Ok(_) => Logger::warn("b4 failed to download the patchset"),
+            Err(io_error) => Logger::warn(format!("Failed to run b4: {io_error}")),
+        }
+        Logger::info("Falling back to native patchset download");
+    }
+
+    match lore_session::download_patchset(
+        lore_api_client,
+        patchsets_cache_dir,
+        representative_patch,
+    ) {
+        Ok(patchset_path) => Ok(patchset_path),
+        Err(error) => {
+            Logger::error(format!("Failed to download patchset: {error}"));
+            Err(error.into())
+        }
     }
 }
@@ -1,10 +1,19 @@
 use derive_getters::Getters;
+use patch_hub::lore::{
+    http_agent::{self, AgentOptions},
+    instance::{self, Instance},
+    local_inbox::{self, LocalInbox, LocalInboxes},
+    request_policy,
+    timestamp::TimestampFormat,
+};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     env,
     fs::{self, File},
     io,
     path::Path,
+    time::Duration,
 };
 
 use super::{cover_renderer::CoverRenderer, patch_renderer::PatchRenderer};
@@ -33,6 +42,92 @@ pub struct Config {
     cover_renderer: CoverRenderer,
     /// Maximum age of a log file in days
     max_log_age: usize,
+    /// Maximum number of patchsets kept downloaded and rendered in advance.
+    /// Setting it to 0 disables prefetching
+    #[serde(default = "default_prefetch_budget")]
+    prefetch_budget: usize,
+    /// Seconds during which cached responses from lore are used without
+    /// checking if they changed
+    #[serde(default = "default_http_cache_ttl")]
+    http_cache_ttl: u64,
+    /// How many times failed requests to lore are retried
+    #[serde(default = "default_max_retries")]
+    max_retries: u32,
+    /// Maximum number of requests sent to lore per second. Setting it to 0
+    /// removes the limit
+    #[serde(default = "default_max_requests_per_second")]
+    max_requests_per_second: u32,
+    /// Public-inbox instances to follow. The first one is used for searches
+    /// and lists without a known instance
+    #[serde(default = "default_instances")]
+    instances: Vec<Instance>,
+    /// URL of the proxy for requests. If unset, the `ALL_PROXY`,
+    /// `HTTPS_PROXY` and `HTTP_PROXY` environment variables are used
+    #[serde(default)]
+    proxy: Option<String>,
+    /// PEM file with root certificates to trust in addition to the bundled
+    /// ones
+    #[serde(default)]
+    ca_certs_path: Option<String>,
+    /// PEM file with the client certificate chain for mutual TLS
+    #[serde(default)]
+    client_cert_path: Option<String>,
+    /// PEM file with the private key of the client certificate. If unset,
+    /// the key is read from the client certificate file
+    #[serde(default)]
+    client_key_path: Option<String>,
+    /// Seconds to wait for a connection to be established
+    #[serde(default = "default_connect_timeout")]
+    connect_timeout: u64,
+    /// Seconds a whole request may take
+    #[serde(default = "default_request_timeout")]
+    request_timeout: u64,
+    /// Lists read from local public-inbox v2 repositories instead of their
+    /// instances, mapped to the paths of the repositories
+    #[serde(default)]
+    local_inboxes: BTreeMap<String, String>,
+    /// Whether the searches saved in `lei` are offered as pseudo lists
+    #[serde(default)]
+    use_lei: bool,
+    /// Whether timestamps are shown in the local timezone or relative to now
+    #[serde(default)]
+    timestamp_format: TimestampFormat,
+    /// Whether the last used list is opened on startup
+    #[serde(default)]
+    open_last_list: bool,
+}
+
+fn default_prefetch_budget() -> usize {
+    5
+}
+
+fn default_http_cache_ttl() -> u64 {
+    300
+}
+
+fn default_max_retries() -> u32 {
+    request_policy::DEFAULT_MAX_RETRIES
+}
+
+fn default_max_requests_per_second() -> u32 {
+    request_policy::DEFAULT_MAX_REQUESTS_PER_SECOND
+}
+
+fn default_instances() -> Vec<Instance> {
+    vec![Instance::default()]
+}
+
+fn default_connect_timeout() -> u64 {
+    http_agent::DEFAULT_CONNECT_TIMEOUT.as_secs()
+}
+
+fn default_request_timeout() -> u64 {
+    http_agent::DEFAULT_REQUEST_TIMEOUT.as_secs()
+}
+
+/// Treats empty values, like an empty environment variable, as unset.
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
 }
 
 impl Config {
@@ -53,6 +148,21 @@ impl Config {
             cache_dir,
             data_dir,
             max_log_age: 30,
+            prefetch_budget: default_prefetch_budget(),
+            http_cache_ttl: default_http_cache_ttl(),
+            max_retries: default_max_retries(),
+            max_requests_per_second: default_max_requests_per_second(),
+            instances: default_instances(),
+            proxy: None,
+            ca_certs_path: None,
+            client_cert_path: None,
+            client_key_path: None,
+            connect_timeout: default_connect_timeout(),
+            request_timeout: default_request_timeout(),
+            local_inboxes: BTreeMap::new(),
+            use_lei: false,
+            timestamp_format: TimestampFormat::default(),
+            open_last_list: false,
         }
     }
 
@@ -103,6 +213,73 @@ impl Config {
         if let Ok(patch_renderer) = env::var("PATCH_HUB_PATCH_RENDERER") {
             self.patch_renderer = patch_renderer.into();
         };
+
+        if let Ok(prefetch_budget) = env::var("PATCH_HUB_PREFETCH_BUDGET") {
+            self.prefetch_budget = prefetch_budget.parse().unwrap();
+        };
+
+        if let Ok(http_cache_ttl) = env::var("PATCH_HUB_HTTP_CACHE_TTL") {
+            self.http_cache_ttl = http_cache_ttl.parse().unwrap();
+        };
+
+        if let Ok(max_retries) = env::var("PATCH_HUB_MAX_RETRIES") {
+            self.max_retries = max_retries.parse().unwrap();
+        };
+
+        if let Ok(max_requests_per_second) = env::var("PATCH_HUB_MAX_REQUESTS_PER_SECOND") {
+            self.max_requests_per_second = max_requests_per_second.parse().unwrap();
+        };
+
+        if let Ok(instances) = env::var("PATCH_HUB_INSTANCES") {
+            self.instances = instance::parse_instances(&instances)
+                .expect("PATCH_HUB_INSTANCES should be in the format name=url,name=url");
+        };
+
+        if let Ok(proxy) = env::var("PATCH_HUB_PROXY") {
+            self.proxy = non_empty(proxy);
+        };
+
+        if let Ok(ca_certs_path) = env::var("PATCH_HUB_CA_CERTS_PATH") {
+            self.ca_certs_path = non_empty(ca_certs_path);
+        };
+
+        if let Ok(client_cert_path) = env::var("PATCH_HUB_CLIENT_CERT_PATH") {
+            self.client_cert_path = non_empty(client_cert_path);
+        };
+
+        if let Ok(client_key_path) = env::var("PATCH_HUB_CLIENT_KEY_PATH") {
+            self.client_key_path = non_empty(client_key_path);
+        };
+
+        if let Ok(connect_timeout) = env::var("PATCH_HUB_CONNECT_TIMEOUT") {
+            self.connect_timeout = connect_timeout.parse().unwrap();
+        };
+
+        if let Ok(request_timeout) = env::var("PATCH_HUB_REQUEST_TIMEOUT") {
+            self.request_timeout = request_timeout.parse().unwrap();
+        };
+
+        if let Ok(local_inboxes) = env::var("PATCH_HUB_LOCAL_INBOXES") {
+            self.local_inboxes = match non_empty(local_inboxes) {
+                Some(local_inboxes) => local_inbox::parse_local_inboxes(&local_inboxes)
+                    .expect("PATCH_HUB_LOCAL_INBOXES should be in the format list=path,list=path"),
+                None => BTreeMap::new(),
+            };
+        };
+
+        if let Ok(use_lei) = env::var("PATCH_HUB_USE_LEI") {
+            self.use_lei = use_lei.parse().unwrap();
+        };
+
+        if let Ok(timestamp_format) = env::var("PATCH_HUB_TIMESTAMP_FORMAT") {
+            self.timestamp_format = timestamp_format
+                .parse()
+                .expect("PATCH_HUB_TIMESTAMP_FORMAT should be either absolute or relative");
+        };
+
+        if let Ok(open_last_list) = env::var("PATCH_HUB_OPEN_LAST_LIST") {
+            self.open_last_list = open_last_list.parse().unwrap();
+        };
     }
 
     pub fn build() -> Self {
@@ -115,6 +292,10 @@ impl Config {
 
         config.override_with_env_vars();
 
+        if config.instances.is_empty() {
+            config.instances = default_instances();
+        }
+
         config
     }
 
@@ -155,6 +336,174 @@ impl Config {
         self.max_log_age = max_log_age;
     }
 
+    pub fn set_prefetch_budget(&mut self, prefetch_budget: usize) {
+        self.prefetch_budget = prefetch_budget;
+    }
+
+    pub fn set_http_cache_ttl(&mut self, http_cache_ttl: u64) {
+        self.http_cache_ttl = http_cache_ttl;
+    }
+
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+
+    pub fn set_max_requests_per_second(&mut self, max_requests_per_second: u32) {
+        self.max_requests_per_second = max_requests_per_second;
+    }
+
+    pub fn set_instances(&mut self, instances: Vec<Instance>) {
+        self.instances = instances;
+    }
+
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.proxy = proxy;
+    }
+
+    pub fn set_ca_certs_path(&mut self, ca_certs_path: Option<String>) {
+        self.ca_certs_path = ca_certs_path;
+    }
+
+    pub fn set_client_cert_path(&mut self, client_cert_path: Option<String>) {
+        self.client_cert_path = client_cert_path;
+    }
+
+    pub fn set_client_key_path(&mut self, client_key_path: Option<String>) {
+        self.client_key_path = client_key_path;
+    }
+
+    pub fn set_connect_timeout(&mut self, connect_timeout: u64) {
+        self.connect_timeout = connect_timeout;
+    }
+
+    pub fn set_request_timeout(&mut self, request_timeout: u64) {
+        self.request_timeout = request_timeout;
+    }
+
+    pub fn set_local_inboxes(&mut self, local_inboxes: BTreeMap<String, String>) {
+        self.local_inboxes = local_inboxes;
+    }
+
+    pub fn set_use_lei(&mut self, use_lei: bool) {
+        self.use_lei = use_lei;
+    }
+
+    pub fn set_timestamp_format(&mut self, timestamp_format: TimestampFormat) {
+        self.timestamp_format = timestamp_format;
+    }
+
+    pub fn set_open_last_list(&mut self, open_last_list: bool) {
+        self.open_last_list = open_last_list;
+    }
+
+    /// Network options of the agent that sends requests to the instances
+    pub fn agent_options(&self) -> AgentOptions {
+        AgentOptions {
+            proxy: self.proxy.clone(),
+            ca_certs_path: self.ca_certs_path.clone(),
+            client_cert_path: self.client_cert_path.clone(),
+            client_key_path: self.client_key_path.clone(),
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            request_timeout: Duration::from_secs(self.request_timeout),
+        }
+    }
+
+    /// Instance used for searches and lists without a known instance
+    pub fn default_instance(&self) -> &Instance {
+        &self.instances[0]
+    }
+
+    /// Returns the instance named `name`, if it's configured
+    pub fn instance(&self, name: &str) -> Option<&Instance> {
+        self.instances
+            .iter()
+            .find(|instance| instance.name() == name)
+    }
+
+    /// Returns the local inbox `list` is read from, if any. Messages of local
+    /// inboxes are linked as if they were archived in the default instance.
+    pub fn local_inbox(&self, list: &str) -> Option<LocalInbox> {
+        self.local_inboxes
+            .get(list)
+            .map(|path| LocalInbox::new(list, path, self.default_instance().url()))
+    }
+
+    /// All the configured local inboxes
+    pub fn all_local_inboxes(&self) -> LocalInboxes {
+        LocalInboxes(
+            self.local_inboxes
+                .keys()
+                .filter_map(|list| self.local_inbox(list))
+                .collect(),
+        )
+    }
+
+    /// Directory of the patchsets downloaded from `instance`
+    pub fn instance_patchsets_cache_dir(&self, instance: &Instance) -> String {
+        format!("{}/{}", self.patchsets_cache_dir, instance.name())
+    }
+
+    /// Moves the patchsets downloaded before the cache was split by instance,
+    /// which were all from lore.kernel.org, to the directory of its instance.
+    pub fn migrate_patchsets_cache(&self) -> io::Result<()> {
+        let lore_instance = self
+            .instances
+            .iter()
+            .find(|instance| instance.is_default())
+            .cloned()
+            .unwrap_or_default();
+        let instance_dir = self.instance_patchsets_cache_dir(&lore_instance);
+
+        for dir_entry in fs::read_dir(&self.patchsets_cache_dir)? {
+            let path = dir_entry?.path();
+            let Some(file_name) = path.file_name() else {
+                continue;
+            };
+            if path.is_file() {
+                fs::create_dir_all(&instance_dir)?;
+                let new_path = Path::new(&instance_dir).join(file_name);
+                if !new_path.exists() {
+                    fs::rename(&path, new_path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Directory of the cached responses from lore
+    pub fn http_cache_dir(&self) -> String {
+        format!("{}/http", self.cache_dir)
+    }
+
+    /// File with the pinned and recently used lists
+    pub fn list_preferences_path(&self) -> String {
+        format!("{}/list_preferences.json", self.data_dir)
+    }
+
+    /// File with the patchsets already opened
+    pub fn seen_patchsets_path(&self) -> String {
+        format!("{}/seen_patchsets.json", self.data_dir)
+    }
+
+    /// File with the saved searches whose new patchsets are tracked
+    pub fn watches_path(&self) -> String {
+        format!("{}/watches.json", self.data_dir)
+    }
+
+    /// Directory of the feeds saved to be browsed offline
+    pub fn feed_snapshots_dir(&self) -> String {
+        format!("{}/feeds", self.cache_dir)
+    }
+
+    /// File where the feed of `list` from `instance` is saved
+    pub fn feed_snapshot_path(&self, instance: &Instance, list: &str) -> String {
+        format!(
+            "{}/{}/{list}.json",
+            self.feed_snapshots_dir(),
+            instance.name()
+        )
+    }
+
     pub fn save_patch_hub_config(&self) -> io::Result<()> {
         let config_path = if let Ok(path) = env::var("PATCH_HUB_CONFIG_PATH") {
             path
@@ -185,10 +534,14 @@ impl Config {
     ///
     /// This function must be called as soon as the Config is built so no other function attempt to use an inexistent folder.
     pub fn create_dirs(&self) {
+        let http_cache_dir = self.http_cache_dir();
+        let feed_snapshots_dir = self.feed_snapshots_dir();
         let paths = vec![
             &self.cache_dir,
             &self.data_dir,
             &self.patchsets_cache_dir,
+            &http_cache_dir,
+            &feed_snapshots_dir,
             &self.logs_path,
         ];
 
@@ -37,6 +37,45 @@ fn can_build_with_default_values() {
         config.git_send_email_options()
     );
     assert_eq!(30, config.max_log_age());
+    assert_eq!(5, config.prefetch_budget());
+    assert_eq!(300, config.http_cache_ttl());
+    assert_eq!(3, config.max_retries());
+    assert_eq!(4, config.max_requests_per_second());
+    assert_eq!(&vec![Instance::default()], config.instances());
+    assert_eq!(
+        "/fake/home/path/.cache/patch_hub/patchsets/lore",
+        config.instance_patchsets_cache_dir(config.default_instance())
+    );
+    assert_eq!(&None, config.proxy());
+    assert_eq!(&None, config.ca_certs_path());
+    assert_eq!(&None, config.client_cert_path());
+    assert_eq!(&None, config.client_key_path());
+    assert_eq!(30, config.connect_timeout());
+    assert_eq!(120, config.request_timeout());
+    assert!(config.local_inboxes().is_empty());
+    assert!(!config.use_lei());
+    assert_eq!(&TimestampFormat::Absolute, config.timestamp_format());
+    assert!(!config.open_last_list());
+    assert_eq!(
+        "/fake/home/path/.local/share/patch_hub/list_preferences.json",
+        config.list_preferences_path()
+    );
+    assert_eq!(
+        "/fake/home/path/.local/share/patch_hub/watches.json",
+        config.watches_path()
+    );
+    assert_eq!(
+        "/fake/home/path/.local/share/patch_hub/seen_patchsets.json",
+        config.seen_patchsets_path()
+    );
+    assert_eq!(
+        "/fake/home/path/.cache/patch_hub/http",
+        config.http_cache_dir()
+    );
+    assert_eq!(
+        "/fake/home/path/.cache/patch_hub/feeds/lore/amd-gfx.json",
+        config.feed_snapshot_path(config.default_instance(), "amd-gfx")
+    );
 }
 
 #[test]
@@ -64,6 +103,53 @@ fn can_build_with_config_file() {
         config.git_send_email_options()
     );
     assert_eq!(42, config.max_log_age());
+    assert_eq!(7, config.prefetch_budget());
+    assert_eq!(60, config.http_cache_ttl());
+    assert_eq!(5, config.max_retries());
+    assert_eq!(2, config.max_requests_per_second());
+    assert_eq!(
+        &vec![
+            Instance::default(),
+            Instance::new("internal", "https://inbox.internal")
+        ],
+        config.instances()
+    );
+    assert_eq!(
+        Some("https://inbox.internal"),
+        config
+            .instance("internal")
+            .map(|instance| instance.url().as_str())
+    );
+    assert_eq!(
+        &Some("http://proxy.internal:3128".to_string()),
+        config.proxy()
+    );
+    assert_eq!(
+        &Some("/etc/ssl/internal-ca.pem".to_string()),
+        config.ca_certs_path()
+    );
+    assert_eq!(
+        &Some("/client/cert.pem".to_string()),
+        config.client_cert_path()
+    );
+    assert_eq!(
+        &Some("/client/key.pem".to_string()),
+        config.client_key_path()
+    );
+    assert_eq!(10, config.connect_timeout());
+    assert_eq!(60, config.request_timeout());
+    assert_eq!(
+        Some(LocalInbox::new(
+            "internal-list",
+            "/srv/public-inbox/internal-list",
+            "https://lore.kernel.org"
+        )),
+        config.local_inbox("internal-list")
+    );
+    assert_eq!(None, config.local_inbox("amd-gfx"));
+    assert!(config.use_lei());
+    assert_eq!(&TimestampFormat::Relative, config.timestamp_format());
+    assert!(config.open_last_list());
 }
 
 #[test]
@@ -74,8 +160,38 @@ fn can_build_with_env_vars() {
     env::set_var("PATCH_HUB_CACHE_DIR", "/fake/cache/path");
     env::set_var("PATCH_HUB_DATA_DIR", "/fake/data/path");
     env::set_var("PATCH_HUB_GIT_SEND_EMAIL_OPTIONS", "--option1 --option2");
+    env::set_var("PATCH_HUB_PREFETCH_BUDGET", "0");
+    env::set_var("PATCH_HUB_HTTP_CACHE_TTL", "0");
+    env::set_var("PATCH_HUB_MAX_RETRIES", "1");
+    env::set_var("PATCH_HUB_MAX_REQUESTS_PER_SECOND", "0");
+    env::set_var("PATCH_HUB_INSTANCES", "mirror=https://inbox.mirror");
+    env::set_var("PATCH_HUB_PROXY", "socks5://localhost:1080");
+    env::set_var("PATCH_HUB_CA_CERTS_PATH", "");
+    env::set_var("PATCH_HUB_CLIENT_CERT_PATH", "/fake/cert.pem");
+    env::set_var("PATCH_HUB_CLIENT_KEY_PATH", "/fake/key.pem");
+    env::set_var("PATCH_HUB_CONNECT_TIMEOUT", "5");
+    env::set_var("PATCH_HUB_REQUEST_TIMEOUT", "15");
+    env::set_var("PATCH_HUB_LOCAL_INBOXES", "amd-gfx=/srv/amd-gfx");
+    env::set_var("PATCH_HUB_USE_LEI", "true");
+    env::set_var("PATCH_HUB_TIMESTAMP_FORMAT", "relative");
+    env::set_var("PATCH_HUB_OPEN_LAST_LIST", "true");
     let config = Config::build();
     env::remove_var("PATCH_HUB_PAGE_SIZE");
+    env::remove_var("PATCH_HUB_PREFETCH_BUDGET");
+    env::remove_var("PATCH_HUB_HTTP_CACHE_TTL");
+    env::remove_var("PATCH_HUB_MAX_RETRIES");
+    env::remove_var("PATCH_HUB_MAX_REQUESTS_PER_SECOND");
+    env::remove_var("PATCH_HUB_INSTANCES");
+    env::remove_var("PATCH_HUB_PROXY");
+    env::remove_var("PATCH_HUB_CA_CERTS_PATH");
+    env::remove_var("PATCH_HUB_CLIENT_CERT_PATH");
+    env::remove_var("PATCH_HUB_CLIENT_KEY_PATH");
+    env::remove_var("PATCH_HUB_CONNECT_TIMEOUT");
+    env::remove_var("PATCH_HUB_REQUEST_TIMEOUT");
+    env::remove_var("PATCH_HUB_LOCAL_INBOXES");
+    env::remove_var("PATCH_HUB_USE_LEI");
+    env::remove_var("PATCH_HUB_TIMESTAMP_FORMAT");
+    env::remove_var("PATCH_HUB_OPEN_LAST_LIST");
     env::remove_var("PATCH_HUB_CACHE_DIR");
     env::remove_var("PATCH_HUB_DATA_DIR");
     env::remove_var("PATCH_HUB_GIT_SEND_EMAIL_OPTIONS");
@@ -96,6 +212,42 @@ fn can_build_with_env_vars() {
     );
     assert_eq!("/fake/data/path/logs", config.logs_path());
     assert_eq!("--option1 --option2", config.git_send_email_options());
+    assert_eq!(0, config.prefetch_budget());
+    assert_eq!(0, config.http_cache_ttl());
+    assert_eq!(1, config.max_retries());
+    assert_eq!(0, config.max_requests_per_second());
+    assert_eq!("mirror", config.default_instance().name());
+    assert_eq!(&Some("socks5://localhost:1080".to_string()), config.proxy());
+    assert_eq!(
+        &None,
+        config.ca_certs_path(),
+        "Empty variables should unset paths"
+    );
+    assert_eq!(
+        &Some("/fake/cert.pem".to_string()),
+        config.client_cert_path()
+    );
+    assert_eq!(&Some("/fake/key.pem".to_string()), config.client_key_path());
+    let agent_options = config.agent_options();
+    assert_eq!(Duration::from_secs(5), agent_options.connect_timeout);
+    assert_eq!(Duration::from_secs(15), agent_options.request_timeout);
+    assert_eq!("/fake/cache/path/http", config.http_cache_dir());
+    assert_eq!(
+        Some(&"/srv/amd-gfx".to_string()),
+        config.local_inboxes().get("amd-gfx")
+    );
+    assert!(config.use_lei());
+    assert_eq!(&TimestampFormat::Relative, config.timestamp_format());
+    assert!(config.open_last_list());
+    assert_eq!(
+        "/fake/data/path/list_preferences.json",
+        config.list_preferences_path()
+    );
+    assert_eq!("/fake/data/path/watches.json", config.watches_path());
+    assert_eq!(
+        "/fake/data/path/seen_patchsets.json",
+        config.seen_patchsets_path()
+    );
 
     env::remove_var("PATCH_HUB_CACHE_DIR");
     env::remove_var("PATCH_HUB_DATA_DIR");
@@ -126,3 +278,33 @@ fn test_config_precedence() {
     env::remove_var("PATCH_HUB_CONFIG_PATH");
     env::remove_var("PATCH_HUB_PAGE_SIZE");
 }
+
+#[test]
+fn should_migrate_patchsets_downloaded_before_instances() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    let cache_dir = env::temp_dir().join("patch_hub_migrate_patchsets_test");
+    let _ = fs::remove_dir_all(&cache_dir);
+    let mut config = Config::default();
+    config.set_cache_dir(cache_dir.to_string_lossy().into_owned());
+    let patchsets_cache_dir = config.patchsets_cache_dir().clone();
+    let lore_dir = config.instance_patchsets_cache_dir(config.default_instance());
+    fs::create_dir_all(&patchsets_cache_dir).unwrap();
+    fs::write(format!("{patchsets_cache_dir}/old.mbx"), "patch").unwrap();
+    fs::write(format!("{patchsets_cache_dir}/old.cover"), "cover").unwrap();
+
+    config.migrate_patchsets_cache().unwrap();
+    config.migrate_patchsets_cache().unwrap();
+
+    assert_eq!(
+        "patch",
+        fs::read_to_string(format!("{lore_dir}/old.mbx")).unwrap()
+    );
+    assert_eq!(
+        "cover",
+        fs::read_to_string(format!("{lore_dir}/old.cover")).unwrap()
+    );
+    assert!(!Path::new(&format!("{patchsets_cache_dir}/old.mbx")).exists());
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
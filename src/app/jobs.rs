@@ -0,0 +1,211 @@
+use color_eyre::eyre::eyre;
+use derive_getters::Getters;
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of worker threads that run the jobs
+const WORKERS_COUNT: usize = 4;
+
+/// Identifies a job spawned by a [JobManager]
+pub type JobId = u64;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Handle given to a running job, so it can report its progress and check if
+/// it was cancelled.
+pub struct JobContext {
+    id: JobId,
+    cancelled: Arc<AtomicBool>,
+    progress_sender: Sender<(JobId, String)>,
+}
+
+impl JobContext {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Long jobs should check this between steps and return early if set, as
+    /// the result of a cancelled job is discarded anyway.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn report_progress(&self, progress: impl Into<String>) {
+        // The manager may be gone if the app is quitting, which is fine
+        let _ = self.progress_sender.send((self.id, progress.into()));
+    }
+}
+
+/// A job that was spawned and hasn't finished yet.
+#[derive(Getters)]
+pub struct RunningJob {
+    description: String,
+    /// Last progress reported by the job, if any
+    progress: Option<String>,
+    #[getter(skip)]
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Runs jobs in a pool of worker threads, so that slow work (like requests to
+/// lore and rendering patches) doesn't freeze the UI.
+///
+/// Each job produces a value of type `T`. The results are collected by
+/// [JobManager::poll], which is meant to be called by the UI loop, so job
+/// results are always handled in the main thread.
+pub struct JobManager<T> {
+    task_sender: Sender<Task>,
+    result_sender: Sender<(JobId, color_eyre::Result<T>)>,
+    result_receiver: Receiver<(JobId, color_eyre::Result<T>)>,
+    progress_sender: Sender<(JobId, String)>,
+    progress_receiver: Receiver<(JobId, String)>,
+    running_jobs: BTreeMap<JobId, RunningJob>,
+    next_id: JobId,
+}
+
+impl<T: Send + 'static> Default for JobManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + 'static> JobManager<T> {
+    pub fn new() -> JobManager<T> {
+        Self::with_workers(WORKERS_COUNT)
+    }
+
+    /// Creates a manager with `workers_count` worker threads. The workers
+    /// exit once the manager is dropped and they finish their current job.
+    pub fn with_workers(workers_count: usize) -> JobManager<T> {
+        let (task_sender, task_receiver) = mpsc::channel::<Task>();
+        let task_receiver = Arc::new(Mutex::new(task_receiver));
+
+        for _ in 0..workers_count.max(1) {
+            let task_receiver = Arc::clone(&task_receiver);
+            thread::spawn(move || loop {
+                let task = task_receiver.lock().unwrap().recv();
+                match task {
+                    Ok(task) => task(),
+                    Err(_) => break,
+                }
+            });
+        }
+
+        let (result_sender, result_receiver) = mpsc::channel();
+        let (progress_sender, progress_receiver) = mpsc::channel();
+
+        JobManager {
+            task_sender,
+            result_sender,
+            result_receiver,
+            progress_sender,
+            progress_receiver,
+            running_jobs: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Queues `job` to run in a worker thread. `description` tells the user
+    /// what the job is doing while it runs.
+    pub fn spawn<F>(&mut self, description: impl Into<String>, job: F) -> JobId
+    where
+        F: FnOnce(&JobContext) -> color_eyre::Result<T> + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let context = JobContext {
+            id,
+            cancelled: Arc::clone(&cancelled),
+            progress_sender: self.progress_sender.clone(),
+        };
+        let result_sender = self.result_sender.clone();
+
+        let task: Task = Box::new(move || {
+            if context.is_cancelled() {
+                return;
+            }
+            // A panicking job would otherwise take its worker down and never
+            // finish
+            let result = panic::catch_unwind(AssertUnwindSafe(|| job(&context))).unwrap_or_else(
+                |panic_payload| Err(eyre!("Job panicked: {}", panic_message(&*panic_payload))),
+            );
+            if !context.is_cancelled() {
+                let _ = result_sender.send((id, result));
+            }
+        });
+
+        self.running_jobs.insert(
+            id,
+            RunningJob {
+                description: description.into(),
+                progress: None,
+                cancelled,
+            },
+        );
+        // Workers only exit after the manager is dropped
+        self.task_sender.send(task).unwrap();
+
+        id
+    }
+
+    /// Cancels the job with the given `id`. A job that is already running
+    /// keeps going until it checks [JobContext::is_cancelled], but its result
+    /// is discarded.
+    pub fn cancel(&mut self, id: JobId) {
+        if let Some(running_job) = self.running_jobs.remove(&id) {
+            running_job.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn has_running_jobs(&self) -> bool {
+        !self.running_jobs.is_empty()
+    }
+
+    /// Running jobs, in the order they were spawned.
+    pub fn running_jobs(&self) -> impl Iterator<Item = &RunningJob> {
+        self.running_jobs.values()
+    }
+
+    /// Updates the progress of the running jobs and returns the results of
+    /// the jobs that finished since the last call, without blocking. Results
+    /// of cancelled jobs are dropped.
+    pub fn poll(&mut self) -> Vec<(JobId, color_eyre::Result<T>)> {
+        while let Ok((id, progress)) = self.progress_receiver.try_recv() {
+            if let Some(running_job) = self.running_jobs.get_mut(&id) {
+                running_job.progress = Some(progress);
+            }
+        }
+
+        let mut finished_jobs = Vec::new();
+        while let Ok((id, result)) = self.result_receiver.try_recv() {
+            if self.running_jobs.remove(&id).is_some() {
+                finished_jobs.push((id, result));
+            }
+        }
+
+        finished_jobs
+    }
+}
+
+fn panic_message(panic_payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+use std::{
+    sync::mpsc::channel,
+    time::{Duration, Instant},
+};
+
+/// Polls `job_manager` until `count` jobs finish or a timeout expires.
+fn wait_for_results<T: Send + 'static>(
+    job_manager: &mut JobManager<T>,
+    count: usize,
+) -> Vec<(JobId, color_eyre::Result<T>)> {
+    let start = Instant::now();
+    let mut results = Vec::new();
+
+    while results.len() < count && start.elapsed() < Duration::from_secs(5) {
+        results.extend(job_manager.poll());
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    results
+}
+
+#[test]
+fn should_run_jobs_in_background() {
+    let mut job_manager: JobManager<usize> = JobManager::with_workers(2);
+
+    let first_id = job_manager.spawn("first", |_| Ok(1));
+    let second_id = job_manager.spawn("second", |_| Ok(2));
+    assert_ne!(first_id, second_id, "Job ids should be unique");
+
+    let mut results: Vec<(JobId, usize)> = wait_for_results(&mut job_manager, 2)
+        .into_iter()
+        .map(|(id, result)| (id, result.unwrap()))
+        .collect();
+    results.sort();
+
+    assert_eq!(vec![(first_id, 1), (second_id, 2)], results);
+    assert!(!job_manager.has_running_jobs());
+}
+
+#[test]
+fn should_report_errors_of_jobs() {
+    let mut job_manager: JobManager<()> = JobManager::with_workers(1);
+
+    job_manager.spawn("failing", |_| Err(color_eyre::eyre::eyre!("failed")));
+
+    let results = wait_for_results(&mut job_manager, 1);
+    assert_eq!(1, results.len());
+    assert_eq!("failed", results[0].1.as_ref().unwrap_err().to_string());
+}
+
+#[test]
+fn should_report_panics_of_jobs_as_errors() {
+    let mut job_manager: JobManager<usize> = JobManager::with_workers(1);
+
+    let panicking_id = job_manager.spawn("panicking", |_| panic!("broken feed"));
+    let next_id = job_manager.spawn("next", |_| Ok(1));
+
+    let results = wait_for_results(&mut job_manager, 2);
+    assert_eq!(2, results.len(), "The worker should survive the panic");
+    assert_eq!(panicking_id, results[0].0);
+    assert_eq!(
+        "Job panicked: broken feed",
+        results[0].1.as_ref().unwrap_err().to_string()
+    );
+    assert_eq!(next_id, results[1].0);
+    assert_eq!(1, *results[1].1.as_ref().unwrap());
+    assert!(!job_manager.has_running_jobs());
+}
+
+#[test]
+fn should_track_progress_of_running_jobs() {
+    let mut job_manager: JobManager<()> = JobManager::with_workers(1);
+    let (release_sender, release_receiver) = channel::<()>();
+    let (reported_sender, reported_receiver) = channel::<()>();
+
+    job_manager.spawn("slow job", move |context| {
+        context.report_progress("halfway");
+        reported_sender.send(()).unwrap();
+        release_receiver.recv().unwrap();
+        Ok(())
+    });
+
+    reported_receiver.recv().unwrap();
+    assert!(job_manager.poll().is_empty());
+    assert!(job_manager.has_running_jobs());
+
+    let running_job = job_manager.running_jobs().next().unwrap();
+    assert_eq!("slow job", running_job.description());
+    assert_eq!(&Some("halfway".to_string()), running_job.progress());
+
+    release_sender.send(()).unwrap();
+    assert_eq!(1, wait_for_results(&mut job_manager, 1).len());
+    assert!(!job_manager.has_running_jobs());
+}
+
+#[test]
+fn should_discard_results_of_cancelled_jobs() {
+    let mut job_manager: JobManager<usize> = JobManager::with_workers(1);
+    let (release_sender, release_receiver) = channel::<()>();
+
+    let cancelled_id = job_manager.spawn("cancelled", move |context| {
+        release_receiver.recv().unwrap();
+        assert!(context.is_cancelled());
+        Ok(1)
+    });
+    let queued_id = job_manager.spawn("queued", |_| Ok(2));
+
+    job_manager.cancel(cancelled_id);
+    assert_eq!(
+        vec!["queued"],
+        job_manager
+            .running_jobs()
+            .map(|running_job| running_job.description().as_str())
+            .collect::<Vec<&str>>()
+    );
+    release_sender.send(()).unwrap();
+
+    let results = wait_for_results(&mut job_manager, 1);
+    assert_eq!(1, results.len());
+    assert_eq!(queued_id, results[0].0);
+    assert!(job_manager.poll().is_empty());
+}
@@ -2,6 +2,7 @@ use std::{
     fmt::Display,
     fs::{self, File, OpenOptions},
     io::Write,
+    sync::{Mutex, MutexGuard, PoisonError},
 };
 
 use chrono::Local;
@@ -21,6 +22,9 @@ static mut LOG_BUFFER: Logger = Logger {
     logs_to_print: Vec::new(),
     print_level: LogLevel::Warning,
 };
+/// Serializes the access to [LOG_BUFFER], as background jobs log from their
+/// own threads
+static LOG_LOCK: Mutex<()> = Mutex::new(());
 
 /// Describes the log level of a message
 ///
@@ -42,7 +46,7 @@ pub struct LogMessage {
 }
 
 /// The Logger singleton that manages logging to [`stderr`] (log buffer) and a log file.
-/// This is safe to use only in single-threaded scenarios. The messages are written to the log file immediatly,
+/// The access to the singleton is serialized, so it's safe to log from any thread. The messages are written to the log file immediatly,
 /// but the messages to the `stderr` are written only after the TUI is closed, so they are kept in memory.
 ///
 /// The logger also has a log level that can be set to filter the messages that are written to the log file.
@@ -73,13 +77,21 @@ pub struct Logger {
 }
 
 impl Logger {
+    /// Private method to take the lock that serializes the access to the
+    /// Logger singleton. A panic while logging doesn't prevent further logging.
+    #[inline]
+    fn lock() -> MutexGuard<'static, ()> {
+        LOG_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Private method to get access to the Logger singleton
     ///
     /// This function makes use of unsafe code to access a static mut. Also, it's `inline` so won't have any overhead
     ///
     /// # Safety
     ///
-    /// It's safe to use in single-threaded scenarios only
+    /// The returned reference must only be used while holding the guard
+    /// returned by [`Logger::lock`]
     ///
     /// # Examples
     /// ```rust norun
@@ -138,7 +150,7 @@ impl Logger {
     ///
     /// # Safety
     ///
-    /// It's safe to use in single-threaded scenarios only
+    /// It's safe to use from any thread
     ///
     /// # Examples
     ///
@@ -151,6 +163,7 @@ impl Logger {
     #[inline]
     #[allow(dead_code)]
     pub fn info<M: Display>(msg: M) {
+        let _guard = Logger::lock();
         Logger::get_logger().log(LogLevel::Info, msg);
     }
 
@@ -162,7 +175,7 @@ impl Logger {
     ///
     /// # Safety
     ///
-    /// It's safe to use in single-threaded scenarios only
+    /// It's safe to use from any thread
     ///
     /// # Examples
     ///
@@ -175,6 +188,7 @@ impl Logger {
     #[inline]
     #[allow(dead_code)]
     pub fn warn<M: Display>(msg: M) {
+        let _guard = Logger::lock();
         Logger::get_logger().log(LogLevel::Warning, msg);
     }
 
@@ -186,7 +200,7 @@ impl Logger {
     ///
     /// # Safety
     ///
-    /// It's safe to use in single-threaded scenarios only
+    /// It's safe to use from any thread
     ///
     /// # Examples
     ///
@@ -199,6 +213,7 @@ impl Logger {
     #[inline]
     #[allow(dead_code)]
     pub fn error<M: Display>(msg: M) {
+        let _guard = Logger::lock();
         Logger::get_logger().log(LogLevel::Error, msg);
     }
 
@@ -219,6 +234,7 @@ impl Logger {
     /// // Any further attempt to use the logger will panic, unless it's reinitialized
     /// ```
     pub fn flush() {
+        let _guard = Logger::lock();
         let logger = Logger::get_logger();
         for entry in &logger.logs_to_print {
             eprintln!("{}", entry);
@@ -245,6 +261,7 @@ impl Logger {
     /// Logger::init_log_file(&config);
     /// ```
     pub fn init_log_file(config: &Config) {
+        let _guard = Logger::lock();
         let logger = Logger::get_logger();
 
         let logs_path = &config.logs_path();
@@ -0,0 +1,159 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use super::jobs::JobId;
+
+#[cfg(test)]
+mod tests;
+
+/// How long the cursor must rest on a patchset before it is prefetched
+pub const PREFETCH_DELAY: Duration = Duration::from_millis(500);
+
+/// Keeps track of the patchsets loaded in advance, so that opening the
+/// highlighted patchset doesn't have to wait for its download and rendering.
+///
+/// Patchsets are identified by a key (the href of their message-id) and the
+/// prefetched values of type `T` are kept up to the configured budget,
+/// evicting the least recently used ones.
+pub struct Prefetcher<T> {
+    budget: usize,
+    /// Key of the highlighted patchset and since when it is highlighted
+    highlighted: Option<(String, Instant)>,
+    /// Prefetched values, from the least to the most recently used
+    prefetched: VecDeque<(String, T)>,
+    /// Job loading a patchset and the key of that patchset, if any
+    job: Option<(String, JobId)>,
+    /// Keys of the patchsets that failed to load, so they aren't retried
+    failed: HashSet<String>,
+}
+
+impl<T> Prefetcher<T> {
+    pub fn new(budget: usize) -> Prefetcher<T> {
+        Prefetcher {
+            budget,
+            highlighted: None,
+            prefetched: VecDeque::new(),
+            job: None,
+            failed: HashSet::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.budget > 0
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict();
+    }
+
+    /// Records that the patchset with `key` is highlighted at `now`. The
+    /// resting time only restarts if the highlighted patchset changed.
+    pub fn highlight(&mut self, key: &str, now: Instant) {
+        match &self.highlighted {
+            Some((highlighted_key, _)) if highlighted_key == key => (),
+            _ => self.highlighted = Some((key.to_string(), now)),
+        }
+    }
+
+    /// Records that no patchset is highlighted.
+    pub fn clear_highlight(&mut self) {
+        self.highlighted = None;
+    }
+
+    /// Whether the highlighted patchset is still waiting to be prefetched.
+    pub fn is_pending(&self) -> bool {
+        match &self.highlighted {
+            Some((key, _)) => self.is_enabled() && !self.is_known(key),
+            None => false,
+        }
+    }
+
+    /// Returns the key of the highlighted patchset if the cursor rested on it
+    /// for [PREFETCH_DELAY] and it wasn't prefetched yet.
+    pub fn due(&self, now: Instant) -> Option<&str> {
+        let (key, since) = self.highlighted.as_ref()?;
+        if !self.is_pending() || now.duration_since(*since) < PREFETCH_DELAY {
+            return None;
+        }
+        Some(key)
+    }
+
+    /// Records that `job` is loading the patchset with `key`. Returns the job
+    /// that was loading another patchset, if any, which is no longer tracked.
+    pub fn start(&mut self, key: &str, job: JobId) -> Option<JobId> {
+        self.job
+            .replace((key.to_string(), job))
+            .map(|(_, previous_job)| previous_job)
+    }
+
+    /// Job loading the patchset with `key`, if any.
+    pub fn job_for(&self, key: &str) -> Option<JobId> {
+        match &self.job {
+            Some((job_key, job)) if job_key == key => Some(*job),
+            _ => None,
+        }
+    }
+
+    /// Stops tracking `job`, which was cancelled.
+    pub fn forget_job(&mut self, job: JobId) {
+        if matches!(&self.job, Some((_, tracked_job)) if *tracked_job == job) {
+            self.job = None;
+        }
+    }
+
+    /// Stores the `value` loaded for the patchset with `key` by `job`, be it a
+    /// prefetch or not.
+    pub fn finish(&mut self, job: JobId, key: &str, value: T) {
+        self.forget_job(job);
+        if !self.is_enabled() {
+            return;
+        }
+        self.prefetched
+            .retain(|(prefetched_key, _)| prefetched_key != key);
+        self.prefetched.push_back((key.to_string(), value));
+        self.evict();
+    }
+
+    /// Records that `job` failed to load the patchset with `key`.
+    pub fn fail(&mut self, job: JobId, key: &str) {
+        self.forget_job(job);
+        self.failed.insert(key.to_string());
+    }
+
+    /// Returns the value prefetched for the patchset with `key`, marking it as
+    /// the most recently used.
+    pub fn get(&mut self, key: &str) -> Option<&T> {
+        let index = self
+            .prefetched
+            .iter()
+            .position(|(prefetched_key, _)| prefetched_key == key)?;
+        let entry = self.prefetched.remove(index)?;
+        self.prefetched.push_back(entry);
+        self.prefetched.back().map(|(_, value)| value)
+    }
+
+    /// Drops all the prefetched values, e.g., when they were rendered with
+    /// outdated configurations.
+    pub fn clear(&mut self) {
+        self.prefetched.clear();
+        self.failed.clear();
+    }
+
+    fn is_known(&self, key: &str) -> bool {
+        self.job_for(key).is_some()
+            || self.failed.contains(key)
+            || self
+                .prefetched
+                .iter()
+                .any(|(prefetched_key, _)| prefetched_key == key)
+    }
+
+    fn evict(&mut self) {
+        while self.prefetched.len() > self.budget {
+            self.prefetched.pop_front();
+        }
+    }
+}
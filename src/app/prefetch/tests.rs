@@ -0,0 +1,95 @@
+use super::*;
+
+#[test]
+fn should_be_due_only_after_resting_on_patchset() {
+    let mut prefetcher: Prefetcher<()> = Prefetcher::new(2);
+    let start = Instant::now();
+
+    prefetcher.highlight("first", start);
+    assert!(prefetcher.is_pending());
+    assert_eq!(None, prefetcher.due(start + PREFETCH_DELAY / 2));
+
+    prefetcher.highlight("second", start + PREFETCH_DELAY / 2);
+    assert_eq!(
+        None,
+        prefetcher.due(start + PREFETCH_DELAY),
+        "Moving the cursor should restart the resting time"
+    );
+
+    prefetcher.highlight("second", start + PREFETCH_DELAY);
+    assert_eq!(
+        Some("second"),
+        prefetcher.due(start + PREFETCH_DELAY * 2),
+        "Highlighting the same patchset shouldn't restart the resting time"
+    );
+
+    prefetcher.clear_highlight();
+    assert!(!prefetcher.is_pending());
+    assert_eq!(None, prefetcher.due(start + PREFETCH_DELAY * 3));
+}
+
+#[test]
+fn should_not_prefetch_known_patchsets() {
+    let mut prefetcher: Prefetcher<()> = Prefetcher::new(2);
+    let start = Instant::now();
+    let later = start + PREFETCH_DELAY;
+
+    prefetcher.highlight("first", start);
+    assert_eq!(None, prefetcher.start("first", 1));
+    assert_eq!(Some(1), prefetcher.job_for("first"));
+    assert_eq!(None, prefetcher.due(later), "Patchset is being prefetched");
+
+    prefetcher.finish(1, "first", ());
+    assert_eq!(None, prefetcher.job_for("first"));
+    assert_eq!(None, prefetcher.due(later), "Patchset was prefetched");
+    assert!(!prefetcher.is_pending());
+
+    prefetcher.highlight("second", start);
+    assert_eq!(None, prefetcher.start("second", 2));
+    assert_eq!(
+        Some(2),
+        prefetcher.start("third", 3),
+        "Starting a prefetch should hand back the previous one"
+    );
+    prefetcher.forget_job(3);
+    assert_eq!(Some("second"), prefetcher.due(later));
+
+    prefetcher.start("second", 4);
+    prefetcher.fail(4, "second");
+    assert_eq!(
+        None,
+        prefetcher.due(later),
+        "Failed patchsets aren't retried"
+    );
+}
+
+#[test]
+fn should_evict_least_recently_used_patchsets() {
+    let mut prefetcher = Prefetcher::new(2);
+
+    prefetcher.finish(1, "first", 1);
+    prefetcher.finish(2, "second", 2);
+    assert_eq!(Some(&1), prefetcher.get("first"));
+    prefetcher.finish(3, "third", 3);
+
+    assert_eq!(None, prefetcher.get("second"));
+    assert_eq!(Some(&1), prefetcher.get("first"));
+    assert_eq!(Some(&3), prefetcher.get("third"));
+
+    prefetcher.set_budget(1);
+    assert_eq!(None, prefetcher.get("first"));
+    assert_eq!(Some(&3), prefetcher.get("third"));
+}
+
+#[test]
+fn should_not_prefetch_without_budget() {
+    let mut prefetcher = Prefetcher::new(0);
+    let start = Instant::now();
+
+    prefetcher.highlight("first", start);
+    assert!(!prefetcher.is_pending());
+    assert_eq!(None, prefetcher.due(start + PREFETCH_DELAY));
+
+    prefetcher.finish(1, "first", 1);
+    assert_eq!(None, prefetcher.get("first"));
+}
@@ -3,6 +3,9 @@ pub mod details_actions;
 pub mod edit_config;
 pub mod latest;
 pub mod mail_list;
+pub mod search;
+pub mod thread;
+pub mod watches;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CurrentScreen {
@@ -11,4 +14,7 @@ pub enum CurrentScreen {
     LatestPatchsets,
     PatchsetDetails,
     EditConfig,
+    Search,
+    PatchsetThread,
+    Watches,
 }
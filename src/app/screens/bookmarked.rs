@@ -1,40 +1,110 @@
-use patch_hub::lore::patch::Patch;
+use chrono::Utc;
+use patch_hub::lore::{
+    patch::{Patch, PatchFilter, PatchOrder},
+    seen::SeenPatchsets,
+};
 
 pub struct BookmarkedPatchsets {
     pub bookmarked_patchsets: Vec<Patch>,
     pub patchset_index: usize,
+    /// Which of the bookmarked patchsets are listed. The selection only
+    /// counts the listed ones.
+    pub patch_filter: PatchFilter,
+    pub order: PatchOrder,
 }
 
 impl BookmarkedPatchsets {
     pub fn select_below_patchset(&mut self) {
-        if self.patchset_index + 1 < self.bookmarked_patchsets.len() {
+        self.patchset_index = self.selected_index();
+        if self.patchset_index + 1 < self.listed_patchsets().len() {
             self.patchset_index += 1;
         }
     }
 
     pub fn select_above_patchset(&mut self) {
-        self.patchset_index = self.patchset_index.saturating_sub(1);
+        self.patchset_index = self.selected_index().saturating_sub(1);
     }
 
-    pub fn get_selected_patchset(&self) -> Patch {
+    /// Selected patchset, if any is listed
+    pub fn get_selected_patchset(&self) -> Option<Patch> {
+        self.listed_patchsets()
+            .get(self.selected_index())
+            .map(|patch| (*patch).clone())
+    }
+
+    /// Index of the selection, clamped to the listed patchsets, as they may
+    /// get older than the maximum age of the filter while listed
+    fn selected_index(&self) -> usize {
+        self.patchset_index
+            .min(self.listed_patchsets().len().saturating_sub(1))
+    }
+
+    /// Bookmarked patchsets that pass [BookmarkedPatchsets::patch_filter],
+    /// in [BookmarkedPatchsets::order]
+    pub fn listed_patchsets(&self) -> Vec<&Patch> {
+        let now = Utc::now();
+        let mut listed_patchsets: Vec<&Patch> = self
+            .bookmarked_patchsets
+            .iter()
+            .filter(|patch| self.patch_filter.matches(patch, now))
+            .collect();
+        self.order.sort(&mut listed_patchsets);
+        listed_patchsets
+    }
+
+    /// Selects the first listed patchset after the selected one that isn't
+    /// in `seen_patchsets`. Returns `false`, leaving the selection
+    /// untouched, if there is none.
+    pub fn select_next_unseen_patchset(&mut self, seen_patchsets: &SeenPatchsets) -> bool {
+        let Some(patchset_index) = self
+            .listed_patchsets()
+            .iter()
+            .enumerate()
+            .skip(self.selected_index() + 1)
+            .find(|(_, patch)| !seen_patchsets.is_seen(patch))
+            .map(|(index, _)| index)
+        else {
+            return false;
+        };
+        self.patchset_index = patchset_index;
+        true
+    }
+
+    /// Lists only the bookmarked patchsets that pass `patch_filter`, selecting
+    /// the first of them.
+    pub fn set_patch_filter(&mut self, patch_filter: PatchFilter) {
+        self.patch_filter = patch_filter;
+        self.patchset_index = 0;
+    }
+
+    /// Lists the bookmarked patchsets in `order`, selecting the first of
+    /// them.
+    pub fn set_order(&mut self, order: PatchOrder) {
+        self.order = order;
+        self.patchset_index = 0;
+    }
+
+    /// Whether `patch` is bookmarked. Bookmarks are told apart by their
+    /// message-id, as the ones saved by older versions lack fields, like
+    /// [Patch::tag].
+    pub fn is_bookmarked(&self, patch: &Patch) -> bool {
+        self.position_of(patch).is_some()
+    }
+
+    fn position_of(&self, patch: &Patch) -> Option<usize> {
         self.bookmarked_patchsets
-            .get(self.patchset_index)
-            .unwrap()
-            .clone()
+            .iter()
+            .position(|bookmarked| bookmarked.message_id() == patch.message_id())
     }
 
     pub fn bookmark_selected_patch(&mut self, patch_to_bookmark: &Patch) {
-        if !self.bookmarked_patchsets.contains(patch_to_bookmark) {
+        if !self.is_bookmarked(patch_to_bookmark) {
             self.bookmarked_patchsets.push(patch_to_bookmark.clone());
         }
     }
 
     pub fn unbookmark_selected_patch(&mut self, patch_to_unbookmark: &Patch) {
-        if let Some(index) = self
-            .bookmarked_patchsets
-            .iter()
-            .position(|r| r == patch_to_unbookmark)
-        {
+        if let Some(index) = self.position_of(patch_to_unbookmark) {
             self.bookmarked_patchsets.remove(index);
         }
     }
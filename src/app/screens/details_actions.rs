@@ -1,5 +1,11 @@
 use super::CurrentScreen;
-use ::patch_hub::lore::{lore_api_client::BlockingLoreAPIClient, lore_session, patch::Patch};
+use ::patch_hub::lore::{
+    lore_api_client::BlockingLoreAPIClient,
+    lore_session,
+    patch::Patch,
+    series::{SeriesHistory, SeriesMetadata, SeriesParts},
+    trailers::PatchsetTrailers,
+};
 use color_eyre::eyre::bail;
 use ratatui::text::Text;
 use std::{collections::HashMap, path::Path, process::Command};
@@ -19,6 +25,25 @@ pub struct DetailsActions {
     pub patchset_actions: HashMap<PatchsetAction, bool>,
     pub last_screen: CurrentScreen,
     pub lore_api_client: BlockingLoreAPIClient,
+    /// Versions of the series of `representative_patch`, once looked up
+    pub series_history: Option<SeriesHistory>,
+    /// Range-diff against another version of the series, previewed instead
+    /// of the patches while set
+    pub range_diff: Option<RangeDiffPreview>,
+    /// Trailers given to each patch, if the thread could be fetched
+    pub trailers: Option<PatchsetTrailers>,
+    /// Parts of the series that were downloaded
+    pub series_parts: SeriesParts,
+    /// Metadata of the series, if it was sent with `b4`
+    pub series_metadata: SeriesMetadata,
+}
+
+/// Pages of a range-diff between two versions of a series, rendered to be
+/// previewed (see [patch_hub::lore::range_diff::range_diff])
+pub struct RangeDiffPreview {
+    pub old_version: usize,
+    pub new_version: usize,
+    pub pages: Vec<Text<'static>>,
 }
 
 const LAST_LINE_PADDING: usize = 10;
@@ -27,11 +52,35 @@ const LAST_LINE_PADDING: usize = 10;
 pub enum PatchsetAction {
     Bookmark,
     ReplyWithReviewedBy,
+    Apply,
 }
 
 impl DetailsActions {
+    /// Previews being shown: the pages of the range-diff, if any, or the
+    /// patches
+    fn previews(&self) -> &[Text<'static>] {
+        match &self.range_diff {
+            Some(range_diff) => &range_diff.pages,
+            None => &self.patches_preview,
+        }
+    }
+
+    /// Preview being shown, if there is any to show
+    pub fn current_preview(&self) -> Option<&Text<'static>> {
+        self.previews().get(self.preview_index)
+    }
+
+    /// Previews the pages of `range_diff` instead of the patches or, if it's
+    /// `None`, goes back to previewing the patches, from the first one.
+    pub fn set_range_diff(&mut self, range_diff: Option<RangeDiffPreview>) {
+        self.range_diff = range_diff;
+        self.preview_index = 0;
+        self.preview_scroll_offset = 0;
+        self.preview_pan = 0;
+    }
+
     pub fn preview_next_patch(&mut self) {
-        if (self.preview_index + 1) < self.patches_preview.len() {
+        if (self.preview_index + 1) < self.previews().len() {
             self.preview_index += 1;
             self.preview_scroll_offset = 0;
             self.preview_pan = 0;
@@ -49,7 +98,7 @@ impl DetailsActions {
     /// Scroll `n` lines down
     pub fn preview_scroll_down(&mut self, n: usize) {
         // TODO: Support for renderers (only considers base preview string)
-        let number_of_lines = self.patches_preview[self.preview_index].height();
+        let number_of_lines = self.current_preview().map_or(0, Text::height);
         if (self.preview_scroll_offset + n) <= number_of_lines {
             self.preview_scroll_offset += n;
         }
@@ -63,8 +112,8 @@ impl DetailsActions {
     /// Scroll to the last line
     pub fn go_to_last_line(&mut self) {
         // TODO: Support for renderers (only considers base preview string)
-        let number_of_lines = self.patches_preview[self.preview_index].height();
-        self.preview_scroll_offset = number_of_lines - LAST_LINE_PADDING;
+        let number_of_lines = self.current_preview().map_or(0, Text::height);
+        self.preview_scroll_offset = number_of_lines.saturating_sub(LAST_LINE_PADDING);
     }
 
     /// Scroll to first line
@@ -104,6 +153,10 @@ impl DetailsActions {
         self.toggle_action(PatchsetAction::ReplyWithReviewedBy);
     }
 
+    pub fn toggle_apply_action(&mut self) {
+        self.toggle_action(PatchsetAction::Apply);
+    }
+
     pub fn toggle_action(&mut self, patchset_action: PatchsetAction) {
         let current_value = *self.patchset_actions.get(&patchset_action).unwrap();
         self.patchset_actions
@@ -115,6 +168,31 @@ impl DetailsActions {
             .patchset_actions
             .get(&PatchsetAction::ReplyWithReviewedBy)
             .unwrap()
+            || *self.patchset_actions.get(&PatchsetAction::Apply).unwrap()
+    }
+
+    /// Applies the patchset with `git am` to the repository in the current
+    /// directory, on top of its `base-commit`, if known. Stops at the first
+    /// command that fails, leaving the repository for the user to sort out.
+    pub fn apply_patchset(&self) -> color_eyre::Result<()> {
+        let tmp_dir = Command::new("mktemp").arg("--directory").output()?;
+        let tmp_dir = Path::new(std::str::from_utf8(&tmp_dir.stdout)?.trim());
+
+        let git_apply_commands = lore_session::prepare_apply_patchset(
+            tmp_dir,
+            &self.raw_patches,
+            self.series_metadata.base_commit().as_deref(),
+        )?;
+
+        for mut command in git_apply_commands {
+            let exit_status = command.spawn()?.wait()?;
+            if !exit_status.success() {
+                println!("`{command:?}` failed\nAborting...");
+                return Ok(());
+            }
+        }
+
+        Ok(())
     }
 
     pub fn reply_patchset_with_reviewed_by(
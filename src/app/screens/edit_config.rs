@@ -1,8 +1,17 @@
-use std::{collections::HashMap, fmt::Display, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    path::Path,
+};
 
 use crate::app::config::Config;
 use color_eyre::eyre::bail;
 use derive_getters::Getters;
+use patch_hub::lore::{
+    instance::{self, Instance},
+    local_inbox,
+    timestamp::TimestampFormat,
+};
 
 #[derive(Debug, Getters)]
 pub struct EditConfig {
@@ -32,6 +41,60 @@ impl EditConfig {
             config.cover_renderer().to_string(),
         );
         config_buffer.insert(EditableConfig::MaxLogAge, config.max_log_age().to_string());
+        config_buffer.insert(
+            EditableConfig::PrefetchBudget,
+            config.prefetch_budget().to_string(),
+        );
+        config_buffer.insert(
+            EditableConfig::HttpCacheTtl,
+            config.http_cache_ttl().to_string(),
+        );
+        config_buffer.insert(EditableConfig::MaxRetries, config.max_retries().to_string());
+        config_buffer.insert(
+            EditableConfig::MaxRequestsPerSecond,
+            config.max_requests_per_second().to_string(),
+        );
+        config_buffer.insert(
+            EditableConfig::Instances,
+            instance::format_instances(config.instances()),
+        );
+        config_buffer.insert(
+            EditableConfig::Proxy,
+            config.proxy().clone().unwrap_or_default(),
+        );
+        config_buffer.insert(
+            EditableConfig::CaCertsPath,
+            config.ca_certs_path().clone().unwrap_or_default(),
+        );
+        config_buffer.insert(
+            EditableConfig::ClientCertPath,
+            config.client_cert_path().clone().unwrap_or_default(),
+        );
+        config_buffer.insert(
+            EditableConfig::ClientKeyPath,
+            config.client_key_path().clone().unwrap_or_default(),
+        );
+        config_buffer.insert(
+            EditableConfig::ConnectTimeout,
+            config.connect_timeout().to_string(),
+        );
+        config_buffer.insert(
+            EditableConfig::RequestTimeout,
+            config.request_timeout().to_string(),
+        );
+        config_buffer.insert(
+            EditableConfig::LocalInboxes,
+            local_inbox::format_local_inboxes(config.local_inboxes()),
+        );
+        config_buffer.insert(EditableConfig::UseLei, config.use_lei().to_string());
+        config_buffer.insert(
+            EditableConfig::TimestampFormat,
+            config.timestamp_format().to_string(),
+        );
+        config_buffer.insert(
+            EditableConfig::OpenLastList,
+            config.open_last_list().to_string(),
+        );
 
         EditConfig {
             config_buffer,
@@ -195,6 +258,221 @@ impl EditConfig {
             Err(_) => Err(()),
         }
     }
+
+    /// Extracts the prefetch budget from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prefetch budget inserted string is not a valid integer
+    pub fn prefetch_budget(&mut self) -> Result<usize, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::PrefetchBudget)
+            .parse::<usize>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the HTTP cache TTL from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP cache TTL inserted string is not a valid integer
+    pub fn http_cache_ttl(&mut self) -> Result<u64, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::HttpCacheTtl)
+            .parse::<u64>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the max retries from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the max retries inserted string is not a valid integer
+    pub fn max_retries(&mut self) -> Result<u32, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::MaxRetries)
+            .parse::<u32>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the max requests per second from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the max requests per second inserted string is not a valid integer
+    pub fn max_requests_per_second(&mut self) -> Result<u32, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::MaxRequestsPerSecond)
+            .parse::<u32>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the public-inbox instances from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inserted string isn't in the format
+    /// `name=url,name=url`
+    pub fn instances(&mut self) -> Result<Vec<Instance>, ()> {
+        match instance::parse_instances(&self.extract_config_buffer_val(&EditableConfig::Instances))
+        {
+            Some(instances) => Ok(instances),
+            None => Err(()),
+        }
+    }
+
+    /// Extracts the proxy URL from the config, which is unset if blank
+    pub fn proxy(&mut self) -> Result<Option<String>, ()> {
+        let proxy = self.extract_config_buffer_val(&EditableConfig::Proxy);
+        // The URL is validated when the agent is built
+        Ok((!proxy.is_empty()).then_some(proxy))
+    }
+
+    fn optional_file(&mut self, editable_config: &EditableConfig) -> Result<Option<String>, ()> {
+        let file_path = self.extract_config_buffer_val(editable_config);
+        if file_path.is_empty() {
+            Ok(None)
+        } else if Path::new(&file_path).is_file() {
+            Ok(Some(file_path))
+        } else {
+            Err(())
+        }
+    }
+
+    /// Extracts the path of the CA certificates from the config, which is
+    /// unset if blank
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is neither blank nor a file
+    pub fn ca_certs_path(&mut self) -> Result<Option<String>, ()> {
+        self.optional_file(&EditableConfig::CaCertsPath)
+    }
+
+    /// Extracts the path of the client certificate from the config, which is
+    /// unset if blank
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is neither blank nor a file
+    pub fn client_cert_path(&mut self) -> Result<Option<String>, ()> {
+        self.optional_file(&EditableConfig::ClientCertPath)
+    }
+
+    /// Extracts the path of the client key from the config, which is unset
+    /// if blank
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is neither blank nor a file
+    pub fn client_key_path(&mut self) -> Result<Option<String>, ()> {
+        self.optional_file(&EditableConfig::ClientKeyPath)
+    }
+
+    /// Extracts the connect timeout from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connect timeout inserted string is not a valid integer
+    pub fn connect_timeout(&mut self) -> Result<u64, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::ConnectTimeout)
+            .parse::<u64>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the request timeout from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request timeout inserted string is not a valid integer
+    pub fn request_timeout(&mut self) -> Result<u64, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::RequestTimeout)
+            .parse::<u64>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts the lists read from local inboxes from the config, which are
+    /// unset if blank
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inserted string is neither blank nor in the
+    /// format `list=path,list=path` or if any path isn't a directory
+    pub fn local_inboxes(&mut self) -> Result<BTreeMap<String, String>, ()> {
+        let local_inboxes = self.extract_config_buffer_val(&EditableConfig::LocalInboxes);
+        if local_inboxes.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        match local_inbox::parse_local_inboxes(&local_inboxes) {
+            Some(local_inboxes) if local_inboxes.values().all(|path| Path::new(path).is_dir()) => {
+                Ok(local_inboxes)
+            }
+            _ => Err(()),
+        }
+    }
+
+    /// Extracts whether the searches saved in `lei` are used from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inserted string is neither `true` nor `false`
+    pub fn use_lei(&mut self) -> Result<bool, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::UseLei)
+            .parse::<bool>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Extracts how timestamps are shown from the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inserted string is neither `absolute` nor
+    /// `relative`
+    pub fn timestamp_format(&mut self) -> Result<TimestampFormat, ()> {
+        self.extract_config_buffer_val(&EditableConfig::TimestampFormat)
+            .parse()
+    }
+
+    /// Extracts whether the last used list is opened on startup from the
+    /// config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inserted string is neither `true` nor `false`
+    pub fn open_last_list(&mut self) -> Result<bool, ()> {
+        match self
+            .extract_config_buffer_val(&EditableConfig::OpenLastList)
+            .parse::<bool>()
+        {
+            Ok(value) => Ok(value),
+            Err(_) => Err(()),
+        }
+    }
 }
 
 #[derive(Debug, Hash, Eq, PartialEq)]
@@ -206,6 +484,21 @@ enum EditableConfig {
     PatchRenderer,
     CoverRenderer,
     MaxLogAge,
+    PrefetchBudget,
+    HttpCacheTtl,
+    MaxRetries,
+    MaxRequestsPerSecond,
+    Instances,
+    Proxy,
+    CaCertsPath,
+    ClientCertPath,
+    ClientKeyPath,
+    ConnectTimeout,
+    RequestTimeout,
+    LocalInboxes,
+    UseLei,
+    TimestampFormat,
+    OpenLastList,
 }
 
 impl TryFrom<usize> for EditableConfig {
@@ -220,6 +513,21 @@ impl TryFrom<usize> for EditableConfig {
             4 => Ok(EditableConfig::PatchRenderer),
             5 => Ok(EditableConfig::CoverRenderer),
             6 => Ok(EditableConfig::MaxLogAge),
+            7 => Ok(EditableConfig::PrefetchBudget),
+            8 => Ok(EditableConfig::HttpCacheTtl),
+            9 => Ok(EditableConfig::MaxRetries),
+            10 => Ok(EditableConfig::MaxRequestsPerSecond),
+            11 => Ok(EditableConfig::Instances),
+            12 => Ok(EditableConfig::Proxy),
+            13 => Ok(EditableConfig::CaCertsPath),
+            14 => Ok(EditableConfig::ClientCertPath),
+            15 => Ok(EditableConfig::ClientKeyPath),
+            16 => Ok(EditableConfig::ConnectTimeout),
+            17 => Ok(EditableConfig::RequestTimeout),
+            18 => Ok(EditableConfig::LocalInboxes),
+            19 => Ok(EditableConfig::UseLei),
+            20 => Ok(EditableConfig::TimestampFormat),
+            21 => Ok(EditableConfig::OpenLastList),
             _ => bail!("Invalid index {} for EditableConfig", value), // Handle out of bounds
         }
     }
@@ -239,6 +547,23 @@ impl Display for EditableConfig {
             }
             EditableConfig::GitSendEmailOpt => write!(f, "`git send email` option"),
             EditableConfig::MaxLogAge => write!(f, "Max Log Age (0 = forever)"),
+            EditableConfig::PrefetchBudget => write!(f, "Prefetch Budget (0 = disabled)"),
+            EditableConfig::HttpCacheTtl => write!(f, "HTTP Cache TTL (seconds)"),
+            EditableConfig::MaxRetries => write!(f, "Max Retries of Requests"),
+            EditableConfig::MaxRequestsPerSecond => {
+                write!(f, "Max Requests per Second (0 = unlimited)")
+            }
+            EditableConfig::Instances => write!(f, "Instances (name=url,name=url)"),
+            EditableConfig::Proxy => write!(f, "Proxy URL"),
+            EditableConfig::CaCertsPath => write!(f, "CA Certificates (PEM file)"),
+            EditableConfig::ClientCertPath => write!(f, "Client Certificate (PEM file)"),
+            EditableConfig::ClientKeyPath => write!(f, "Client Key (PEM file)"),
+            EditableConfig::ConnectTimeout => write!(f, "Connect Timeout (seconds)"),
+            EditableConfig::RequestTimeout => write!(f, "Request Timeout (seconds)"),
+            EditableConfig::LocalInboxes => write!(f, "Local Inboxes (list=path,list=path)"),
+            EditableConfig::UseLei => write!(f, "Use lei Saved Searches (true, false)"),
+            EditableConfig::TimestampFormat => write!(f, "Timestamp Format (absolute, relative)"),
+            EditableConfig::OpenLastList => write!(f, "Open Last List on Startup (true, false)"),
         }
     }
 }
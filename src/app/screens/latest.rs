@@ -1,56 +1,278 @@
-use color_eyre::eyre::bail;
+use crate::app::{
+    jobs::{JobContext, JobId},
+    logging::Logger,
+};
+use chrono::Utc;
+use color_eyre::eyre::Report;
 use derive_getters::Getters;
 use patch_hub::lore::{
-    lore_api_client::{BlockingLoreAPIClient, ClientError},
+    feed_snapshot,
+    local_source::LocalSource,
+    lore_api_client::{BlockingLoreAPIClient, ClientError, PatchFeedRequest},
     lore_session::{LoreSession, LoreSessionError},
-    patch::Patch,
+    patch::{self, Patch, PatchFilter, PatchOrder},
+    query::LoreQuery,
+    seen::SeenPatchsets,
+    series::SeriesParts,
 };
 
+/// How close to the end of the current page the selection must get for the
+/// next page to be prefetched
+const NEXT_PAGE_PREFETCH_DISTANCE: usize = 5;
+
+/// How many pages of patchsets filtered out may be fetched for each listed
+/// page before giving up on filling it
+const MAX_FILTERED_OUT_PAGES_PER_PAGE: usize = 10;
+
 #[derive(Getters)]
 pub struct LatestPatchsets {
     lore_session: LoreSession,
     lore_api_client: BlockingLoreAPIClient,
+    /// Name of the feed, which joins the names of its lists if merged
     target_list: String,
+    /// Lists whose feeds are merged, usually only one
+    target_lists: Vec<String>,
+    query: LoreQuery,
     page_number: usize,
     patchset_index: usize,
     page_size: usize,
+    /// Job fetching the patchsets of the current (or next) page, if any
+    fetch_job: Option<JobId>,
+    /// Whether the last fetch reached the end of the feed
+    is_feed_exhausted: bool,
+    /// Where the fetched feed is saved, to be browsed offline, if anywhere
+    snapshot_path: Option<String>,
+    /// When the snapshot the patchsets were restored from was taken, in
+    /// seconds since the Unix epoch, if they weren't fetched
+    stale_since: Option<u64>,
+    /// Where the feed is read from instead of its instance, if anywhere
+    local_source: Option<LocalSource>,
+    /// Which of the fetched patchsets are listed. Pages and the selection
+    /// only count the listed ones.
+    patch_filter: PatchFilter,
+    /// Order of the listed patchsets. Only the fetched ones are sorted, so
+    /// pages are reshuffled as more patchsets are fetched.
+    order: PatchOrder,
+    /// Message-ids of the listed patchsets, in order, updated whenever the
+    /// session, the filter or the order changes, so the ages are those of
+    /// then
+    #[getter(skip)]
+    listed_ids: Vec<String>,
 }
 
 impl LatestPatchsets {
     pub fn new(
         target_list: String,
+        query: LoreQuery,
+        page_size: usize,
+        lore_api_client: BlockingLoreAPIClient,
+    ) -> LatestPatchsets {
+        Self::merged(vec![target_list], query, page_size, lore_api_client)
+    }
+
+    /// Lists the patchsets of the feeds of `target_lists`, merged (see
+    /// [LoreSession::merged]).
+    pub fn merged(
+        target_lists: Vec<String>,
+        query: LoreQuery,
         page_size: usize,
         lore_api_client: BlockingLoreAPIClient,
     ) -> LatestPatchsets {
         LatestPatchsets {
-            lore_session: LoreSession::new(target_list.clone()),
+            lore_session: LoreSession::merged(target_lists.clone(), query.clone()),
             lore_api_client,
-            target_list,
+            target_list: target_lists.join("+"),
+            target_lists,
+            query,
             page_number: 1,
             patchset_index: 0,
             page_size,
+            fetch_job: None,
+            is_feed_exhausted: false,
+            snapshot_path: None,
+            stale_since: None,
+            local_source: None,
+            patch_filter: PatchFilter::default(),
+            order: PatchOrder::default(),
+            listed_ids: Vec::new(),
         }
     }
 
-    pub fn fetch_current_page(&mut self) -> color_eyre::Result<()> {
-        if let Err(lore_session_error) = self.lore_session.process_n_representative_patches(
-            &self.lore_api_client,
-            self.page_size * self.page_number,
-        ) {
-            match lore_session_error {
-                LoreSessionError::FromLoreAPIClient(client_error) => match client_error {
-                    ClientError::FromUreq(_) => {
-                        bail!("Failed to request feed\n{client_error:#?}")
-                    }
-                    ClientError::EndOfFeed => (),
-                },
-            }
+    /// Reads the feed from `local_source` instead of requesting it. As the
+    /// source is always at hand, its feed isn't saved in snapshots.
+    pub fn with_local_source(mut self, local_source: LocalSource) -> LatestPatchsets {
+        self.local_source = Some(local_source);
+        self.snapshot_path = None;
+        self
+    }
+
+    /// Saves the feed to `snapshot_path` after each fetch, so it can be
+    /// restored with [LatestPatchsets::restore_snapshot].
+    pub fn with_snapshot_path(mut self, snapshot_path: String) -> LatestPatchsets {
+        self.snapshot_path = Some(snapshot_path);
+        self
+    }
+
+    /// Replaces the patchsets with the ones of the last saved snapshot of the
+    /// feed, which won't be fetched any further. Returns `false`, leaving the
+    /// state untouched, if there is no snapshot.
+    pub fn restore_snapshot(&mut self) -> bool {
+        let Some(snapshot_path) = &self.snapshot_path else {
+            return false;
+        };
+        let Ok(feed_snapshot) = feed_snapshot::load_feed_snapshot(snapshot_path) else {
+            return false;
         };
-        Ok(())
+
+        self.lore_session = LoreSession::merged(self.target_lists.clone(), self.query.clone())
+            .with_snapshot(&feed_snapshot);
+        self.page_number = 1;
+        self.patchset_index = 0;
+        self.fetch_job = None;
+        self.is_feed_exhausted = true;
+        self.stale_since = Some(feed_snapshot.saved_at());
+        self.update_listed_ids();
+        true
+    }
+
+    /// Stops fetching patchsets, keeping the ones already fetched or, if
+    /// there are none, restoring the last snapshot of the feed, if any.
+    pub fn go_offline(&mut self, lore_api_client: BlockingLoreAPIClient) {
+        self.lore_api_client = lore_api_client;
+        if !self.lore_session.representative_patches_ids().is_empty() || !self.restore_snapshot() {
+            self.fetch_job = None;
+            self.is_feed_exhausted = true;
+        }
+    }
+
+    /// Returns a job that fetches the patchsets missing to fill the current
+    /// page. The job works on a copy of the session, which it returns to be
+    /// applied with [LatestPatchsets::finish_fetch].
+    pub fn fetch_current_page_job(&self) -> impl FetchJob {
+        self.fetch_up_to_job(self.page_size * self.page_number)
+    }
+
+    /// Like [LatestPatchsets::fetch_current_page_job], but also fills the
+    /// next page, so it is ready once the user gets there.
+    pub fn fetch_next_page_job(&self) -> impl FetchJob {
+        self.fetch_up_to_job(self.page_size * (self.page_number + 1))
+    }
+
+    /// While filtering, the listed patchsets may still fall short of
+    /// `listed_count` after the job, so it must be repeated until the page
+    /// is filled or no more can be fetched (see
+    /// [LatestPatchsets::is_current_page_missing]).
+    fn fetch_up_to_job(&self, listed_count: usize) -> impl FetchJob {
+        let patchsets_count = self.lore_session.representative_patches_ids().len()
+            + listed_count.saturating_sub(self.processed_patchsets_count());
+        let mut lore_session = self.lore_session.clone();
+        let lore_api_client = self.lore_api_client.clone();
+        let local_source = self.local_source.clone();
+        // Offline fetches only come from cached responses, which may be older
+        // than the snapshot
+        let snapshot_path = self
+            .snapshot_path
+            .clone()
+            .filter(|_| !lore_api_client.is_offline());
+
+        move |_| {
+            let is_feed_exhausted = match &local_source {
+                Some(local_source) => {
+                    fetch_patchsets(&mut lore_session, local_source, patchsets_count)?
+                }
+                None => fetch_patchsets(&mut lore_session, &lore_api_client, patchsets_count)?,
+            };
+            if let Some(snapshot_path) = snapshot_path {
+                if let Err(io_error) =
+                    feed_snapshot::save_feed_snapshot(&lore_session.snapshot(), &snapshot_path)
+                {
+                    Logger::warn(format!("Failed to save feed snapshot: {io_error}"));
+                }
+            }
+            Ok((lore_session, is_feed_exhausted))
+        }
+    }
+
+    /// Whether the patchsets of the current page still need to be fetched.
+    pub fn is_current_page_missing(&self) -> bool {
+        self.processed_patchsets_count() < self.page_size * self.page_number
+            && self.can_fetch_more()
+    }
+
+    /// Whether fetching more patchsets may still list any of them. Besides
+    /// the end of the feed, fetching stops once the patchsets get older than
+    /// the maximum age of the filter or, so a narrow filter doesn't go
+    /// through the whole feed, once too many pages were filtered out.
+    fn can_fetch_more(&self) -> bool {
+        if self.is_feed_exhausted {
+            return false;
+        }
+
+        let fetched_patchsets: Vec<&Patch> = self
+            .lore_session
+            .representative_patches_ids()
+            .iter()
+            .filter_map(|message_id| self.lore_session.get_processed_patch(message_id))
+            .collect();
+        if self
+            .patch_filter
+            .rules_out_older(fetched_patchsets.iter().copied(), Utc::now())
+        {
+            return false;
+        }
+
+        let filtered_out_count = fetched_patchsets.len() - self.processed_patchsets_count();
+        filtered_out_count < self.page_size * self.page_number * MAX_FILTERED_OUT_PAGES_PER_PAGE
+    }
+
+    /// Whether the selection is close enough to the end of the current page
+    /// for the next page to be fetched in advance.
+    pub fn should_prefetch_next_page(&self) -> bool {
+        let current_page_end = self.page_size * self.page_number;
+        let processed_patchsets_count = self.processed_patchsets_count();
+
+        self.fetch_job.is_none()
+            && processed_patchsets_count >= current_page_end
+            && processed_patchsets_count < current_page_end + self.page_size
+            && self.patchset_index + NEXT_PAGE_PREFETCH_DISTANCE >= current_page_end
+            && self.can_fetch_more()
+    }
+
+    pub fn set_fetch_job(&mut self, fetch_job: JobId) {
+        self.fetch_job = Some(fetch_job);
+    }
+
+    /// Applies the session fetched by the job `fetch_job`. Returns `false`,
+    /// leaving the state untouched, if it isn't the current fetch job.
+    pub fn finish_fetch(
+        &mut self,
+        fetch_job: JobId,
+        (lore_session, is_feed_exhausted): FetchedFeed,
+    ) -> bool {
+        if self.fetch_job != Some(fetch_job) {
+            return false;
+        }
+        self.fetch_job = None;
+        self.lore_session = lore_session;
+        self.is_feed_exhausted = is_feed_exhausted;
+        self.update_listed_ids();
+        true
+    }
+
+    /// Forgets the job `fetch_job`, which failed, so the patchsets can be
+    /// fetched again. Returns `false`, leaving the state untouched, if it
+    /// isn't the current fetch job.
+    pub fn fail_fetch(&mut self, fetch_job: JobId) -> bool {
+        if self.fetch_job != Some(fetch_job) {
+            return false;
+        }
+        self.fetch_job = None;
+        true
     }
 
     pub fn select_below_patchset(&mut self) {
-        if self.patchset_index + 1 < self.lore_session.representative_patches_ids().len()
+        self.patchset_index = self.selected_index();
+        if self.patchset_index + 1 < self.processed_patchsets_count()
             && self.patchset_index + 1 < self.page_size * self.page_number
         {
             self.patchset_index += 1;
@@ -58,6 +280,7 @@ impl LatestPatchsets {
     }
 
     pub fn select_above_patchset(&mut self) {
+        self.patchset_index = self.selected_index();
         if self.patchset_index == 0 {
             return;
         }
@@ -67,7 +290,7 @@ impl LatestPatchsets {
     }
 
     pub fn increment_page(&mut self) {
-        let patchsets_processed: usize = self.lore_session.representative_patches_ids().len();
+        let patchsets_processed: usize = self.processed_patchsets_count();
         if self.page_size * self.page_number > patchsets_processed {
             return;
         }
@@ -83,25 +306,161 @@ impl LatestPatchsets {
         self.patchset_index = self.page_size * (&self.page_number - 1);
     }
 
-    pub fn get_selected_patchset(&self) -> Patch {
-        let message_id: &str = self
+    /// Selected patchset, unless the current page is still being fetched or
+    /// has no listed patchsets
+    pub fn get_selected_patchset(&self) -> Option<Patch> {
+        self.listed_ids
+            .get(self.selected_index())
+            .and_then(|message_id| self.lore_session.get_processed_patch(message_id))
+            .cloned()
+    }
+
+    /// Index of the selection, clamped to the listed patchsets of the current
+    /// page, as the listing may change under the selection when it's updated
+    fn selected_index(&self) -> usize {
+        let page_start = self.page_size * (self.page_number - 1);
+        self.patchset_index
+            .min(self.processed_patchsets_count().saturating_sub(1))
+            .max(page_start)
+    }
+
+    pub fn get_current_patch_feed_page(&self) -> Option<Vec<&Patch>> {
+        let lower_end = self.page_size * (self.page_number - 1);
+
+        // Narrow search queries may yield less patchsets than a page
+        if self.listed_ids.len() <= lower_end {
+            return None;
+        }
+        Some(
+            self.listed_ids
+                .iter()
+                .skip(lower_end)
+                .take(self.page_size)
+                .filter_map(|message_id| self.lore_session.get_processed_patch(message_id))
+                .collect(),
+        )
+    }
+
+    /// Fetched patchsets that pass [LatestPatchsets::patch_filter], in
+    /// [LatestPatchsets::order]
+    pub fn listed_patchsets(&self) -> Vec<&Patch> {
+        self.listed_ids
+            .iter()
+            .filter_map(|message_id| self.lore_session.get_processed_patch(message_id))
+            .collect()
+    }
+
+    /// Filters and sorts the fetched patchsets again, as listing them on
+    /// every call would be too slow for rendering.
+    fn update_listed_ids(&mut self) {
+        let now = Utc::now();
+        let mut listed_patchsets: Vec<&Patch> = self
             .lore_session
             .representative_patches_ids()
-            .get(self.patchset_index)
-            .unwrap();
+            .iter()
+            .filter_map(|message_id| self.lore_session.get_processed_patch(message_id))
+            .filter(|patch| self.patch_filter.matches(patch, now))
+            .collect();
+        self.order.sort(&mut listed_patchsets);
+        self.listed_ids = listed_patchsets
+            .into_iter()
+            .map(|patch| patch.message_id().href.clone())
+            .collect();
+    }
 
-        self.lore_session
-            .get_processed_patch(message_id)
-            .unwrap()
-            .clone()
+    /// Selects the first listed patchset after the selected one that isn't
+    /// in `seen_patchsets`, moving to its page. Returns `false`, leaving the
+    /// selection untouched, if there is none among the fetched ones.
+    pub fn select_next_unseen_patchset(&mut self, seen_patchsets: &SeenPatchsets) -> bool {
+        let Some(patchset_index) = self
+            .listed_patchsets()
+            .iter()
+            .enumerate()
+            .skip(self.selected_index() + 1)
+            .find(|(_, patch)| !seen_patchsets.is_seen(patch))
+            .map(|(index, _)| index)
+        else {
+            return false;
+        };
+        self.patchset_index = patchset_index;
+        self.page_number = patchset_index / self.page_size + 1;
+        true
     }
 
-    pub fn get_current_patch_feed_page(&self) -> Option<Vec<&Patch>> {
+    /// Lists only the patchsets that pass `patch_filter`, going back to the
+    /// first page.
+    pub fn set_patch_filter(&mut self, patch_filter: PatchFilter) {
+        self.patch_filter = patch_filter;
+        self.page_number = 1;
+        self.patchset_index = 0;
+        self.update_listed_ids();
+    }
+
+    /// Lists the patchsets in `order`, going back to the first page.
+    pub fn set_order(&mut self, order: PatchOrder) {
+        self.order = order;
+        self.page_number = 1;
+        self.patchset_index = 0;
+        self.update_listed_ids();
+    }
+
+    /// Trees targeted by the fetched patchsets, to filter by
+    pub fn target_trees(&self) -> Vec<String> {
+        patch::target_trees(
+            self.lore_session
+                .representative_patches_ids()
+                .iter()
+                .filter_map(|message_id| self.lore_session.get_processed_patch(message_id)),
+        )
+    }
+
+    /// Parts seen in the feed of the series of the patch `message_id`
+    pub fn series_parts(&self, message_id: &str) -> Option<&SeriesParts> {
+        self.lore_session.series_parts(message_id)
+    }
+
+    /// Lists the patch `message_id` was seen in, if the feeds of several
+    /// lists are merged
+    pub fn merged_lists_of(&self, message_id: &str) -> Option<&[String]> {
         self.lore_session
-            .get_patch_feed_page(self.page_size, self.page_number)
+            .is_merged()
+            .then(|| self.lore_session.lists_of(message_id))
     }
 
+    /// How many of the fetched patchsets are listed
     pub fn processed_patchsets_count(&self) -> usize {
-        self.lore_session.representative_patches_ids().len()
+        self.listed_ids.len()
+    }
+}
+
+/// Session fetched by a [FetchJob] and whether it reached the end of the feed
+pub type FetchedFeed = (LoreSession, bool);
+
+/// Job that fetches patchsets into a copy of the session of [LatestPatchsets]
+pub trait FetchJob:
+    FnOnce(&JobContext) -> color_eyre::Result<FetchedFeed> + Send + 'static
+{
+}
+
+impl<F> FetchJob for F where
+    F: FnOnce(&JobContext) -> color_eyre::Result<FetchedFeed> + Send + 'static
+{
+}
+
+/// Fetches patchsets until `lore_session` has `patchsets_count` of them.
+/// Returns `true` if the end of the feed was reached first.
+fn fetch_patchsets<T: PatchFeedRequest>(
+    lore_session: &mut LoreSession,
+    lore_api_client: &T,
+    patchsets_count: usize,
+) -> color_eyre::Result<bool> {
+    match lore_session.process_n_representative_patches(lore_api_client, patchsets_count) {
+        Ok(()) => Ok(false),
+        Err(LoreSessionError::FromLoreAPIClient(ClientError::EndOfFeed)) => Ok(true),
+        // The error is kept as the source, so callers can tell if the
+        // instance was unreachable
+        Err(lore_session_error) => {
+            Err(Report::new(lore_session_error).wrap_err("Failed to request feed"))
+        }
     }
 }
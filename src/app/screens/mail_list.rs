@@ -1,27 +1,81 @@
-use color_eyre::eyre::bail;
+use crate::app::{is_unreachable, logging::Logger};
+use color_eyre::eyre::Report;
 use patch_hub::lore::{
-    lore_api_client::BlockingLoreAPIClient, lore_session, mailing_list::MailingList,
+    instance::Instance,
+    lei::{LeiSearch, LEI_LIST_PREFIX},
+    local_inbox::LocalInboxes,
+    lore_api_client::BlockingLoreAPIClient,
+    lore_session,
+    mailing_list::{ListPreferences, MailingList, MailingListMatch},
 };
 
 pub struct MailingListSelection {
     pub mailing_lists: Vec<MailingList>,
     pub target_list: String,
+    /// Lists matching [MailingListSelection::target_list], best first
     pub possible_mailing_lists: Vec<MailingList>,
+    /// How each of [MailingListSelection::possible_mailing_lists] matched,
+    /// at the same index
+    pub possible_mailing_list_matches: Vec<MailingListMatch>,
     pub highlighted_list_index: usize,
     pub mailing_lists_path: String,
-    pub lore_api_client: BlockingLoreAPIClient,
+    /// Configured public-inbox instances, with the clients to reach them
+    pub instance_clients: Vec<(Instance, BlockingLoreAPIClient)>,
+    /// Inboxes read from disk, whose lists are offered along the ones of the
+    /// default instance
+    pub local_inboxes: LocalInboxes,
+    /// Searches saved in `lei`, offered as pseudo lists of the default
+    /// instance
+    pub lei_searches: Vec<LeiSearch>,
+    /// Pinned and recently used lists, listed before the others
+    pub list_preferences: ListPreferences,
+    pub list_preferences_path: String,
+    /// Lists marked to have their feeds merged into one
+    pub marked_lists: Vec<MailingList>,
 }
 
 impl MailingListSelection {
+    /// Fetches the catalogue of mailing lists of every instance, bypassing
+    /// cached responses that are still considered fresh. Instances that fail
+    /// keep their previous lists, with a warning, and an error is only
+    /// returned if every instance failed, telling the instances were
+    /// unreachable only if none was reached.
     pub fn refresh_available_mailing_lists(&mut self) -> color_eyre::Result<()> {
-        match lore_session::fetch_available_lists(&self.lore_api_client) {
-            Ok(available_mailing_lists) => {
-                self.mailing_lists = available_mailing_lists;
-            }
-            Err(failed_available_lists_request) => {
-                bail!(format!("{failed_available_lists_request:#?}"));
-            }
-        };
+        let mut mailing_lists = Vec::new();
+        let mut failed_count = 0;
+        let mut last_error: Option<Report> = None;
+        for (instance, lore_api_client) in &self.instance_clients {
+            match lore_session::fetch_available_lists(&lore_api_client.force_refresh()) {
+                Ok(available_mailing_lists) => mailing_lists.extend(
+                    available_mailing_lists
+                        .into_iter()
+                        .map(|mailing_list| mailing_list.with_instance(instance.name())),
+                ),
+                // The error is kept as the source, so callers can tell if the
+                // instance was unreachable
+                Err(failed_available_lists_request) => {
+                    let error = Report::new(failed_available_lists_request)
+                        .wrap_err(format!("Failed to fetch lists of {}", instance.name()));
+                    Logger::warn(format!("{error:#}"));
+                    mailing_lists.extend(
+                        self.mailing_lists
+                            .iter()
+                            .filter(|mailing_list| mailing_list.instance() == instance.name())
+                            .cloned(),
+                    );
+                    failed_count += 1;
+                    // Going offline is only right if no instance was reached
+                    if last_error.as_ref().is_none_or(is_unreachable) {
+                        last_error = Some(error);
+                    }
+                }
+            };
+        }
+        if let Some(error) = last_error.filter(|_| failed_count == self.instance_clients.len()) {
+            return Err(error);
+        }
+        self.mailing_lists = mailing_lists;
+        self.add_local_lists();
 
         self.clear_target_list();
 
@@ -30,6 +84,40 @@ impl MailingListSelection {
         Ok(())
     }
 
+    /// Adds the lists of [MailingListSelection::local_inboxes] that aren't
+    /// available yet and the pseudo lists of
+    /// [MailingListSelection::lei_searches], replacing the previous ones, as
+    /// lists of the default instance, whose links they share.
+    pub fn add_local_lists(&mut self) {
+        self.mailing_lists
+            .retain(|mailing_list| !mailing_list.name().starts_with(LEI_LIST_PREFIX));
+
+        if let Some((default_instance, _)) = self.instance_clients.first() {
+            // Listing local inboxes only reads their descriptions, so it
+            // doesn't fail
+            let local_lists =
+                lore_session::fetch_available_lists(&self.local_inboxes).unwrap_or_default();
+            for local_list in local_lists {
+                let local_list = local_list.with_instance(default_instance.name());
+                let is_available = self.mailing_lists.iter().any(|mailing_list| {
+                    mailing_list.name() == local_list.name()
+                        && mailing_list.instance() == local_list.instance()
+                });
+                if !is_available {
+                    self.mailing_lists.push(local_list);
+                }
+            }
+
+            for lei_search in &self.lei_searches {
+                self.mailing_lists.push(
+                    MailingList::new(lei_search.name(), lei_search.query())
+                        .with_instance(default_instance.name()),
+                );
+            }
+        }
+        self.mailing_lists.sort();
+    }
+
     pub fn remove_last_target_list_char(&mut self) {
         if !self.target_list.is_empty() {
             self.target_list.pop();
@@ -47,16 +135,121 @@ impl MailingListSelection {
         self.process_possible_mailing_lists();
     }
 
-    fn process_possible_mailing_lists(&mut self) {
-        let mut possible_mailing_lists: Vec<MailingList> = Vec::new();
+    /// Pins the highlighted list or, if it's already pinned, unpins it,
+    /// keeping it highlighted.
+    pub fn toggle_favorite_highlighted_list(&mut self) -> std::io::Result<()> {
+        let Some(mailing_list) = self
+            .possible_mailing_lists
+            .get(self.highlighted_list_index)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        self.list_preferences.toggle_favorite(&mailing_list);
+        self.process_possible_mailing_lists();
+        if let Some(list_index) = self
+            .possible_mailing_lists
+            .iter()
+            .position(|possible_list| *possible_list == mailing_list)
+        {
+            self.highlighted_list_index = list_index;
+        }
+
+        lore_session::save_list_preferences(&self.list_preferences, &self.list_preferences_path)
+    }
 
-        for mailing_list in &self.mailing_lists {
-            if mailing_list.name().starts_with(&self.target_list) {
-                possible_mailing_lists.push(mailing_list.clone());
+    /// Marks the highlighted list to have its feed merged with the ones of
+    /// the other marked lists or, if it's already marked, unmarks it.
+    /// Returns `false`, leaving the marks untouched, if the list belongs to
+    /// another instance than the marked ones, as feeds are only merged
+    /// within an instance.
+    pub fn toggle_mark_highlighted_list(&mut self) -> bool {
+        let Some(mailing_list) = self.possible_mailing_lists.get(self.highlighted_list_index)
+        else {
+            return true;
+        };
+        if let Some(marked_index) = self
+            .marked_lists
+            .iter()
+            .position(|marked_list| marked_list == mailing_list)
+        {
+            self.marked_lists.remove(marked_index);
+            return true;
+        }
+        if self
+            .marked_lists
+            .first()
+            .is_some_and(|marked_list| marked_list.instance() != mailing_list.instance())
+        {
+            return false;
+        }
+        self.marked_lists.push(mailing_list.clone());
+        true
+    }
+
+    pub fn is_marked(&self, mailing_list: &MailingList) -> bool {
+        self.marked_lists.contains(mailing_list)
+    }
+
+    /// Records `mailing_list` as the most recently used one.
+    pub fn record_list_use(&mut self, mailing_list: &MailingList) -> std::io::Result<()> {
+        self.list_preferences.record_use(mailing_list);
+        lore_session::save_list_preferences(&self.list_preferences, &self.list_preferences_path)
+    }
+
+    /// Highlights the last used list, clearing the typed text. Returns
+    /// `false` if there is none or it's no longer available.
+    pub fn highlight_last_used_list(&mut self) -> bool {
+        self.clear_target_list();
+        let Some(last_used) = self.list_preferences.last_used() else {
+            return false;
+        };
+        match self
+            .possible_mailing_lists
+            .iter()
+            .position(|mailing_list| last_used.refers_to(mailing_list))
+        {
+            Some(list_index) => {
+                self.highlighted_list_index = list_index;
+                true
             }
+            None => false,
         }
+    }
+
+    /// Fuzzily matches the lists against [MailingListSelection::target_list],
+    /// ranking them by score. Before anything is typed, pinned and recently
+    /// used lists come first, in the order of [ListPreferences::pinned_rank],
+    /// so they don't outrank better matches afterwards. Ties keep the lists
+    /// sorted by name.
+    fn process_possible_mailing_lists(&mut self) {
+        let mut possible_mailing_lists: Vec<(&MailingList, MailingListMatch)> = self
+            .mailing_lists
+            .iter()
+            .filter_map(|mailing_list| {
+                mailing_list
+                    .fuzzy_match(&self.target_list)
+                    .map(|list_match| (mailing_list, list_match))
+            })
+            .collect();
+        let is_pattern_empty = self.target_list.is_empty();
+        possible_mailing_lists.sort_by_key(|(mailing_list, list_match)| {
+            (
+                self.list_preferences
+                    .pinned_rank(mailing_list)
+                    .filter(|_| is_pattern_empty)
+                    .unwrap_or(usize::MAX),
+                std::cmp::Reverse(list_match.score()),
+            )
+        });
 
-        self.possible_mailing_lists = possible_mailing_lists;
+        (
+            self.possible_mailing_lists,
+            self.possible_mailing_list_matches,
+        ) = possible_mailing_lists
+            .into_iter()
+            .map(|(mailing_list, list_match)| (mailing_list.clone(), list_match))
+            .unzip();
         self.highlighted_list_index = 0;
     }
 
@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fmt::Display};
+
+use color_eyre::eyre::bail;
+use derive_getters::Getters;
+use patch_hub::lore::{query::LoreQuery, watch::Watch};
+
+/// Default inbox used for searches, which aggregates every list archived on
+/// the public-inbox instance
+const DEFAULT_SEARCH_LIST: &str = "all";
+
+#[derive(Debug, Getters)]
+pub struct Search {
+    #[getter(skip)]
+    fields_buffer: HashMap<SearchField, String>,
+    highlighted: usize,
+    is_editing: bool,
+    curr_edit: String,
+    /// Feedback of the last search, like a query without results
+    status: Option<String>,
+}
+
+impl Search {
+    pub fn new(target_list: &str, instance: &str) -> Self {
+        let mut fields_buffer = HashMap::new();
+        for i in 0..SEARCH_FIELDS_COUNT {
+            fields_buffer.insert(SearchField::try_from(i).unwrap(), String::new());
+        }
+
+        let target_list = if target_list.is_empty() {
+            DEFAULT_SEARCH_LIST
+        } else {
+            target_list
+        };
+        fields_buffer.insert(SearchField::TargetList, target_list.to_string());
+        fields_buffer.insert(SearchField::Instance, instance.to_string());
+
+        Search {
+            fields_buffer,
+            highlighted: 0,
+            is_editing: false,
+            curr_edit: String::new(),
+            status: None,
+        }
+    }
+
+    /// Get the number of fields in the search form
+    pub fn field_count(&self) -> usize {
+        self.fields_buffer.len()
+    }
+
+    /// Get the label and value of the field at the given index
+    pub fn field(&self, i: usize) -> (String, String) {
+        let search_field = SearchField::try_from(i).unwrap();
+        let value = self.fields_buffer.get(&search_field).unwrap();
+        (search_field.to_string(), value.clone())
+    }
+
+    /// Toggle editing mode
+    pub fn toggle_editing(&mut self) {
+        if !self.is_editing {
+            let search_field = SearchField::try_from(self.highlighted).unwrap();
+            if let Some(value) = self.fields_buffer.get(&search_field) {
+                self.curr_edit = value.clone();
+            }
+        }
+        self.is_editing = !self.is_editing;
+    }
+
+    /// Move the highlight to the previous field
+    pub fn highlight_prev(&mut self) {
+        if self.highlighted > 0 {
+            self.highlighted -= 1;
+        }
+    }
+
+    /// Move the highlight to the next field
+    pub fn highlight_next(&mut self) {
+        if self.highlighted + 1 < self.fields_buffer.len() {
+            self.highlighted += 1;
+        }
+    }
+
+    /// Remove the last char from the current editing value if not empty
+    pub fn backspace_edit(&mut self) {
+        self.curr_edit.pop();
+    }
+
+    /// Appends a new char to the current editing value
+    pub fn append_edit(&mut self, ch: char) {
+        self.curr_edit.push(ch);
+    }
+
+    /// Clear the current editing value
+    pub fn clear_edit(&mut self) {
+        self.curr_edit.clear();
+    }
+
+    /// Push the current edit value to the fields buffer
+    pub fn stage_edit(&mut self) {
+        let search_field = SearchField::try_from(self.highlighted).unwrap();
+        self.fields_buffer
+            .insert(search_field, std::mem::take(&mut self.curr_edit));
+    }
+
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.status = status;
+    }
+
+    /// Target list of the search, falling back to the `all` inbox when blank
+    pub fn target_list(&self) -> String {
+        let target_list = self
+            .fields_buffer
+            .get(&SearchField::TargetList)
+            .map(|value| value.trim())
+            .unwrap_or_default();
+
+        if target_list.is_empty() {
+            DEFAULT_SEARCH_LIST.to_string()
+        } else {
+            target_list.to_string()
+        }
+    }
+
+    /// Name of the public-inbox instance to search, if not blank
+    pub fn instance(&self) -> Option<String> {
+        self.fields_buffer
+            .get(&SearchField::Instance)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Builds a [`Watch`] over `instance` of the current search, named after
+    /// the watch name field or, if blank, after the query
+    pub fn watch(&self, instance: &str) -> Watch {
+        let query = self.build_query();
+        let name = self
+            .fields_buffer
+            .get(&SearchField::WatchName)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| format!("{query} in {}", self.target_list()));
+
+        Watch::new(&name, &self.target_list(), instance, query)
+    }
+
+    /// Builds the [`LoreQuery`] described by the current field values
+    pub fn build_query(&self) -> LoreQuery {
+        let value = |search_field: SearchField| {
+            self.fields_buffer
+                .get(&search_field)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        LoreQuery {
+            author: value(SearchField::Author),
+            subject: value(SearchField::Subject),
+            touched_file: value(SearchField::TouchedFile),
+            date_since: value(SearchField::DateSince),
+            date_until: value(SearchField::DateUntil),
+            cc: value(SearchField::Cc),
+            free_text: value(SearchField::FreeText),
+        }
+    }
+}
+
+const SEARCH_FIELDS_COUNT: usize = 10;
+
+#[derive(Debug, Hash, Eq, PartialEq)]
+enum SearchField {
+    TargetList,
+    Instance,
+    Author,
+    Subject,
+    TouchedFile,
+    DateSince,
+    DateUntil,
+    Cc,
+    FreeText,
+    WatchName,
+}
+
+impl TryFrom<usize> for SearchField {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SearchField::TargetList),
+            1 => Ok(SearchField::Instance),
+            2 => Ok(SearchField::Author),
+            3 => Ok(SearchField::Subject),
+            4 => Ok(SearchField::TouchedFile),
+            5 => Ok(SearchField::DateSince),
+            6 => Ok(SearchField::DateUntil),
+            7 => Ok(SearchField::Cc),
+            8 => Ok(SearchField::FreeText),
+            9 => Ok(SearchField::WatchName),
+            _ => bail!("Invalid index {} for SearchField", value),
+        }
+    }
+}
+
+impl Display for SearchField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchField::TargetList => write!(f, "Mailing List"),
+            SearchField::Instance => write!(f, "Instance"),
+            SearchField::Author => write!(f, "Author (f:)"),
+            SearchField::Subject => write!(f, "Subject (s:)"),
+            SearchField::TouchedFile => write!(f, "Touched File (dfn:)"),
+            SearchField::DateSince => write!(f, "Since (e.g. 2024-01-01, 2.weeks.ago)"),
+            SearchField::DateUntil => write!(f, "Until"),
+            SearchField::Cc => write!(f, "Cc (c:)"),
+            SearchField::FreeText => write!(f, "Free Text"),
+            SearchField::WatchName => write!(f, "Watch Name (to save with w)"),
+        }
+    }
+}
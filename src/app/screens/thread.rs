@@ -0,0 +1,113 @@
+use patch_hub::lore::thread::{Thread, ThreadMessage};
+
+/// State of the threaded conversation of a patchset.
+pub struct ThreadView {
+    pub thread: Thread,
+    /// Messages in display order, as pairs of message index and depth in the
+    /// reply tree
+    pub flattened_messages: Vec<(usize, usize)>,
+    /// Index of the selected entry of `flattened_messages`
+    pub selected_index: usize,
+    pub body_scroll_offset: usize,
+    /// If true, consecutive quoted lines are collapsed into a single line
+    pub fold_quotes: bool,
+}
+
+impl ThreadView {
+    pub fn new(thread: Thread) -> Self {
+        let flattened_messages = thread.flatten();
+
+        ThreadView {
+            thread,
+            flattened_messages,
+            selected_index: 0,
+            body_scroll_offset: 0,
+            fold_quotes: true,
+        }
+    }
+
+    pub fn select_next_message(&mut self) {
+        if self.selected_index + 1 < self.flattened_messages.len() {
+            self.selected_index += 1;
+            self.body_scroll_offset = 0;
+        }
+    }
+
+    pub fn select_previous_message(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+            self.body_scroll_offset = 0;
+        }
+    }
+
+    /// Scroll `n` lines down
+    pub fn body_scroll_down(&mut self, n: usize) {
+        let number_of_lines = self.selected_message_lines().len();
+        if (self.body_scroll_offset + n) <= number_of_lines {
+            self.body_scroll_offset += n;
+        }
+    }
+
+    /// Scroll `n` lines up
+    pub fn body_scroll_up(&mut self, n: usize) {
+        self.body_scroll_offset = self.body_scroll_offset.saturating_sub(n);
+    }
+
+    pub fn toggle_quote_folding(&mut self) {
+        self.fold_quotes = !self.fold_quotes;
+        self.body_scroll_offset = 0;
+    }
+
+    /// Returns the selected message, if the thread has any message.
+    pub fn selected_message(&self) -> Option<&ThreadMessage> {
+        self.flattened_messages
+            .get(self.selected_index)
+            .map(|&(index, _)| &self.thread.messages()[index])
+    }
+
+    /// Lines of the selected message body, with quoted text folded if
+    /// [ThreadView::fold_quotes] is set.
+    pub fn selected_message_lines(&self) -> Vec<String> {
+        let Some(message) = self.selected_message() else {
+            return Vec::new();
+        };
+
+        if self.fold_quotes {
+            fold_quoted_lines(message.body())
+        } else {
+            message.body().lines().map(str::to_string).collect()
+        }
+    }
+}
+
+/// Collapses each block of consecutive quoted lines (those starting with `>`)
+/// into a single line telling how many lines were folded.
+fn fold_quoted_lines(body: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut quoted_lines_count = 0;
+
+    for line in body.lines() {
+        if line.starts_with('>') {
+            quoted_lines_count += 1;
+            continue;
+        }
+        if quoted_lines_count > 0 {
+            lines.push(folded_quote_line(quoted_lines_count));
+            quoted_lines_count = 0;
+        }
+        lines.push(line.to_string());
+    }
+
+    if quoted_lines_count > 0 {
+        lines.push(folded_quote_line(quoted_lines_count));
+    }
+
+    lines
+}
+
+fn folded_quote_line(quoted_lines_count: usize) -> String {
+    match quoted_lines_count {
+        1 => "> [1 quoted line folded]".to_string(),
+        n => format!("> [{n} quoted lines folded]"),
+    }
+}
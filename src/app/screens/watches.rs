@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use patch_hub::lore::watch::{self, NewPatchsets, Watch};
+
+use crate::app::jobs::JobId;
+
+pub struct Watches {
+    pub watches: Vec<Watch>,
+    pub watch_index: usize,
+    pub watches_path: String,
+    /// New patchsets of each watch, by name, once counted
+    pub new_patchsets: HashMap<String, NewPatchsets>,
+    /// Job counting the new patchsets of the watches, if any
+    pub count_job: Option<JobId>,
+    /// Whether the latest patchsets being browsed are the results of a watch
+    pub is_watch_open: bool,
+}
+
+impl Watches {
+    pub fn select_below_watch(&mut self) {
+        if self.watch_index + 1 < self.watches.len() {
+            self.watch_index += 1;
+        }
+    }
+
+    pub fn select_above_watch(&mut self) {
+        self.watch_index = self.watch_index.saturating_sub(1);
+    }
+
+    pub fn selected_watch(&self) -> Option<&Watch> {
+        self.watches.get(self.watch_index)
+    }
+
+    /// Adds `new_watch`, replacing the watch of the same name, if any.
+    pub fn add_watch(&mut self, new_watch: Watch) -> std::io::Result<()> {
+        match self
+            .watches
+            .iter_mut()
+            .find(|watch| watch.name() == new_watch.name())
+        {
+            Some(watch) => *watch = new_watch,
+            None => self.watches.push(new_watch),
+        }
+        watch::save_watches(&self.watches, &self.watches_path)
+    }
+
+    pub fn remove_selected_watch(&mut self) -> std::io::Result<()> {
+        if self.watch_index >= self.watches.len() {
+            return Ok(());
+        }
+        let removed_watch = self.watches.remove(self.watch_index);
+        self.new_patchsets.remove(removed_watch.name());
+        self.watch_index = self.watch_index.min(self.watches.len().saturating_sub(1));
+        watch::save_watches(&self.watches, &self.watches_path)
+    }
+
+    /// Records the newest patchset counted for the selected watch as seen, so
+    /// only the ones sent after it are new on the next visit.
+    pub fn mark_selected_watch_seen(&mut self) -> std::io::Result<()> {
+        let Some(watch) = self.watches.get_mut(self.watch_index) else {
+            return Ok(());
+        };
+        if let Some(new_patchsets) = self.new_patchsets.get(watch.name()) {
+            watch.mark_seen(new_patchsets.newest().clone());
+        }
+        watch::save_watches(&self.watches, &self.watches_path)
+    }
+}
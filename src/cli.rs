@@ -15,6 +15,9 @@ pub struct Cli {
     #[clap(short = 'c', long, action)]
     /// Prints the current configurations to the terminal with the applied overrides
     pub show_configs: bool,
+    #[clap(short = 'o', long, action)]
+    /// Starts offline, browsing only the feeds and patchsets saved before, without sending requests
+    pub offline: bool,
 }
 
 impl Cli {
@@ -40,6 +43,11 @@ impl Cli {
             return ControlFlow::Break(Ok(()));
         }
 
+        if self.offline {
+            Logger::info("Starting offline");
+            app.set_offline(true);
+        }
+
         ControlFlow::Continue(terminal)
     }
 }
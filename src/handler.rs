@@ -3,6 +3,9 @@ pub mod details_actions;
 pub mod edit_config;
 pub mod latest;
 pub mod mail_list;
+pub mod search;
+pub mod thread;
+pub mod watches;
 
 use std::{
     ops::ControlFlow,
@@ -10,13 +13,12 @@ use std::{
 };
 
 use crate::{
-    app::{logging::Logger, screens::CurrentScreen, App},
+    app::{screens::CurrentScreen, App},
     loading_screen,
     ui::draw_ui,
 };
 
 use bookmarked::handle_bookmarked_patchsets;
-use color_eyre::eyre::bail;
 use details_actions::handle_patchset_details;
 use edit_config::handle_edit_config;
 use latest::handle_latest_patchsets;
@@ -26,6 +28,12 @@ use ratatui::{
     prelude::Backend,
     Terminal,
 };
+use search::handle_search;
+use thread::handle_thread;
+use watches::handle_watches;
+
+/// How often the UI is refreshed while there is work happening in background
+const JOBS_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
 
 fn key_handling<B>(
     mut terminal: Terminal<B>,
@@ -50,7 +58,7 @@ where
                 return handle_bookmarked_patchsets(app, key, terminal);
             }
             CurrentScreen::PatchsetDetails => {
-                handle_patchset_details(app, key, &mut terminal)?;
+                return handle_patchset_details(app, key, terminal);
             }
             CurrentScreen::EditConfig => {
                 handle_edit_config(app, key)?;
@@ -58,6 +66,15 @@ where
             CurrentScreen::LatestPatchsets => {
                 return handle_latest_patchsets(app, key, terminal);
             }
+            CurrentScreen::Search => {
+                return handle_search(app, key, terminal);
+            }
+            CurrentScreen::PatchsetThread => {
+                handle_thread(app, key, &mut terminal)?;
+            }
+            CurrentScreen::Watches => {
+                return handle_watches(app, key, terminal);
+            }
         }
     }
     Ok(ControlFlow::Continue(terminal))
@@ -69,26 +86,12 @@ where
 {
     match app.current_screen {
         CurrentScreen::MailingListSelection => {
-            if app.mailing_list_selection.mailing_lists.is_empty() {
+            if app.mailing_list_selection.mailing_lists.is_empty() && !app.is_offline {
                 terminal = loading_screen! {
                     terminal, "Fetching mailing lists" => {
-                        app.mailing_list_selection.refresh_available_mailing_lists()?;
-                    }
-                };
-            }
-        }
-        CurrentScreen::LatestPatchsets => {
-            let patchsets_state = app.latest_patchsets.as_mut().unwrap();
-            let target_list = patchsets_state.target_list().to_string();
-            if patchsets_state.processed_patchsets_count() == 0 {
-                terminal = loading_screen! {
-                    terminal,
-                    format!("Fetching patchsets from {}", target_list) => {
-                        patchsets_state.fetch_current_page()?;
+                        app.refresh_available_mailing_lists()?;
                     }
                 };
-
-                app.mailing_list_selection.clear_target_list();
             }
         }
         CurrentScreen::BookmarkedPatchsets => {
@@ -99,6 +102,8 @@ where
         _ => {}
     }
 
+    app.prefetch();
+
     Ok(terminal)
 }
 
@@ -106,21 +111,22 @@ pub fn run_app<B>(mut terminal: Terminal<B>, mut app: App) -> color_eyre::Result
 where
     B: Backend + Send + 'static,
 {
-    if !app.check_external_deps() {
-        Logger::error("patch-hub cannot be executed because some dependencies are missing");
-        bail!("patch-hub cannot be executed because some dependencies are missing, check logs for more information");
-    }
+    app.check_external_deps();
 
     loop {
+        app.apply_finished_jobs();
+
         terminal = logic_handling(terminal, &mut app)?;
 
         terminal.draw(|f| draw_ui(f, &app))?;
 
-        // *IMPORTANT*: Uncommenting the if below makes `patch-hub` not block
-        // until an event is captured.  We should only do it when (if ever) we
-        // need to refresh the UI independently of any event as doing so gravely
-        // hinders the performance to below acceptable.
-        // if event::poll(Duration::from_millis(16))? {
+        // *IMPORTANT*: Only poll for events while there is work happening in
+        // background, so progress, results and prefetches happen without a
+        // key press. Otherwise, block until an event is captured, as polling
+        // all the time gravely hinders the performance to below acceptable.
+        if app.has_pending_work() && !event::poll(JOBS_REFRESH_INTERVAL)? {
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Release {
                 continue;
@@ -130,7 +136,6 @@ where
                 ControlFlow::Break(_) => return Ok(()),
             }
         }
-        // }
     }
 }
 
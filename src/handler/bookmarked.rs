@@ -2,9 +2,9 @@ use std::ops::ControlFlow;
 
 use crate::{
     app::{screens::CurrentScreen, App},
-    loading_screen,
     ui::popup::{help::HelpPopUpBuilder, PopUp},
 };
+use patch_hub::lore::patch;
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent},
     prelude::Backend,
@@ -14,7 +14,7 @@ use ratatui::{
 pub fn handle_bookmarked_patchsets<B>(
     app: &mut App,
     key: KeyEvent,
-    mut terminal: Terminal<B>,
+    terminal: Terminal<B>,
 ) -> color_eyre::Result<ControlFlow<(), Terminal<B>>>
 where
     B: Backend + Send + 'static,
@@ -25,6 +25,7 @@ where
             app.popup = Some(popup);
         }
         KeyCode::Esc => {
+            app.cancel_details_actions_job();
             app.bookmarked_patchsets.patchset_index = 0;
             app.set_current_screen(CurrentScreen::MailingListSelection);
         }
@@ -34,14 +35,46 @@ where
         KeyCode::Char('k') | KeyCode::Up => {
             app.bookmarked_patchsets.select_above_patchset();
         }
-        KeyCode::Enter => {
-            terminal = loading_screen! {
-                terminal,
-                "Loading patchset" => {
-                    app.init_details_actions()?;
-                    app.set_current_screen(CurrentScreen::PatchsetDetails);
-                }
+        KeyCode::Char('r') => {
+            let mut patch_filter = app.bookmarked_patchsets.patch_filter.clone();
+            patch_filter.hide_rfc = !patch_filter.hide_rfc;
+            app.bookmarked_patchsets.set_patch_filter(patch_filter);
+        }
+        KeyCode::Char('t') => {
+            let mut patch_filter = app.bookmarked_patchsets.patch_filter.clone();
+            patch_filter.cycle_target_tree(&patch::target_trees(
+                &app.bookmarked_patchsets.bookmarked_patchsets,
+            ));
+            app.bookmarked_patchsets.set_patch_filter(patch_filter);
+        }
+        KeyCode::Char('s') => {
+            let mut patch_filter = app.bookmarked_patchsets.patch_filter.clone();
+            patch_filter.subsystem = match patch_filter.subsystem {
+                Some(_) => None,
+                None => app
+                    .bookmarked_patchsets
+                    .get_selected_patchset()
+                    .and_then(|patch| patch.tag().subsystem().clone()),
             };
+            app.bookmarked_patchsets.set_patch_filter(patch_filter);
+        }
+        KeyCode::Char('d') => {
+            let mut patch_filter = app.bookmarked_patchsets.patch_filter.clone();
+            patch_filter.cycle_max_age();
+            app.bookmarked_patchsets.set_patch_filter(patch_filter);
+        }
+        KeyCode::Char('m') => {
+            app.mark_listed_patchsets_seen();
+        }
+        KeyCode::Char('u') => {
+            app.select_next_unseen_patchset();
+        }
+        KeyCode::Char('o') => {
+            let order = app.bookmarked_patchsets.order.next();
+            app.bookmarked_patchsets.set_order(order);
+        }
+        KeyCode::Enter => {
+            app.init_details_actions()?;
         }
         _ => {}
     }
@@ -57,6 +90,13 @@ pub fn generate_help_popup() -> Box<dyn PopUp> {
         .keybind("?", "Show this help screen")
         .keybind("j/🡇", "Down")
         .keybind("k/🡅", "Up")
+        .keybind("r", "Hide or show RFCs")
+        .keybind("t", "Show only the patchsets for the next tree (e.g. net-next)")
+        .keybind("s", "Show only the subsystem of the selected patchset, or every one")
+        .keybind("d", "Show only the patchsets updated in the last day, week or month")
+        .keybind("o", "Sort patchsets by date")
+        .keybind("m", "Mark the listed patchsets as read")
+        .keybind("u", "Jump to the next unread patchset")
         .build();
 
     Box::new(popup)
@@ -1,7 +1,8 @@
-use std::time::Duration;
+use std::{ops::ControlFlow, time::Duration};
 
 use crate::{
     app::{screens::CurrentScreen, App},
+    loading_screen,
     ui::popup::{help::HelpPopUpBuilder, PopUp},
     utils,
 };
@@ -13,18 +14,21 @@ use ratatui::{
 
 use super::wait_key_press;
 
-pub fn handle_patchset_details<B: Backend>(
+pub fn handle_patchset_details<B>(
     app: &mut App,
     key: KeyEvent,
-    terminal: &mut Terminal<B>,
-) -> color_eyre::Result<()> {
+    mut terminal: Terminal<B>,
+) -> color_eyre::Result<ControlFlow<(), Terminal<B>>>
+where
+    B: Backend + Send + 'static,
+{
     let patchset_details_and_actions = app.details_actions.as_mut().unwrap();
 
     if key.modifiers.contains(KeyModifiers::SHIFT) {
         if let KeyCode::Char('G') = key.code {
             patchset_details_and_actions.go_to_last_line()
         }
-        return Ok(());
+        return Ok(ControlFlow::Continue(terminal));
     }
 
     if key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -45,7 +49,7 @@ pub fn handle_patchset_details<B: Backend>(
             }
             _ => {}
         }
-        return Ok(());
+        return Ok(ControlFlow::Continue(terminal));
     }
 
     match key.code {
@@ -55,6 +59,7 @@ pub fn handle_patchset_details<B: Backend>(
         }
         KeyCode::Esc => {
             let ps_da_clone = patchset_details_and_actions.last_screen.clone();
+            app.cancel_details_actions_job();
             app.set_current_screen(ps_da_clone);
             app.reset_details_actions();
         }
@@ -93,9 +98,34 @@ pub fn handle_patchset_details<B: Backend>(
         KeyCode::Char('r') => {
             patchset_details_and_actions.toggle_reply_with_reviewed_by_action();
         }
+        KeyCode::Char('[') => {
+            app.jump_to_series_version(false);
+        }
+        KeyCode::Char(']') => {
+            app.jump_to_series_version(true);
+        }
+        KeyCode::Char('c') => {
+            app.toggle_range_diff();
+        }
+        KeyCode::Char('a') => {
+            patchset_details_and_actions.toggle_apply_action();
+        }
+        KeyCode::Char('o') => {
+            app.open_prerequisite();
+        }
+        KeyCode::Char('t') => {
+            terminal = loading_screen! {
+                terminal,
+                "Loading thread" => {
+                    if app.init_thread_view()? {
+                        app.set_current_screen(CurrentScreen::PatchsetThread);
+                    }
+                }
+            };
+        }
         KeyCode::Enter => {
             if patchset_details_and_actions.actions_require_user_io() {
-                utils::setup_user_io(terminal)?;
+                utils::setup_user_io(&mut terminal)?;
                 app.consolidate_patchset_actions()?;
                 println!("\nPress ENTER continue...");
                 loop {
@@ -105,7 +135,7 @@ pub fn handle_patchset_details<B: Backend>(
                         }
                     }
                 }
-                utils::teardown_user_io(terminal)?;
+                utils::teardown_user_io(&mut terminal)?;
             } else {
                 app.consolidate_patchset_actions()?;
             }
@@ -113,13 +143,13 @@ pub fn handle_patchset_details<B: Backend>(
         }
         _ => {}
     }
-    Ok(())
+    Ok(ControlFlow::Continue(terminal))
 }
 
 pub fn generate_help_popup() -> Box<dyn PopUp> {
     let popup = HelpPopUpBuilder::new()
         .title("Patchset Details and Actions")
-        .description("This screen displays the details of a patchset and allows you to perform actions on it.\nA series of actions are available to you, they are:\n - Bookmark: Save the patchset for later\n - Reply with Reviewed-by: Reply to the patchset with a Reviewed-by tag")
+        .description("This screen displays the details of a patchset and allows you to perform actions on it.\nA series of actions are available to you, they are:\n - Bookmark: Save the patchset for later\n - Reply with Reviewed-by: Reply to the patchset with a Reviewed-by tag\n - Apply: Apply the patchset with `git am` to the repository patch-hub runs in, on top of its base-commit, if known")
         .keybind("ESC", "Exit")
         .keybind("ENTER", "Consolidate marked actions")
         .keybind("?", "Show this help screen")
@@ -135,6 +165,12 @@ pub fn generate_help_popup() -> Box<dyn PopUp> {
         .keybind("p", "Preview previous patch")
         .keybind("b", "Toggle bookmark action")
         .keybind("r", "Toggle reply with Reviewed-by action")
+        .keybind("a", "Toggle apply action")
+        .keybind("t", "View the patchset thread")
+        .keybind("[", "Open the previous version of the series")
+        .keybind("]", "Open the next version of the series")
+        .keybind("c", "Toggle the range-diff against the previous version")
+        .keybind("o", "Open the first prerequisite series found")
         .build();
 
     Box::new(popup)
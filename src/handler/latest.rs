@@ -2,7 +2,6 @@ use std::ops::ControlFlow;
 
 use crate::{
     app::{screens::CurrentScreen, App},
-    loading_screen,
     ui::popup::{help::HelpPopUpBuilder, PopUp},
 };
 use ratatui::{
@@ -14,7 +13,7 @@ use ratatui::{
 pub fn handle_latest_patchsets<B>(
     app: &mut App,
     key: KeyEvent,
-    mut terminal: Terminal<B>,
+    terminal: Terminal<B>,
 ) -> color_eyre::Result<ControlFlow<(), Terminal<B>>>
 where
     B: Backend + Send + 'static,
@@ -27,8 +26,17 @@ where
             app.popup = Some(popup);
         }
         KeyCode::Esc => {
+            app.cancel_details_actions_job();
             app.reset_latest_patchsets();
-            app.set_current_screen(CurrentScreen::MailingListSelection);
+            if app.search.is_some() {
+                app.set_current_screen(CurrentScreen::Search);
+            } else if app.watches.is_watch_open {
+                app.watches.is_watch_open = false;
+                app.count_new_watched_patchsets();
+                app.set_current_screen(CurrentScreen::Watches);
+            } else {
+                app.set_current_screen(CurrentScreen::MailingListSelection);
+            }
         }
         KeyCode::Char('j') | KeyCode::Down => {
             latest_patchsets.select_below_patchset();
@@ -37,26 +45,57 @@ where
             latest_patchsets.select_above_patchset();
         }
         KeyCode::Char('l') | KeyCode::Right => {
-            let list_name = latest_patchsets.target_list().to_string();
-            terminal = loading_screen! {
-                terminal,
-                format!("Fetching patchsets from {}", list_name) => {
-                    latest_patchsets.increment_page();
-                    latest_patchsets.fetch_current_page()?;
-                }
-            };
+            latest_patchsets.increment_page();
+            app.fetch_latest_patchsets_page();
         }
         KeyCode::Char('h') | KeyCode::Left => {
             latest_patchsets.decrement_page();
         }
-        KeyCode::Enter => {
-            terminal = loading_screen! {
-                terminal,
-                "Loading patchset" => {
-                    app.init_details_actions()?;
-                    app.set_current_screen(CurrentScreen::PatchsetDetails);
-                }
+        KeyCode::Char('r') => {
+            let mut patch_filter = latest_patchsets.patch_filter().clone();
+            patch_filter.hide_rfc = !patch_filter.hide_rfc;
+            latest_patchsets.set_patch_filter(patch_filter);
+            app.fetch_latest_patchsets_page();
+        }
+        KeyCode::Char('t') => {
+            let mut patch_filter = latest_patchsets.patch_filter().clone();
+            patch_filter.cycle_target_tree(&latest_patchsets.target_trees());
+            latest_patchsets.set_patch_filter(patch_filter);
+            app.fetch_latest_patchsets_page();
+        }
+        KeyCode::Char('s') => {
+            let mut patch_filter = latest_patchsets.patch_filter().clone();
+            patch_filter.subsystem = match patch_filter.subsystem {
+                Some(_) => None,
+                // The current page may still be being fetched
+                None => latest_patchsets
+                    .get_selected_patchset()
+                    .and_then(|patch| patch.tag().subsystem().clone()),
             };
+            latest_patchsets.set_patch_filter(patch_filter);
+            app.fetch_latest_patchsets_page();
+        }
+        KeyCode::Char('d') => {
+            let mut patch_filter = latest_patchsets.patch_filter().clone();
+            patch_filter.cycle_max_age();
+            latest_patchsets.set_patch_filter(patch_filter);
+            app.fetch_latest_patchsets_page();
+        }
+        KeyCode::Char('m') => {
+            app.mark_listed_patchsets_seen();
+        }
+        KeyCode::Char('u') => {
+            app.select_next_unseen_patchset();
+        }
+        KeyCode::Char('o') => {
+            latest_patchsets.set_order(latest_patchsets.order().next());
+        }
+        KeyCode::F(5) => {
+            app.cancel_details_actions_job();
+            app.refresh_latest_patchsets();
+        }
+        KeyCode::Enter => {
+            app.init_details_actions()?;
         }
         _ => {}
     }
@@ -74,6 +113,14 @@ pub fn generate_help_popup() -> Box<dyn PopUp> {
         .keybind("k/🡅", "Up")
         .keybind("l/🡆", "Next page")
         .keybind("h/🡄", "Previous page")
+        .keybind("r", "Hide or show RFCs")
+        .keybind("t", "Show only the patchsets for the next tree (e.g. net-next)")
+        .keybind("s", "Show only the subsystem of the selected patchset, or every one")
+        .keybind("d", "Show only the patchsets updated in the last day, week or month")
+        .keybind("o", "Sort fetched patchsets by date")
+        .keybind("m", "Mark the fetched patchsets as read")
+        .keybind("u", "Jump to the next unread patchset")
+        .keybind("F5", "Refresh patchsets (goes back online)")
         .build();
     Box::new(popup)
 }
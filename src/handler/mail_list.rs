@@ -1,6 +1,7 @@
 use std::ops::ControlFlow;
 
 use crate::{
+    app::logging::Logger,
     app::{screens::CurrentScreen, App},
     loading_screen,
     ui::popup::{help::HelpPopUpBuilder, PopUp},
@@ -25,31 +26,23 @@ where
             app.popup = Some(popup);
         }
         KeyCode::Enter => {
-            if app.mailing_list_selection.has_valid_target_list() {
+            if !app.mailing_list_selection.marked_lists.is_empty() {
+                app.init_merged_latest_patchsets();
+                app.fetch_latest_patchsets_page();
+                app.mailing_list_selection.clear_target_list();
+                app.set_current_screen(CurrentScreen::LatestPatchsets);
+            } else if app.mailing_list_selection.has_valid_target_list() {
                 app.init_latest_patchsets();
-                let list_name = app
-                    .latest_patchsets
-                    .as_ref()
-                    .unwrap()
-                    .target_list()
-                    .to_string();
-
-                terminal = loading_screen! {
-                    terminal,
-                    format!("Fetching patchsets from {}", list_name) => {
-                        app.latest_patchsets.as_mut().unwrap().fetch_current_page()?;
-                        app.mailing_list_selection.clear_target_list();
-                        app.set_current_screen(CurrentScreen::LatestPatchsets);
-                    }
-                };
+                app.fetch_latest_patchsets_page();
+                app.mailing_list_selection.clear_target_list();
+                app.set_current_screen(CurrentScreen::LatestPatchsets);
             }
         }
         KeyCode::F(5) => {
             terminal = loading_screen! {
                 terminal,
                 "Refreshing lists" => {
-                    app.mailing_list_selection
-                        .refresh_available_mailing_lists()?;
+                    app.refresh_available_mailing_lists()?;
                 }
             };
         }
@@ -57,12 +50,33 @@ where
             app.init_edit_config();
             app.set_current_screen(CurrentScreen::EditConfig);
         }
+        KeyCode::F(3) => {
+            app.init_search();
+            app.mailing_list_selection.clear_target_list();
+            app.set_current_screen(CurrentScreen::Search);
+        }
+        KeyCode::F(6) => {
+            app.mailing_list_selection.clear_target_list();
+            app.count_new_watched_patchsets();
+            app.set_current_screen(CurrentScreen::Watches);
+        }
         KeyCode::F(1) => {
             if !app.bookmarked_patchsets.bookmarked_patchsets.is_empty() {
                 app.mailing_list_selection.clear_target_list();
                 app.set_current_screen(CurrentScreen::BookmarkedPatchsets);
             }
         }
+        KeyCode::Tab => {
+            app.toggle_mark_highlighted_list();
+        }
+        KeyCode::F(4) => {
+            if let Err(io_error) = app
+                .mailing_list_selection
+                .toggle_favorite_highlighted_list()
+            {
+                Logger::warn(format!("Failed to save list preferences: {io_error}"));
+            }
+        }
         KeyCode::Backspace => {
             app.mailing_list_selection.remove_last_target_list_char();
         }
@@ -87,15 +101,19 @@ where
 pub fn generate_help_popup() -> Box<dyn PopUp> {
     let popup = HelpPopUpBuilder::new()
         .title("Mailing List Selection")
-        .description("This is the mailing list selection screen.\nYou can select a mailing list by typing the name of the list.")
+        .description("This is the mailing list selection screen.\nYou can select a mailing list by typing parts of its name or description.\nPinned (★) and recently used (↺) lists are shown first.")
         .keybind("ESC", "Exit")
-        .keybind("ENTER", "Open the selected mailing list")
+        .keybind("ENTER", "Open the selected mailing list, or the marked ones merged")
+        .keybind("TAB", "Mark or unmark the selected list to be merged")
         .keybind("?", "Show this help screen")
         .keybind("🡇", "Down")
         .keybind("🡅", "Up")
         .keybind("F1", "Show bookmarked patchsets")
         .keybind("F2", "Edit config options")
-        .keybind("F5", "Refresh lists")
+        .keybind("F3", "Search patchsets")
+        .keybind("F4", "Pin or unpin the selected list")
+        .keybind("F5", "Refresh lists (goes back online)")
+        .keybind("F6", "Show watches")
         .build();
 
     Box::new(popup)
@@ -0,0 +1,93 @@
+use std::ops::ControlFlow;
+
+use crate::{
+    app::{screens::CurrentScreen, App},
+    ui::popup::{help::HelpPopUpBuilder, PopUp},
+};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    prelude::Backend,
+    Terminal,
+};
+
+pub fn handle_search<B>(
+    app: &mut App,
+    key: KeyEvent,
+    terminal: Terminal<B>,
+) -> color_eyre::Result<ControlFlow<(), Terminal<B>>>
+where
+    B: Backend + Send + 'static,
+{
+    let search_state = app.search.as_mut().unwrap();
+
+    match search_state.is_editing() {
+        true => match key.code {
+            KeyCode::Esc => {
+                search_state.clear_edit();
+                search_state.toggle_editing();
+            }
+            KeyCode::Backspace => {
+                search_state.backspace_edit();
+            }
+            KeyCode::Char(ch) => {
+                search_state.append_edit(ch);
+            }
+            KeyCode::Enter => {
+                search_state.stage_edit();
+                search_state.clear_edit();
+                search_state.toggle_editing();
+            }
+            _ => {}
+        },
+        false => match key.code {
+            KeyCode::Char('?') => {
+                let popup = generate_help_popup();
+                app.popup = Some(popup);
+            }
+            KeyCode::Esc => {
+                app.reset_latest_patchsets();
+                app.reset_search();
+                app.set_current_screen(CurrentScreen::MailingListSelection);
+            }
+            KeyCode::Char('e') => {
+                search_state.toggle_editing();
+            }
+            KeyCode::Char('w') => {
+                app.save_search_as_watch();
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                search_state.highlight_next();
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                search_state.highlight_prev();
+            }
+            KeyCode::Enter => {
+                // The search screen is kept until the first page arrives, see
+                // `App::finish_latest_patchsets_fetch`
+                search_state.set_status(None);
+                app.reset_latest_patchsets();
+                if app.init_latest_patchsets_from_search() {
+                    app.fetch_latest_patchsets_page();
+                }
+            }
+            _ => {}
+        },
+    }
+    Ok(ControlFlow::Continue(terminal))
+}
+
+pub fn generate_help_popup() -> Box<dyn PopUp> {
+    let popup = HelpPopUpBuilder::new()
+        .title("Search")
+        .description("This screen allows you to search patchsets on a mailing list.\nEach filled field narrows the results and blank fields are ignored.\nThe free text field accepts any public-inbox search syntax.")
+        .keybind("ESC", "Exit")
+        .keybind("ENTER", "Run the search")
+        .keybind("?", "Show this help screen")
+        .keybind("j/🡇", "Down")
+        .keybind("k/🡅", "Up")
+        .keybind("e", "Toggle editing for a search field")
+        .keybind("w", "Save the search as a watch, to track its new patchsets")
+        .build();
+
+    Box::new(popup)
+}
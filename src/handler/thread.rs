@@ -0,0 +1,75 @@
+use crate::{
+    app::{screens::CurrentScreen, App},
+    ui::popup::{help::HelpPopUpBuilder, PopUp},
+};
+use ratatui::{
+    backend::Backend,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    Terminal,
+};
+
+pub fn handle_thread<B: Backend>(
+    app: &mut App,
+    key: KeyEvent,
+    terminal: &mut Terminal<B>,
+) -> color_eyre::Result<()> {
+    let thread_view = app.thread_view.as_mut().unwrap();
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        let terminal_height = terminal.size().unwrap().height as usize;
+        match key.code {
+            KeyCode::Char('u') => {
+                thread_view.body_scroll_up(terminal_height / 2);
+            }
+            KeyCode::Char('d') => {
+                thread_view.body_scroll_down(terminal_height / 2);
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    match key.code {
+        KeyCode::Char('?') => {
+            let popup = generate_help_popup();
+            app.popup = Some(popup);
+        }
+        KeyCode::Esc => {
+            app.reset_thread_view();
+            app.set_current_screen(CurrentScreen::PatchsetDetails);
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            thread_view.body_scroll_down(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            thread_view.body_scroll_up(1);
+        }
+        KeyCode::Char('n') => {
+            thread_view.select_next_message();
+        }
+        KeyCode::Char('p') => {
+            thread_view.select_previous_message();
+        }
+        KeyCode::Char('z') => {
+            thread_view.toggle_quote_folding();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn generate_help_popup() -> Box<dyn PopUp> {
+    let popup = HelpPopUpBuilder::new()
+        .title("Patchset Thread")
+        .description("This screen displays the whole conversation of a patchset as a reply tree.\nQuoted text is folded by default, so you can focus on what each reply adds.")
+        .keybind("ESC", "Return to the patchset details")
+        .keybind("?", "Show this help screen")
+        .keybind("j/🡇", "Scroll down")
+        .keybind("k/🡅", "Scroll up")
+        .keybind("n", "Next message")
+        .keybind("p", "Previous message")
+        .keybind("z", "Toggle quoted text folding")
+        .build();
+
+    Box::new(popup)
+}
@@ -0,0 +1,72 @@
+use std::ops::ControlFlow;
+
+use crate::{
+    app::{logging::Logger, screens::CurrentScreen, App},
+    ui::popup::{help::HelpPopUpBuilder, PopUp},
+};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    prelude::Backend,
+    Terminal,
+};
+
+pub fn handle_watches<B>(
+    app: &mut App,
+    key: KeyEvent,
+    terminal: Terminal<B>,
+) -> color_eyre::Result<ControlFlow<(), Terminal<B>>>
+where
+    B: Backend + Send + 'static,
+{
+    match key.code {
+        KeyCode::Char('?') => {
+            let popup = generate_help_popup();
+            app.popup = Some(popup);
+        }
+        KeyCode::Esc => {
+            if let Some(count_job) = app.watches.count_job.take() {
+                app.jobs.cancel(count_job);
+            }
+            app.set_current_screen(CurrentScreen::MailingListSelection);
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.watches.select_below_watch();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.watches.select_above_watch();
+        }
+        KeyCode::Char('d') => {
+            if let Err(io_error) = app.watches.remove_selected_watch() {
+                Logger::error(format!("Failed to save watches: {io_error}"));
+            }
+        }
+        KeyCode::F(5) => {
+            app.count_new_watched_patchsets();
+        }
+        KeyCode::Enter => {
+            app.reset_latest_patchsets();
+            if app.init_latest_patchsets_from_watch() {
+                app.fetch_latest_patchsets_page();
+                app.set_current_screen(CurrentScreen::LatestPatchsets);
+            }
+        }
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(terminal))
+}
+
+pub fn generate_help_popup() -> Box<dyn PopUp> {
+    let popup = HelpPopUpBuilder::new()
+        .title("Watches")
+        .description("This screen lists the saved searches, with how many patchsets matched them since the last visit.\nSearches are saved as watches from the search screen.\nOpening a watch marks its patchsets as seen.")
+        .keybind("ESC", "Exit")
+        .keybind("ENTER", "Open the patchsets of the selected watch")
+        .keybind("?", "Show this help screen")
+        .keybind("j/🡇", "Down")
+        .keybind("k/🡅", "Up")
+        .keybind("d", "Delete the selected watch")
+        .keybind("F5", "Count the new patchsets again")
+        .build();
+
+    Box::new(popup)
+}
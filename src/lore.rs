@@ -1,4 +1,24 @@
+pub mod atom;
+pub mod feed_snapshot;
+pub mod fuzzy;
+pub mod http_agent;
+pub mod http_cache;
+pub mod instance;
+pub mod json_file;
+pub mod lei;
+pub mod local_inbox;
+pub mod local_source;
 pub mod lore_api_client;
 pub mod lore_session;
 pub mod mailing_list;
+pub mod mbox;
 pub mod patch;
+pub mod query;
+pub mod range_diff;
+pub mod request_policy;
+pub mod seen;
+pub mod series;
+pub mod thread;
+pub mod timestamp;
+pub mod trailers;
+pub mod watch;
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests;
+
+/// An entry of an Atom feed, with the fields lore sets and
+/// [super::patch::Patch] reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedEntry {
+    pub title: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Date of the message in RFC 3339, like `2024-07-18T16:49:00Z`
+    pub updated: String,
+    /// URL of the message
+    pub href: String,
+    /// URL of the message this one replies to, if any
+    pub in_reply_to_href: Option<String>,
+}
+
+/// Renders `entries` as an Atom feed in the same layout as the feeds of lore,
+/// so feeds built from other sources are processed like the ones requested
+/// from lore.
+pub fn render_feed(entries: &[FeedEntry]) -> String {
+    let mut feed = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+        <feed xmlns=\"http://www.w3.org/2005/Atom\" \
+        xmlns:thr=\"http://purl.org/syndication/thread/1.0\">",
+    );
+
+    for entry in entries {
+        feed.push_str(&format!(
+            "<entry><author><name>{}</name><email>{}</email></author>\
+            <title>{}</title><updated>{}</updated><link href=\"{}\" />",
+            escape_xml(&entry.author_name),
+            escape_xml(&entry.author_email),
+            escape_xml(&entry.title),
+            escape_xml(&entry.updated),
+            escape_xml(&entry.href),
+        ));
+        if let Some(in_reply_to_href) = &entry.in_reply_to_href {
+            feed.push_str(&format!(
+                "<thr:in-reply-to href=\"{}\" />",
+                escape_xml(in_reply_to_href)
+            ));
+        }
+        feed.push_str("</entry>");
+    }
+    feed.push_str("</feed>");
+
+    feed
+}
+
+/// Escapes the characters with special meaning in XML text and attributes.
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
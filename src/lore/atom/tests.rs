@@ -0,0 +1,46 @@
+use serde_xml_rs::from_str;
+
+use super::*;
+use crate::lore::patch::PatchFeed;
+
+#[test]
+fn should_render_feed_readable_as_patch_feed() {
+    let entries = vec![
+        FeedEntry {
+            title: "[PATCH 1/1] file: Fix <something> & more".to_string(),
+            author_name: "Foo \"Bar\"".to_string(),
+            author_email: "foo@bar.foo.bar".to_string(),
+            updated: "2024-07-18T16:49:00Z".to_string(),
+            href: "https://lore.kernel.org/some-list/patch@foo.bar/".to_string(),
+            in_reply_to_href: Some("https://lore.kernel.org/some-list/cover@foo.bar/".to_string()),
+        },
+        FeedEntry {
+            title: "[PATCH 0/1] file: Fix something".to_string(),
+            author_name: "Foo Bar".to_string(),
+            author_email: "foo@bar.foo.bar".to_string(),
+            updated: "2024-07-18T16:48:00Z".to_string(),
+            href: "https://lore.kernel.org/some-list/cover@foo.bar/".to_string(),
+            in_reply_to_href: None,
+        },
+    ];
+
+    let patch_feed: PatchFeed = from_str(&render_feed(&entries)).unwrap();
+    let patches = patch_feed.patches();
+
+    assert_eq!(2, patches.len());
+    assert_eq!(
+        "[PATCH 1/1] file: Fix <something> & more",
+        patches[0].title()
+    );
+    assert_eq!("Foo \"Bar\"", patches[0].author().name);
+    assert_eq!(
+        &crate::lore::timestamp::parse_timestamp("2024-07-18T16:49:00Z"),
+        patches[0].updated()
+    );
+    assert_eq!("patch@foo.bar", patches[0].message_id().id());
+    assert_eq!(
+        "cover@foo.bar",
+        patches[0].in_reply_to().as_ref().unwrap().id()
+    );
+    assert_eq!(&None, patches[1].in_reply_to());
+}
@@ -0,0 +1,57 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::{json_file, patch::Patch};
+
+#[cfg(test)]
+mod tests;
+
+/// The representative patches of a feed, as last fetched, saved so the feed
+/// can still be browsed when its instance can't be reached.
+#[derive(Getters, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeedSnapshot {
+    /// When the snapshot was taken, in seconds since the Unix epoch
+    saved_at: u64,
+    representative_patches: Vec<Patch>,
+    /// Lists each representative patch was seen in, for feeds merging
+    /// several lists
+    #[serde(default)]
+    lists: HashMap<String, Vec<String>>,
+}
+
+impl FeedSnapshot {
+    /// Takes a snapshot of `representative_patches`, in feed order.
+    pub fn new(representative_patches: Vec<Patch>) -> FeedSnapshot {
+        FeedSnapshot {
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            representative_patches,
+            lists: HashMap::new(),
+        }
+    }
+
+    /// Records the lists each representative patch, by message-id, was seen
+    /// in.
+    pub fn with_lists(mut self, lists: HashMap<String, Vec<String>>) -> FeedSnapshot {
+        self.lists = lists;
+        self
+    }
+}
+
+pub fn save_feed_snapshot(feed_snapshot: &FeedSnapshot, filepath: &str) -> io::Result<()> {
+    json_file::save_json_atomically(filepath, feed_snapshot)
+}
+
+pub fn load_feed_snapshot(filepath: &str) -> io::Result<FeedSnapshot> {
+    let feed_snapshot_file = File::open(filepath)?;
+    let feed_snapshot = serde_json::from_reader(feed_snapshot_file)?;
+    Ok(feed_snapshot)
+}
@@ -0,0 +1,32 @@
+use super::*;
+use crate::lore::patch::{Author, MessageID};
+use std::{fs, path::Path};
+
+#[test]
+fn should_save_and_load_feed_snapshots() {
+    let filepath = std::env::temp_dir().join("patch_hub_feed_snapshot_test/list.json");
+    let filepath = filepath.to_str().unwrap();
+    let _ = fs::remove_file(filepath);
+
+    let feed_snapshot = FeedSnapshot::new(vec![Patch::new(
+        "[PATCH] file: Fix something".to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.foo.bar".to_string(),
+        },
+        MessageID {
+            href: "http://lore.kernel.org/some-list/1234@foo.bar/".to_string(),
+        },
+        None,
+        "2024-07-18T16:49:00Z".to_string(),
+    )]);
+    save_feed_snapshot(&feed_snapshot, filepath).unwrap();
+
+    assert_eq!(feed_snapshot, load_feed_snapshot(filepath).unwrap());
+    let is_tmp_file_left = fs::read_dir(Path::new(filepath).parent().unwrap())
+        .unwrap()
+        .any(|dir_entry| dir_entry.unwrap().path().extension() == Some("tmp".as_ref()));
+    assert!(!is_tmp_file_left, "Temporary file should be renamed");
+
+    fs::remove_file(filepath).unwrap();
+}
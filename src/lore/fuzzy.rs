@@ -0,0 +1,134 @@
+use derive_getters::Getters;
+
+#[cfg(test)]
+mod tests;
+
+/// Score of each matched character
+const MATCH_SCORE: i64 = 16;
+/// Extra score of characters matched at the start of a word, like the `i`
+/// of `linux-i2c`
+const BOUNDARY_BONUS: i64 = 8;
+/// Extra score of characters matched right after the previous one
+const CONSECUTIVE_BONUS: i64 = 8;
+/// Penalty of each character skipped between two matched ones
+const GAP_PENALTY: i64 = 1;
+
+/// Where a pattern was found in a text by [fuzzy_match] and how well it fits
+#[derive(Getters, Debug, Clone, PartialEq, Eq, Default)]
+pub struct FuzzyMatch {
+    score: i64,
+    /// Positions, in characters, of the characters of the text matched by
+    /// the pattern, in increasing order
+    indices: Vec<usize>,
+}
+
+/// Matches the characters of `pattern`, in order but not necessarily
+/// adjacent, against `text`, ignoring case and whitespace in the pattern.
+/// Among the possible alignments, picks the one with the best score, which
+/// favours characters matched consecutively or at the start of words.
+/// Returns `None` if `pattern` isn't a subsequence of `text`. An empty
+/// pattern matches anything with a score of zero.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(fold_case)
+        .collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let text: Vec<char> = text.chars().collect();
+    if text.len() < pattern.len() {
+        return None;
+    }
+
+    let char_scores: Vec<i64> = (0..text.len())
+        .map(|index| {
+            let is_boundary = index == 0 || !text[index - 1].is_alphanumeric();
+            MATCH_SCORE + if is_boundary { BOUNDARY_BONUS } else { 0 }
+        })
+        .collect();
+    let text: Vec<char> = text.into_iter().map(fold_case).collect();
+
+    // `scores[i][j]` is the best score of matching `pattern[..=i]` with
+    // `pattern[i]` at `text[j]`, reached from `pattern[i - 1]` at
+    // `previous[i][j]`
+    let mut scores: Vec<Vec<Option<i64>>> = Vec::with_capacity(pattern.len());
+    let mut previous: Vec<Vec<usize>> = Vec::with_capacity(pattern.len());
+    for (i, pattern_ch) in pattern.iter().enumerate() {
+        let mut row = vec![None; text.len()];
+        let mut row_previous = vec![0; text.len()];
+        // Best score, with its position, of the previous pattern character
+        // matched before `j - 1`, already penalized for the gap up to `j`
+        let mut gapped: Option<(i64, usize)> = None;
+
+        for (j, text_ch) in text.iter().enumerate() {
+            let reached_from = if i == 0 {
+                Some((0, 0))
+            } else if j == 0 {
+                None
+            } else {
+                let upper_row = &scores[i - 1];
+                if j >= 2 {
+                    let candidate = upper_row[j - 2].map(|score| (score - GAP_PENALTY, j - 2));
+                    gapped = max_score(
+                        gapped.map(|(score, index)| (score - GAP_PENALTY, index)),
+                        candidate,
+                    );
+                }
+                let consecutive = upper_row[j - 1].map(|score| (score + CONSECUTIVE_BONUS, j - 1));
+                max_score(consecutive, gapped)
+            };
+
+            if text_ch == pattern_ch {
+                if let Some((score, index)) = reached_from {
+                    row[j] = Some(score + char_scores[j]);
+                    row_previous[j] = index;
+                }
+            }
+        }
+        scores.push(row);
+        previous.push(row_previous);
+    }
+
+    let last_row = scores.last()?;
+    let (score, mut index) = last_row
+        .iter()
+        .enumerate()
+        .filter_map(|(index, score)| score.map(|score| (score, index)))
+        // The first of the best ends, so ties favour earlier matches
+        .fold(
+            None,
+            |best: Option<(i64, usize)>, (score, index)| match best {
+                Some((best_score, _)) if best_score >= score => best,
+                _ => Some((score, index)),
+            },
+        )?;
+
+    let mut indices = vec![0; pattern.len()];
+    for i in (0..pattern.len()).rev() {
+        indices[i] = index;
+        index = previous[i][index];
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+impl FuzzyMatch {
+    pub fn into_indices(self) -> Vec<usize> {
+        self.indices
+    }
+}
+
+fn fold_case(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
+
+/// The one with the higher score, favouring `a` on ties
+fn max_score(a: Option<(i64, usize)>, b: Option<(i64, usize)>) -> Option<(i64, usize)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn should_match_subsequences_ignoring_case() {
+    let fuzzy_match_result = fuzzy_match("I2C", "linux-i2c").unwrap();
+    assert_eq!(&vec![6, 7, 8], fuzzy_match_result.indices());
+
+    let fuzzy_match_result = fuzzy_match("rst", "Rust for Linux").unwrap();
+    assert_eq!(&vec![0, 2, 3], fuzzy_match_result.indices());
+
+    assert_eq!(None, fuzzy_match("i2c", "linux-iio"));
+    assert_eq!(None, fuzzy_match("cba", "abc"));
+    assert_eq!(None, fuzzy_match("rust", "rus"));
+}
+
+#[test]
+fn empty_pattern_should_match_anything() {
+    assert_eq!(Some(FuzzyMatch::default()), fuzzy_match("", "linux-i2c"));
+    assert_eq!(Some(FuzzyMatch::default()), fuzzy_match(" ", ""));
+}
+
+#[test]
+fn should_ignore_whitespace_in_pattern() {
+    let fuzzy_match_result = fuzzy_match("rust linux", "rust-for-linux").unwrap();
+    assert_eq!(
+        &vec![0, 1, 2, 3, 9, 10, 11, 12, 13],
+        fuzzy_match_result.indices()
+    );
+}
+
+#[test]
+fn should_prefer_consecutive_and_word_start_matches() {
+    // The greedy alignment would take the `i` and `c` of `linux-iio-...`
+    let fuzzy_match_result = fuzzy_match("i2c", "linux-iio-i2c").unwrap();
+    assert_eq!(&vec![10, 11, 12], fuzzy_match_result.indices());
+
+    let fuzzy_match_result = fuzzy_match("nf", "net-netfilter").unwrap();
+    assert_eq!(&vec![4, 7], fuzzy_match_result.indices());
+
+    let word_start = fuzzy_match("bpf", "bpf").unwrap();
+    let scattered = fuzzy_match("bpf", "b-p-f-extra").unwrap();
+    let inside_word = fuzzy_match("bpf", "xbxpxf").unwrap();
+    assert!(word_start.score() > scattered.score());
+    assert!(scattered.score() > inside_word.score());
+}
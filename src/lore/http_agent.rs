@@ -0,0 +1,171 @@
+use std::{fs, time::Duration};
+
+use thiserror::Error;
+use ureq::{
+    http::Uri,
+    tls::{self, Certificate, ClientCert, PemItem, PrivateKey, RootCerts, TlsConfig},
+    Agent, Proxy,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How long to wait for a connection to be established, by default
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a whole request may take, by default
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Error, Debug)]
+pub enum AgentError {
+    #[error("Invalid proxy URL {0}")]
+    InvalidProxy(String),
+
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid PEM file {path}: {source}")]
+    InvalidPem { path: String, source: ureq::Error },
+
+    #[error("No certificate found in {0}")]
+    NoCertificate(String),
+
+    #[error("No private key found in {0}")]
+    NoPrivateKey(String),
+}
+
+/// Network options of the agent that sends the requests to public-inbox
+/// instances.
+#[derive(Debug, Clone)]
+pub struct AgentOptions {
+    /// URL of the HTTP(S) or SOCKS proxy. If `None`, the proxy set in the
+    /// `ALL_PROXY`, `HTTPS_PROXY` or `HTTP_PROXY` environment variables, if
+    /// any, is used
+    pub proxy: Option<String>,
+    /// PEM file with root certificates to trust in addition to the bundled
+    /// Mozilla root certificates
+    pub ca_certs_path: Option<String>,
+    /// PEM file with the certificate chain to authenticate as client
+    pub client_cert_path: Option<String>,
+    /// PEM file with the private key of the client certificate. If `None`,
+    /// the key is read from `client_cert_path`
+    pub client_key_path: Option<String>,
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+}
+
+impl Default for AgentOptions {
+    fn default() -> Self {
+        AgentOptions {
+            proxy: None,
+            ca_certs_path: None,
+            client_cert_path: None,
+            client_key_path: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// Builds an agent that sends requests as set in `options`.
+///
+/// # Errors
+///
+/// Returns an error if the proxy URL is invalid or if any of the certificate
+/// files can't be read or doesn't have the expected contents.
+pub fn build_agent(options: &AgentOptions) -> Result<Agent, AgentError> {
+    let mut tls_config = TlsConfig::builder();
+    if let Some(ca_certs_path) = &options.ca_certs_path {
+        let (ca_certs, _) = read_pem(ca_certs_path)?;
+        if ca_certs.is_empty() {
+            return Err(AgentError::NoCertificate(ca_certs_path.clone()));
+        }
+        tls_config = tls_config.root_certs(with_bundled_root_certs(ca_certs));
+    }
+    if let Some(client_cert_path) = &options.client_cert_path {
+        tls_config = tls_config.client_cert(Some(read_client_cert(
+            client_cert_path,
+            options.client_key_path.as_deref(),
+        )?));
+    }
+
+    let mut config = Agent::config_builder()
+        .user_agent(Some(format!(
+            "kworkflow/patch-hub/{}",
+            env!("CARGO_PKG_VERSION")
+        )))
+        .timeout_connect(Some(options.connect_timeout))
+        .timeout_per_call(Some(options.request_timeout))
+        .tls_config(tls_config.build());
+    if let Some(proxy) = &options.proxy {
+        config = config.proxy(Some(parse_proxy(proxy)?));
+    }
+
+    Ok(config.build().into())
+}
+
+/// Root certificates made of the bundled Mozilla ones and `ca_certs`, as
+/// setting specific root certificates replaces the bundled ones.
+fn with_bundled_root_certs(ca_certs: Vec<Certificate<'static>>) -> RootCerts {
+    webpki_root_certs::TLS_SERVER_ROOT_CERTS
+        .iter()
+        .map(|root_cert| Certificate::from_der(root_cert.as_ref()))
+        .chain(ca_certs)
+        .into()
+}
+
+fn parse_proxy(proxy: &str) -> Result<Proxy, AgentError> {
+    // `Proxy::new` panics on malformed URIs, so they are rejected beforehand
+    if proxy.parse::<Uri>().is_err() {
+        return Err(AgentError::InvalidProxy(proxy.to_string()));
+    }
+    Proxy::new(proxy).map_err(|_| AgentError::InvalidProxy(proxy.to_string()))
+}
+
+fn read_client_cert(cert_path: &str, key_path: Option<&str>) -> Result<ClientCert, AgentError> {
+    let (cert_chain, cert_file_key) = read_pem(cert_path)?;
+    if cert_chain.is_empty() {
+        return Err(AgentError::NoCertificate(cert_path.to_string()));
+    }
+
+    let (key, key_path) = match key_path {
+        Some(key_path) => (read_pem(key_path)?.1, key_path),
+        None => (cert_file_key, cert_path),
+    };
+    let key = key.ok_or_else(|| AgentError::NoPrivateKey(key_path.to_string()))?;
+
+    Ok(ClientCert::new_with_certs(&cert_chain, key))
+}
+
+/// Reads the certificates and the first private key in the PEM file at
+/// `path`.
+fn read_pem(
+    path: &str,
+) -> Result<(Vec<Certificate<'static>>, Option<PrivateKey<'static>>), AgentError> {
+    let pem = fs::read(path).map_err(|source| AgentError::Read {
+        path: path.to_string(),
+        source,
+    })?;
+
+    let mut certs = Vec::new();
+    let mut key = None;
+    for pem_item in tls::parse_pem(&pem) {
+        match pem_item {
+            Ok(PemItem::Certificate(cert)) => certs.push(cert.to_owned()),
+            Ok(PemItem::PrivateKey(private_key)) => {
+                key.get_or_insert_with(|| private_key.to_owned());
+            }
+            Ok(_) => {}
+            Err(source) => {
+                return Err(AgentError::InvalidPem {
+                    path: path.to_string(),
+                    source,
+                })
+            }
+        }
+    }
+
+    Ok((certs, key))
+}
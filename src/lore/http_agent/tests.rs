@@ -0,0 +1,82 @@
+use super::*;
+
+const CERT_PATH: &str = "src/test_samples/http_agent/cert.pem";
+const KEY_PATH: &str = "src/test_samples/http_agent/key.pem";
+const CERT_AND_KEY_PATH: &str = "src/test_samples/http_agent/cert_and_key.pem";
+
+#[test]
+fn should_build_agent_with_proxy_and_certificates() {
+    let options = AgentOptions {
+        proxy: Some("http://proxy.example.com:3128".to_string()),
+        ca_certs_path: Some(CERT_PATH.to_string()),
+        client_cert_path: Some(CERT_PATH.to_string()),
+        client_key_path: Some(KEY_PATH.to_string()),
+        ..Default::default()
+    };
+    assert!(build_agent(&options).is_ok());
+
+    let options = AgentOptions {
+        client_cert_path: Some(CERT_AND_KEY_PATH.to_string()),
+        ..Default::default()
+    };
+    assert!(
+        build_agent(&options).is_ok(),
+        "Key should be read from the certificate file if no key file is set"
+    );
+}
+
+#[test]
+fn should_fail_to_build_agent_with_invalid_options() {
+    let options = AgentOptions {
+        proxy: Some("http://[invalid".to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        build_agent(&options),
+        Err(AgentError::InvalidProxy(_))
+    ));
+
+    let options = AgentOptions {
+        ca_certs_path: Some("src/test_samples/http_agent/missing.pem".to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        build_agent(&options),
+        Err(AgentError::Read { .. })
+    ));
+
+    let options = AgentOptions {
+        ca_certs_path: Some(KEY_PATH.to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        build_agent(&options),
+        Err(AgentError::NoCertificate(_))
+    ));
+
+    let options = AgentOptions {
+        client_cert_path: Some(CERT_PATH.to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        build_agent(&options),
+        Err(AgentError::NoPrivateKey(_))
+    ));
+}
+
+#[test]
+fn should_trust_ca_certs_in_addition_to_bundled_ones() {
+    let (ca_certs, _) = read_pem(CERT_PATH).unwrap();
+
+    let RootCerts::Specific(root_certs) = with_bundled_root_certs(ca_certs.clone()) else {
+        panic!("Root certificates should be specific");
+    };
+
+    assert_eq!(
+        webpki_root_certs::TLS_SERVER_ROOT_CERTS.len() + ca_certs.len(),
+        root_certs.len()
+    );
+    assert!(root_certs
+        .iter()
+        .any(|root_cert| root_cert.der() == ca_certs[0].der()));
+}
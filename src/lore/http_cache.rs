@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::json_file;
+
+#[cfg(test)]
+mod tests;
+
+/// Response to a GET request as stored in the [HttpCache].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CachedResponse {
+    pub url: String,
+    /// Value of the `ETag` header of the response, if any
+    pub etag: Option<String>,
+    /// Value of the `Last-Modified` header of the response, if any
+    pub last_modified: Option<String>,
+    /// When the response was last fetched or revalidated, in seconds since
+    /// the Unix epoch
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+impl CachedResponse {
+    pub fn new(
+        url: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        body: String,
+    ) -> CachedResponse {
+        CachedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            fetched_at: now(),
+            body,
+        }
+    }
+
+    /// Marks the response as just revalidated with the server.
+    pub fn touch(&mut self) {
+        self.fetched_at = now();
+    }
+}
+
+/// On-disk cache of responses to GET requests, keyed by URL.
+///
+/// Responses younger than `ttl` are considered fresh and can be used without
+/// contacting the server. Older ones should be revalidated with a conditional
+/// request using their `ETag` and `Last-Modified` values.
+#[derive(Clone, Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> HttpCache {
+        HttpCache {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// Returns the cached response to `url`, if any.
+    pub fn load(&self, url: &str) -> Option<CachedResponse> {
+        let file_contents = fs::read_to_string(self.entry_path(url)).ok()?;
+        let cached_response: CachedResponse = serde_json::from_str(&file_contents).ok()?;
+
+        // Different URLs may hash to the same entry
+        if cached_response.url != url {
+            return None;
+        }
+        Some(cached_response)
+    }
+
+    pub fn store(&self, cached_response: &CachedResponse) -> io::Result<()> {
+        json_file::save_json_atomically(self.entry_path(&cached_response.url), cached_response)
+    }
+
+    pub fn is_fresh(&self, cached_response: &CachedResponse) -> bool {
+        now().saturating_sub(cached_response.fetched_at) < self.ttl.as_secs()
+    }
+
+    /// Removes the files of the cache directory that aren't entries, like
+    /// the ones named by the hash used by older versions or temporary files
+    /// left by interrupted writes.
+    pub fn remove_unknown_files(&self) -> io::Result<()> {
+        let dir_entries = match fs::read_dir(&self.dir) {
+            Ok(dir_entries) => dir_entries,
+            Err(io_error) if io_error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(io_error) => return Err(io_error),
+        };
+
+        for dir_entry in dir_entries {
+            let path = dir_entry?.path();
+            let is_entry = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(|file_name| file_name.strip_suffix(".json"))
+                .is_some_and(|hash| {
+                    hash.len() == 64 && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+                });
+            if !is_entry && path.is_file() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Entries are named by the SHA-256 of their URL, which, unlike the
+    /// hashers of the standard library, is stable across Rust releases.
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let hash: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.dir.join(format!("{hash}.json"))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+#[test]
+fn should_store_and_load_responses_by_url() {
+    let cache_dir = std::env::temp_dir().join("patch_hub_http_cache_store_test");
+    let _ = fs::remove_dir_all(&cache_dir);
+    let http_cache = HttpCache::new(&cache_dir, Duration::from_secs(60));
+
+    let cached_response = CachedResponse::new(
+        "https://lore.kernel.org/all/",
+        Some("\"etag\"".to_string()),
+        None,
+        "body".to_string(),
+    );
+    http_cache.store(&cached_response).unwrap();
+
+    assert_eq!(
+        Some(cached_response),
+        http_cache.load("https://lore.kernel.org/all/")
+    );
+    assert_eq!(None, http_cache.load("https://lore.kernel.org/other/"));
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn should_expire_responses_older_than_ttl() {
+    let http_cache = HttpCache::new("/unused", Duration::from_secs(60));
+    let mut cached_response = CachedResponse::new("url", None, None, String::new());
+
+    assert!(http_cache.is_fresh(&cached_response));
+
+    cached_response.fetched_at -= 60;
+    assert!(!http_cache.is_fresh(&cached_response));
+
+    cached_response.touch();
+    assert!(http_cache.is_fresh(&cached_response));
+    assert!(
+        !HttpCache::new("/unused", Duration::ZERO).is_fresh(&cached_response),
+        "Nothing should be fresh without a TTL"
+    );
+}
+
+#[test]
+fn should_name_entries_by_stable_hash_and_remove_unknown_files() {
+    let cache_dir = std::env::temp_dir().join("patch_hub_http_cache_cleanup_test");
+    let _ = fs::remove_dir_all(&cache_dir);
+    let http_cache = HttpCache::new(&cache_dir, Duration::from_secs(60));
+
+    http_cache
+        .store(&CachedResponse::new("url", None, None, "body".to_string()))
+        .unwrap();
+    let entry_path =
+        cache_dir.join("28e5ebabd9d8f6e237df63da2b503785093f0229241bc7021198f63c43b93269.json");
+    fs::write(cache_dir.join("0123456789abcdef.json"), "{}").unwrap();
+    fs::write(cache_dir.join("entry.json.1-2.tmp"), "{").unwrap();
+
+    http_cache.remove_unknown_files().unwrap();
+
+    let mut file_names: Vec<String> = fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|dir_entry| dir_entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    file_names.sort();
+    assert_eq!(
+        vec![entry_path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()],
+        file_names
+    );
+    assert!(http_cache.load("url").is_some());
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
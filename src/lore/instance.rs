@@ -0,0 +1,84 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// Name of the instance used when none is configured
+pub const DEFAULT_INSTANCE_NAME: &str = "lore";
+/// URL of the instance used when none is configured
+pub const DEFAULT_INSTANCE_URL: &str = "https://lore.kernel.org";
+
+/// A public-inbox instance, like lore.kernel.org or a mirror of it.
+#[derive(Getters, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Instance {
+    /// Short name that identifies the instance to the user and in the caches
+    name: String,
+    /// Base URL of the instance, without trailing slash
+    url: String,
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Instance::new(DEFAULT_INSTANCE_NAME, DEFAULT_INSTANCE_URL)
+    }
+}
+
+impl Instance {
+    pub fn new(name: &str, url: &str) -> Instance {
+        Instance {
+            name: name.to_string(),
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Whether `message_url`, like the href of a message-id from a feed, is
+    /// from this instance. The scheme is ignored, as feeds may link to the
+    /// instance over plain HTTP.
+    pub fn owns(&self, message_url: &str) -> bool {
+        let Some(message_path) = strip_scheme(message_url).strip_prefix(strip_scheme(&self.url))
+        else {
+            return false;
+        };
+        message_path.is_empty() || message_path.starts_with('/')
+    }
+
+    /// Whether this is the instance patch-hub uses when none is configured.
+    pub fn is_default(&self) -> bool {
+        self.url == DEFAULT_INSTANCE_URL
+    }
+}
+
+/// Parses `instances` in the format `name=url,name=url`, as used to override
+/// the configured instances. Returns `None` if any entry is malformed.
+pub fn parse_instances(instances: &str) -> Option<Vec<Instance>> {
+    instances
+        .split(',')
+        .map(|instance| {
+            let (name, url) = instance.split_once('=')?;
+            let (name, url) = (name.trim(), url.trim());
+            if name.is_empty() || url.is_empty() {
+                return None;
+            }
+            Some(Instance::new(name, url))
+        })
+        .collect()
+}
+
+/// Formats `instances` in the format read by [parse_instances].
+pub fn format_instances(instances: &[Instance]) -> String {
+    instances
+        .iter()
+        .map(|instance| format!("{}={}", instance.name, instance.url))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Finds, in `instances`, the one that `message_url` is from.
+pub fn find_instance<'a>(instances: &'a [Instance], message_url: &str) -> Option<&'a Instance> {
+    instances.iter().find(|instance| instance.owns(message_url))
+}
+
+fn strip_scheme(url: &str) -> &str {
+    url.split_once("://").map_or(url, |(_, rest)| rest)
+}
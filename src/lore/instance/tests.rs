@@ -0,0 +1,54 @@
+use super::*;
+
+#[test]
+fn should_tell_which_instance_owns_a_message() {
+    let instances = [
+        Instance::default(),
+        Instance::new("mirror", "https://inbox.example.org/mirror/"),
+    ];
+
+    assert_eq!(
+        Some(&instances[0]),
+        find_instance(&instances, "http://lore.kernel.org/amd-gfx/1234@foo.bar/"),
+        "Scheme of the message URL should be ignored"
+    );
+    assert_eq!(
+        Some(&instances[1]),
+        find_instance(
+            &instances,
+            "https://inbox.example.org/mirror/list/1234@foo.bar/"
+        )
+    );
+    assert_eq!(
+        None,
+        find_instance(
+            &instances,
+            "https://inbox.example.org/mirrors/list/1234@foo.bar/"
+        )
+    );
+    assert!(instances[0].is_default());
+    assert!(!instances[1].is_default());
+}
+
+#[test]
+fn should_parse_instances() {
+    assert_eq!(
+        Some(vec![
+            Instance::default(),
+            Instance::new("internal", "https://inbox.internal"),
+        ]),
+        parse_instances("lore=https://lore.kernel.org, internal = https://inbox.internal/")
+    );
+    assert_eq!(
+        "lore=https://lore.kernel.org,internal=https://inbox.internal",
+        format_instances(
+            &parse_instances("lore=https://lore.kernel.org,internal=https://inbox.internal")
+                .unwrap()
+        )
+    );
+    assert_eq!(None, parse_instances("lore"));
+    assert_eq!(
+        None,
+        parse_instances("lore=https://lore.kernel.org,=https://foo")
+    );
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Writes `value` as JSON to `filepath`, creating its parent directories.
+///
+/// The JSON is written to a temporary file that is then renamed over
+/// `filepath`, so readers never see a partial file. Each write has its own
+/// temporary file, as several threads may save the same file at once.
+pub fn save_json_atomically<T: Serialize + ?Sized>(
+    filepath: impl AsRef<Path>,
+    value: &T,
+) -> io::Result<()> {
+    write_atomically_with(filepath.as_ref(), |file| {
+        serde_json::to_writer(file, value)?;
+        Ok(())
+    })
+}
+
+/// Writes `contents` to `filepath` like [save_json_atomically] does, so an
+/// interrupted write never leaves a partial file behind.
+pub fn write_atomically(filepath: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomically_with(filepath.as_ref(), |mut file| {
+        file.write_all(contents.as_ref())
+    })
+}
+
+fn write_atomically_with(
+    filepath: &Path,
+    write: impl FnOnce(File) -> io::Result<()>,
+) -> io::Result<()> {
+    static TMP_FILES_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    if let Some(parent) = filepath.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_filename = format!(
+        "{}.{}-{}.tmp",
+        filepath.display(),
+        process::id(),
+        TMP_FILES_COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+    write(File::create(&tmp_filename)?)?;
+    fs::rename(tmp_filename, filepath)?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn should_save_json_creating_parent_dirs() {
+    let dir = std::env::temp_dir().join("patch_hub_json_file_test");
+    let _ = fs::remove_dir_all(&dir);
+    let filepath = dir.join("nested/values.json");
+
+    save_json_atomically(&filepath, &vec![1, 2]).unwrap();
+    save_json_atomically(&filepath, &vec![3]).unwrap();
+
+    assert_eq!("[3]", fs::read_to_string(&filepath).unwrap());
+    assert_eq!(
+        1,
+        fs::read_dir(filepath.parent().unwrap()).unwrap().count(),
+        "No temporary file should be left behind"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_write_contents_atomically() {
+    let dir = std::env::temp_dir().join("patch_hub_json_file_write_test");
+    let _ = fs::remove_dir_all(&dir);
+    let filepath = dir.join("patchset.mbx");
+
+    write_atomically(&filepath, "first").unwrap();
+    write_atomically(&filepath, "second").unwrap();
+
+    assert_eq!("second", fs::read_to_string(&filepath).unwrap());
+    assert_eq!(
+        1,
+        fs::read_dir(&dir).unwrap().count(),
+        "No temporary file should be left behind"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,258 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    atom::{self, FeedEntry},
+    lore_api_client::{ClientError, PatchFeedRequest, ThreadRequest},
+    lore_session::LORE_PAGE_SIZE,
+    query::LoreQuery,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Prefix of the names of the pseudo lists of saved `lei` searches, which
+/// sets them apart from the lists of the instances
+pub const LEI_LIST_PREFIX: &str = "lei:";
+
+/// A search saved with `lei q --save`, offered as a pseudo mailing list whose
+/// feed has the patches among the results of the search.
+///
+/// The search is run again on every request, looking where it was saved to
+/// look, so the results are as fresh as the sources of `lei`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeiSearch {
+    /// Name of the pseudo list, made of [LEI_LIST_PREFIX] and the name of the
+    /// output of the search
+    name: String,
+    /// Search terms of the saved search
+    query: String,
+    /// Options of the saved search that select where it looks, like
+    /// `--only` and `--include`
+    options: Vec<String>,
+    /// URL of the instance the messages are linked to, as `lei` doesn't tell
+    /// where they are archived
+    base_url: String,
+}
+
+impl LeiSearch {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Links `message_id` to the `all` inbox of the instance, as the pseudo
+    /// list only exists locally.
+    fn message_url(&self, message_id: &str) -> String {
+        format!("{}/all/{message_id}/", self.base_url)
+    }
+}
+
+impl PatchFeedRequest for LeiSearch {
+    /// Runs the saved search, restricted to the patches matching `query`,
+    /// and builds a feed of the results, skipping the first `min_index` of
+    /// them.
+    fn request_patch_feed(
+        &self,
+        target_list: &str,
+        query: &LoreQuery,
+        min_index: usize,
+    ) -> Result<String, ClientError> {
+        if target_list != self.name {
+            return Err(ClientError::Status(404));
+        }
+
+        let mut args = vec![
+            "q".to_string(),
+            "--format=json".to_string(),
+            format!("--offset={min_index}"),
+            format!("--limit={LORE_PAGE_SIZE}"),
+        ];
+        args.extend(self.options.iter().cloned());
+        args.push(format!("({}) AND {}", self.query, query.to_search_terms()));
+
+        let results = run_lei(&args)?;
+        let entries = parse_results(&results, |message_id| self.message_url(message_id))
+            .map_err(|json_error| ClientError::Lei(json_error.into()))?;
+        if entries.is_empty() {
+            return Err(ClientError::EndOfFeed);
+        }
+
+        Ok(atom::render_feed(&entries))
+    }
+}
+
+impl ThreadRequest for LeiSearch {
+    /// Runs `lei` for the whole thread of `message_id`, looking where the
+    /// saved search looks.
+    fn request_thread_mbox(
+        &self,
+        _target_list: &str,
+        message_id: &str,
+    ) -> Result<String, ClientError> {
+        let mut args = vec![
+            "q".to_string(),
+            "--format=mboxrd".to_string(),
+            "--threads".to_string(),
+        ];
+        args.extend(self.options.iter().cloned());
+        args.push(format!("m:{message_id}"));
+
+        let thread_mbox = run_lei(&args)?;
+        if thread_mbox.trim().is_empty() {
+            return Err(ClientError::Status(404));
+        }
+
+        Ok(thread_mbox)
+    }
+}
+
+/// Lists the searches saved in `lei`, linking their messages to `base_url`.
+///
+/// # Errors
+///
+/// Returns an error if `lei` can't be run or fails.
+pub fn saved_searches(base_url: &str) -> Result<Vec<LeiSearch>, ClientError> {
+    let saved_searches = run_lei(&["ls-search".to_string(), "--format=json".to_string()])?;
+    parse_saved_searches(&saved_searches, base_url)
+        .map_err(|json_error| ClientError::Lei(json_error.into()))
+}
+
+fn run_lei(args: &[String]) -> Result<String, ClientError> {
+    let output = Command::new("lei")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(ClientError::Lei)?;
+    if !output.status.success() {
+        return Err(ClientError::Lei(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses the output of `lei ls-search --format=json`. Searches without an
+/// output or a query are left out.
+fn parse_saved_searches(
+    saved_searches: &str,
+    base_url: &str,
+) -> Result<Vec<LeiSearch>, serde_json::Error> {
+    let saved_searches: Vec<serde_json::Map<String, Value>> = serde_json::from_str(saved_searches)?;
+
+    Ok(saved_searches
+        .iter()
+        .filter_map(|saved_search| {
+            let output = strings(saved_search.get("output")).pop()?;
+            let query = strings(saved_search.get("q")).join(" ");
+            if query.is_empty() {
+                return None;
+            }
+
+            let mut options = Vec::new();
+            for option in ["only", "include", "exclude"] {
+                for value in strings(saved_search.get(option)) {
+                    options.push(format!("--{option}={value}"));
+                }
+            }
+
+            Some(LeiSearch {
+                name: format!("{LEI_LIST_PREFIX}{}", output_name(&output)),
+                query,
+                options,
+                base_url: base_url.trim_end_matches('/').to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Values of a setting of a saved search, which is either a string or an
+/// array of them.
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Name of the output of a saved search, like `linux-mm` for
+/// `maildir:/home/user/Mail/linux-mm/`.
+fn output_name(output: &str) -> &str {
+    let path = match output.split_once(':') {
+        Some((format, path)) if format.chars().all(|c| c.is_ascii_alphanumeric()) => path,
+        _ => output,
+    };
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path)
+}
+
+/// A result of `lei q --format=json`. Only the fields of the feed entries are
+/// read.
+#[derive(Deserialize)]
+struct LeiResult {
+    /// Message-ID
+    m: String,
+    /// Message-IDs of the References and In-Reply-To headers, oldest first
+    #[serde(default)]
+    refs: Vec<String>,
+    /// Subject
+    #[serde(default)]
+    s: String,
+    /// Senders, as pairs of name and address
+    #[serde(default)]
+    f: Vec<(Option<String>, Option<String>)>,
+    /// Date, in ISO 8601
+    #[serde(default)]
+    dt: String,
+}
+
+/// Turns the output of `lei q --format=json` into feed entries, linking the
+/// messages with `message_url`.
+fn parse_results(
+    results: &str,
+    message_url: impl Fn(&str) -> String,
+) -> Result<Vec<FeedEntry>, serde_json::Error> {
+    // `lei` may end the array with a `null`
+    let results: Vec<Option<LeiResult>> = serde_json::from_str(results)?;
+
+    Ok(results
+        .into_iter()
+        .flatten()
+        .map(|result| {
+            let (author_name, author_email) = result.f.into_iter().next().unwrap_or_default();
+            FeedEntry {
+                title: result.s,
+                author_name: author_name.unwrap_or_default(),
+                author_email: author_email.unwrap_or_default(),
+                updated: result.dt,
+                href: message_url(bare_message_id(&result.m)),
+                in_reply_to_href: result
+                    .refs
+                    .last()
+                    .map(|in_reply_to| message_url(bare_message_id(in_reply_to))),
+            }
+        })
+        .collect())
+}
+
+fn bare_message_id(message_id: &str) -> &str {
+    message_id
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+}
@@ -0,0 +1,82 @@
+use std::fs;
+
+use super::*;
+
+#[test]
+fn should_parse_saved_searches() {
+    let saved_searches = fs::read_to_string("src/test_samples/lei/ls_search.json").unwrap();
+    let saved_searches = parse_saved_searches(&saved_searches, "https://lore.kernel.org/").unwrap();
+
+    assert_eq!(
+        2,
+        saved_searches.len(),
+        "Searches without query should be left out"
+    );
+    assert_eq!("lei:drm-vkms", saved_searches[0].name());
+    assert_eq!(
+        "dfn:drivers/gpu/drm/vkms rt:2.months.ago..",
+        saved_searches[0].query()
+    );
+    assert_eq!(
+        vec!["--only=https://lore.kernel.org/dri-devel"],
+        saved_searches[0].options
+    );
+    assert_eq!("lei:my-patches", saved_searches[1].name());
+    assert_eq!("f:foo@bar.foo.bar", saved_searches[1].query());
+    assert_eq!(
+        vec![
+            "--include=https://lore.kernel.org/all",
+            "--include=/srv/public-inbox/internal"
+        ],
+        saved_searches[1].options
+    );
+}
+
+#[test]
+fn should_turn_results_into_feed_entries() {
+    let results = fs::read_to_string("src/test_samples/lei/q_results.json").unwrap();
+    let entries = parse_results(&results, |message_id| {
+        format!("https://lore.kernel.org/lei:drm-vkms/{message_id}/")
+    })
+    .unwrap();
+
+    assert_eq!(
+        vec![
+            FeedEntry {
+                title: "[PATCH 1/1] drm/vkms: Fix something".to_string(),
+                author_name: "Foo Bar".to_string(),
+                author_email: "foo@bar.foo.bar".to_string(),
+                updated: "2024-07-18T16:49:01Z".to_string(),
+                href: "https://lore.kernel.org/lei:drm-vkms/patch@foo.bar/".to_string(),
+                in_reply_to_href: Some(
+                    "https://lore.kernel.org/lei:drm-vkms/cover@foo.bar/".to_string()
+                ),
+            },
+            FeedEntry {
+                title: "[PATCH 0/1] drm/vkms: Fix something".to_string(),
+                author_name: "Foo Bar".to_string(),
+                author_email: "foo@bar.foo.bar".to_string(),
+                updated: "2024-07-18T16:49:00Z".to_string(),
+                href: "https://lore.kernel.org/lei:drm-vkms/cover@foo.bar/".to_string(),
+                in_reply_to_href: None,
+            },
+        ],
+        entries
+    );
+    assert!(parse_results("[]", |message_id| message_id.to_string())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_link_results_to_all_inbox_of_instance() {
+    let saved_searches = fs::read_to_string("src/test_samples/lei/ls_search.json").unwrap();
+    let lei_search = parse_saved_searches(&saved_searches, "https://lore.kernel.org/")
+        .unwrap()
+        .remove(0);
+
+    assert_eq!(
+        "https://lore.kernel.org/all/patch@foo.bar/",
+        lei_search.message_url("patch@foo.bar")
+    );
+}
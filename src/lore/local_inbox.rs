@@ -0,0 +1,590 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{
+    atom::{self, escape_xml, FeedEntry},
+    lore_api_client::{AvailableListsRequest, ClientError, PatchFeedRequest, ThreadRequest},
+    lore_session::{is_patch_subject, LORE_PAGE_SIZE},
+    mbox::{extract_message_ids, Message},
+    query::LoreQuery,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How many messages are read from git at once
+const READ_BATCH_SIZE: usize = 100;
+/// Separator line written between the messages of the thread mboxes
+const MBOXRD_SEPARATOR: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970";
+
+/// Indexes of the inboxes read so far, by path. [LocalInbox]es are built
+/// for each request, so the indexes are kept apart from them.
+static INBOX_INDEXES: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<InboxIndex>>>>> =
+    LazyLock::new(Default::default);
+
+/// A public-inbox v2 repository on disk, like the ones `grokmirror` and
+/// `public-inbox-clone` mirror, serving the requests of its list without
+/// going over HTTP.
+///
+/// Each epoch of the inbox (`git/0.git`, `git/1.git`, ...) is a git
+/// repository in which every commit adds a message as the blob `m`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalInbox {
+    /// Name of the list archived in the inbox
+    name: String,
+    path: PathBuf,
+    /// URL of the instance the list is archived in, used to build the links
+    /// of the messages, so they are the same as the ones of its feed
+    base_url: String,
+}
+
+impl LocalInbox {
+    pub fn new(name: &str, path: impl Into<PathBuf>, base_url: &str) -> LocalInbox {
+        LocalInbox {
+            name: name.to_string(),
+            path: path.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Description of the inbox, as set in its `description` file.
+    pub fn description(&self) -> String {
+        fs::read_to_string(self.path.join("description"))
+            .map(|description| description.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Git repositories of the epochs of the inbox, from the newest to the
+    /// oldest.
+    fn epochs(&self) -> io::Result<Vec<PathBuf>> {
+        let mut epochs: Vec<(usize, PathBuf)> = fs::read_dir(self.path.join("git"))?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_name = entry.file_name();
+                let epoch = file_name.to_str()?.strip_suffix(".git")?.parse().ok()?;
+                Some((epoch, entry.path()))
+            })
+            .collect();
+        if epochs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} isn't a public-inbox v2 repository", self.path.display()),
+            ));
+        }
+
+        epochs.sort_by(|(epoch, _), (other_epoch, _)| other_epoch.cmp(epoch));
+        Ok(epochs.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Index of the messages of the inbox, rebuilt if any epoch got new
+    /// commits since it was built.
+    fn index(&self) -> io::Result<Arc<Mutex<InboxIndex>>> {
+        let epochs = self.epochs()?;
+        let heads = epochs
+            .iter()
+            .map(|epoch| {
+                let head = git(epoch, &["rev-parse", "HEAD"])?;
+                Ok(String::from_utf8_lossy(&head).trim().to_string())
+            })
+            .collect::<io::Result<Vec<String>>>()?;
+
+        let index = Arc::clone(lock(&INBOX_INDEXES).entry(self.path.clone()).or_default());
+        {
+            let mut inbox_index = lock(&index);
+            if inbox_index.heads != heads {
+                *inbox_index = InboxIndex::build(epochs, heads)?;
+            }
+        }
+        Ok(index)
+    }
+
+    fn message_url(&self, message_id: &str) -> String {
+        format!("{}/{}/{message_id}/", self.base_url, self.name)
+    }
+
+    /// Turns `message` into an entry of the feed of the inbox.
+    fn to_feed_entry(&self, message: &Message) -> FeedEntry {
+        let (author_name, author_email) = split_address(message.header("From").unwrap_or_default());
+        let updated = message
+            .header("Date")
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .map(|date| {
+                date.with_timezone(&Utc)
+                    .format("%Y-%m-%dT%H:%M:%SZ")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let in_reply_to = message
+            .header("In-Reply-To")
+            .and_then(|value| extract_message_ids(value).into_iter().next());
+
+        FeedEntry {
+            title: message.subject().to_string(),
+            author_name: author_name.to_string(),
+            author_email: author_email.to_string(),
+            updated,
+            href: self.message_url(message.message_id().unwrap_or_default()),
+            in_reply_to_href: in_reply_to.map(|in_reply_to| self.message_url(in_reply_to)),
+        }
+    }
+}
+
+impl PatchFeedRequest for LocalInbox {
+    /// Builds a feed of the patches in the inbox that match `query`, newest
+    /// first, skipping the first `min_index` of them. Only the inbox of
+    /// `target_list` is read.
+    fn request_patch_feed(
+        &self,
+        target_list: &str,
+        query: &LoreQuery,
+        min_index: usize,
+    ) -> Result<String, ClientError> {
+        if target_list != self.name {
+            return Err(ClientError::Status(404));
+        }
+
+        let index = self.index().map_err(ClientError::LocalInbox)?;
+        let mut index = lock(&index);
+        let page_positions = index
+            .patch_positions(query, min_index + LORE_PAGE_SIZE)
+            .map_err(ClientError::LocalInbox)?
+            .into_iter()
+            .skip(min_index)
+            .collect::<Vec<usize>>();
+        if page_positions.is_empty() {
+            return Err(ClientError::EndOfFeed);
+        }
+
+        let entries: Vec<FeedEntry> = index
+            .read_messages(&page_positions)
+            .map_err(ClientError::LocalInbox)?
+            .iter()
+            .flatten()
+            .map(|raw_message| self.to_feed_entry(&Message::parse(raw_message)))
+            .collect();
+
+        Ok(atom::render_feed(&entries))
+    }
+}
+
+impl ThreadRequest for LocalInbox {
+    /// Builds an mbox with the message `message_id` and the messages that
+    /// reply to it, directly or not. As replies come after the message they
+    /// reply to, only the messages newer than it are read.
+    fn request_thread_mbox(
+        &self,
+        _target_list: &str,
+        message_id: &str,
+    ) -> Result<String, ClientError> {
+        let index = self.index().map_err(ClientError::LocalInbox)?;
+        let mut index = lock(&index);
+        index
+            .index_until(message_id)
+            .map_err(ClientError::LocalInbox)?;
+        let Some(&message_position) = index.message_positions.get(message_id) else {
+            return Err(ClientError::Status(404));
+        };
+
+        let mut positions: Vec<usize> = index
+            .reply_positions
+            .get(message_id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&position| position < message_position)
+            .collect();
+        positions.push(message_position);
+        // Messages go in the mbox from the oldest to the newest
+        positions.sort_unstable_by(|position, other_position| other_position.cmp(position));
+        positions.dedup();
+
+        Ok(index
+            .read_messages(&positions)
+            .map_err(ClientError::LocalInbox)?
+            .iter()
+            .flatten()
+            .map(|raw_message| format_mboxrd_message(raw_message))
+            .collect())
+    }
+}
+
+/// The local inboxes patch-hub reads lists from.
+#[derive(Debug, Clone, Default)]
+pub struct LocalInboxes(pub Vec<LocalInbox>);
+
+impl AvailableListsRequest for LocalInboxes {
+    /// Lists the inboxes in the same layout as the listing page of lore,
+    /// with all of them in the first page.
+    fn request_available_lists(&self, min_index: usize) -> Result<String, ClientError> {
+        let mut listing = String::from("<html><body><pre></pre><pre></pre><pre>");
+        if min_index == 0 {
+            for local_inbox in &self.0 {
+                listing.push_str(&format!(
+                    "* - <a href=\"{0}\">{0}</a>\n  {1}\n\n",
+                    escape_xml(local_inbox.name()),
+                    escape_xml(&local_inbox.description()),
+                ));
+            }
+            // Like in lore, the inboxes are followed by the pseudo-list `all`,
+            // which also ends the description of the last inbox
+            listing.push_str("* - <a href=\"all/\">all</a>\n  All of the local inboxes\n\n");
+        }
+        listing.push_str("</pre></body></html>");
+
+        Ok(listing)
+    }
+}
+
+/// Parses `local_inboxes` in the format `list=path,list=path`, as used to
+/// override the configured inboxes. Returns `None` if any entry is malformed.
+pub fn parse_local_inboxes(local_inboxes: &str) -> Option<BTreeMap<String, String>> {
+    local_inboxes
+        .split(',')
+        .map(|local_inbox| {
+            let (list, path) = local_inbox.split_once('=')?;
+            let (list, path) = (list.trim(), path.trim());
+            if list.is_empty() || path.is_empty() {
+                return None;
+            }
+            Some((list.to_string(), path.to_string()))
+        })
+        .collect()
+}
+
+/// Formats `local_inboxes` in the format [parse_local_inboxes] parses.
+pub fn format_local_inboxes(local_inboxes: &BTreeMap<String, String>) -> String {
+    local_inboxes
+        .iter()
+        .map(|(list, path)| format!("{list}={path}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// What is known of the messages of an inbox, as of the heads of its epochs.
+///
+/// Messages are told apart by their position in the inbox, from the newest
+/// (0) to the oldest. They are indexed by Message-ID as they are read, from
+/// the newest on, so finding a thread only reads the messages newer than it
+/// once.
+#[derive(Default)]
+struct InboxIndex {
+    /// Heads of the epochs, from the newest epoch to the oldest
+    heads: Vec<String>,
+    epochs: Vec<PathBuf>,
+    /// Commit of each message, with the index of its epoch in `epochs`
+    commits: Vec<(usize, String)>,
+    /// How many of the newest messages were indexed
+    indexed_count: usize,
+    /// Position of each indexed message, by Message-ID
+    message_positions: HashMap<String, usize>,
+    /// Positions of the indexed messages that reply to each message,
+    /// directly or not, by Message-ID
+    reply_positions: HashMap<String, Vec<usize>>,
+    /// Patches found so far for each query, by its terms
+    feed_cursors: HashMap<String, FeedCursor>,
+}
+
+/// How far the patches matching a query were looked for
+#[derive(Default)]
+struct FeedCursor {
+    /// Positions of the matching patches, newest first
+    patch_positions: Vec<usize>,
+    /// Position of the first message not looked at yet
+    next_position: usize,
+}
+
+impl InboxIndex {
+    /// Lists the commits of the messages of `epochs`, which go from the
+    /// newest to the oldest, as of `heads`.
+    fn build(epochs: Vec<PathBuf>, heads: Vec<String>) -> io::Result<InboxIndex> {
+        let mut commits = Vec::new();
+        for (epoch_index, (epoch, head)) in epochs.iter().zip(&heads).enumerate() {
+            let output = git(epoch, &["rev-list", head])?;
+            commits.extend(
+                String::from_utf8_lossy(&output)
+                    .lines()
+                    .map(|commit| (epoch_index, commit.to_string())),
+            );
+        }
+
+        Ok(InboxIndex {
+            heads,
+            epochs,
+            commits,
+            ..Default::default()
+        })
+    }
+
+    /// Positions of the newest `count` patches that match `query`, or of all
+    /// of them, if there are less. Only the messages not looked at by
+    /// previous calls with the same query are read.
+    fn patch_positions(&mut self, query: &LoreQuery, count: usize) -> io::Result<Vec<usize>> {
+        let query_terms = query.to_string();
+        let mut feed_cursor = self.feed_cursors.remove(&query_terms).unwrap_or_default();
+
+        while feed_cursor.patch_positions.len() < count
+            && feed_cursor.next_position < self.commits.len()
+        {
+            let positions: Vec<usize> = (feed_cursor.next_position..self.commits.len())
+                .take(READ_BATCH_SIZE)
+                .collect();
+            for (position, raw_message) in positions.iter().zip(self.read_messages(&positions)?) {
+                let message = raw_message.map(|raw_message| Message::parse(&raw_message));
+                self.record(*position, message.as_ref());
+                if message
+                    .is_some_and(|message| is_patch(&message) && matches_query(&message, query))
+                {
+                    feed_cursor.patch_positions.push(*position);
+                }
+            }
+            feed_cursor.next_position += positions.len();
+        }
+
+        let patch_positions = feed_cursor
+            .patch_positions
+            .iter()
+            .take(count)
+            .copied()
+            .collect();
+        self.feed_cursors.insert(query_terms, feed_cursor);
+        Ok(patch_positions)
+    }
+
+    /// Indexes the messages, from the newest on, until the one with
+    /// `message_id` or the end of the inbox.
+    fn index_until(&mut self, message_id: &str) -> io::Result<()> {
+        while !self.message_positions.contains_key(message_id)
+            && self.indexed_count < self.commits.len()
+        {
+            let positions: Vec<usize> = (self.indexed_count..self.commits.len())
+                .take(READ_BATCH_SIZE)
+                .collect();
+            for (position, raw_message) in positions.iter().zip(self.read_messages(&positions)?) {
+                let message = raw_message.map(|raw_message| Message::parse(&raw_message));
+                self.record(*position, message.as_ref());
+            }
+        }
+        Ok(())
+    }
+
+    /// Indexes `message`, read from `position`, if it's the next one to be
+    /// indexed. Positions without a message are just skipped.
+    fn record(&mut self, position: usize, message: Option<&Message>) {
+        if position != self.indexed_count {
+            return;
+        }
+        self.indexed_count += 1;
+
+        let Some(message) = message else {
+            return;
+        };
+        if let Some(message_id) = message.message_id() {
+            // On duplicates, the newest message wins
+            self.message_positions
+                .entry(message_id.to_string())
+                .or_insert(position);
+        }
+        for header_name in ["In-Reply-To", "References"] {
+            for replied_id in extract_message_ids(message.header(header_name).unwrap_or_default()) {
+                self.reply_positions
+                    .entry(replied_id.to_string())
+                    .or_default()
+                    .push(position);
+            }
+        }
+    }
+
+    /// Reads the messages at `positions`, in the same order. Positions
+    /// without a message, like the ones of commits that remove messages from
+    /// the inbox, are `None`.
+    fn read_messages(&self, positions: &[usize]) -> io::Result<Vec<Option<String>>> {
+        let mut messages = Vec::with_capacity(positions.len());
+
+        // Consecutive positions in the same epoch are read at once
+        for batch in positions.chunk_by(|&position, &other_position| {
+            self.commits[position].0 == self.commits[other_position].0
+        }) {
+            let epoch = &self.epochs[self.commits[batch[0]].0];
+            let commits: Vec<&str> = batch
+                .iter()
+                .map(|&position| self.commits[position].1.as_str())
+                .collect();
+            messages.extend(read_message_blobs(epoch, &commits)?);
+        }
+
+        Ok(messages)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Indexes are left consistent, even if a panic interrupted their update
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Reads the message blobs added by `commits` in the repository `epoch`
+/// with a single `git cat-file --batch`. Commits without a message, like the
+/// ones that remove messages from the inbox, are `None`.
+fn read_message_blobs(epoch: &Path, commits: &[&str]) -> io::Result<Vec<Option<String>>> {
+    let mut cat_file = Command::new("git")
+        .arg("--git-dir")
+        .arg(epoch)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Requests are written from another thread, as `git` stops reading them
+    // while its output isn't consumed
+    let mut stdin = cat_file.stdin.take().unwrap();
+    let requests: String = commits
+        .iter()
+        .map(|commit| format!("{commit}:m\n"))
+        .collect();
+    let writer = thread::spawn(move || stdin.write_all(requests.as_bytes()));
+
+    let mut stdout = BufReader::new(cat_file.stdout.take().unwrap());
+    let mut messages = Vec::new();
+    let mut header = String::new();
+    for _ in commits {
+        header.clear();
+        if stdout.read_line(&mut header)? == 0 {
+            break;
+        }
+        // Header is `<object> <type> <size>` or `<object> missing`
+        let Some(size) = header
+            .split_whitespace()
+            .nth(2)
+            .and_then(|size| size.parse::<usize>().ok())
+        else {
+            messages.push(None);
+            continue;
+        };
+
+        let mut blob = vec![0; size + 1];
+        stdout.read_exact(&mut blob)?;
+        blob.pop();
+        messages.push(Some(String::from_utf8_lossy(&blob).into_owned()));
+    }
+    messages.resize(commits.len(), None);
+
+    writer.join().unwrap()?;
+    cat_file.wait()?;
+    Ok(messages)
+}
+
+fn git(git_dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed in {}",
+            args.join(" "),
+            git_dir.display()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Whether `message` is a patch (or RFC) and not a reply to one, like the
+/// base term of [LoreQuery] demands. Patches are told apart by their
+/// `[PATCH ...]` tag, like in the feeds from lore.
+fn is_patch(message: &Message) -> bool {
+    is_patch_subject(message.subject())
+}
+
+/// Whether `message` matches every field of `query`. Fields match if they
+/// are contained in the respective header, ignoring case. Dates must be in
+/// the `YYYY-MM-DD` or `YYYYMMDD` formats, otherwise they are ignored.
+fn matches_query(message: &Message, query: &LoreQuery) -> bool {
+    let contains = |haystack: &str, needle: &Option<String>| match needle.as_deref().map(str::trim)
+    {
+        Some(needle) if !needle.is_empty() => haystack
+            .to_lowercase()
+            .contains(&needle.trim_matches('"').to_lowercase()),
+        _ => true,
+    };
+    let header = |name: &str| message.header(name).unwrap_or_default();
+
+    // Paths of both sides of the diffs, like `a/file.c` and `b/file.c`
+    let touched_files: Vec<&str> = message
+        .body()
+        .lines()
+        .filter_map(|line| line.strip_prefix("diff --git "))
+        .flat_map(str::split_whitespace)
+        .collect();
+    let is_touched_file_matched = match query.touched_file.as_deref().map(str::trim) {
+        Some(touched_file) if !touched_file.is_empty() => touched_files
+            .iter()
+            .any(|file| contains(file, &query.touched_file)),
+        _ => true,
+    };
+    let date = DateTime::parse_from_rfc2822(header("Date").trim())
+        .ok()
+        .map(|date| date.date_naive());
+    let is_within_dates = match date {
+        Some(date) => {
+            parse_date(&query.date_since).is_none_or(|date_since| date >= date_since)
+                && parse_date(&query.date_until).is_none_or(|date_until| date <= date_until)
+        }
+        None => true,
+    };
+
+    contains(header("From"), &query.author)
+        && contains(message.subject(), &query.subject)
+        && is_touched_file_matched
+        && contains(header("Cc"), &query.cc)
+        && (contains(message.subject(), &query.free_text)
+            || contains(message.body(), &query.free_text))
+        && is_within_dates
+}
+
+fn parse_date(date: &Option<String>) -> Option<NaiveDate> {
+    let date = date.as_deref()?.trim();
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y%m%d"))
+        .ok()
+}
+
+/// Splits an address like `Name <email>` into its name and email.
+fn split_address(address: &str) -> (&str, &str) {
+    match address.split_once('<') {
+        Some((name, email)) => (
+            name.trim().trim_matches('"').trim(),
+            email.trim_end().trim_end_matches('>'),
+        ),
+        None => ("", address.trim()),
+    }
+}
+
+/// Writes `raw_message` as an mboxrd message, escaping its `From ` lines.
+fn format_mboxrd_message(raw_message: &str) -> String {
+    let mut mbox_message = format!("{MBOXRD_SEPARATOR}\n");
+    for line in raw_message.lines() {
+        if line.trim_start_matches('>').starts_with("From ") {
+            mbox_message.push('>');
+        }
+        mbox_message.push_str(line);
+        mbox_message.push('\n');
+    }
+    mbox_message.push('\n');
+
+    mbox_message
+}
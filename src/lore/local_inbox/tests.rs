@@ -0,0 +1,285 @@
+use serde_xml_rs::from_str;
+
+use super::*;
+use crate::lore::{
+    lore_session::fetch_available_lists, mailing_list::MailingList, mbox::parse_mbox,
+    patch::PatchFeed,
+};
+
+const MESSAGES: [&str; 4] = [
+    "From: Foo Bar <foo@bar.foo.bar>\n\
+    Subject: [PATCH 0/1] file: Fix something\n\
+    Date: Thu, 18 Jul 2024 16:49:00 +0000\n\
+    Message-ID: <cover@foo.bar>\n\
+    \n\
+    Cover letter\n",
+    "From: Foo Bar <foo@bar.foo.bar>\n\
+    Subject: [PATCH 1/1] file: Fix something\n\
+    Date: Thu, 18 Jul 2024 16:49:01 +0000\n\
+    Message-ID: <patch@foo.bar>\n\
+    In-Reply-To: <cover@foo.bar>\n\
+    References: <cover@foo.bar>\n\
+    \n\
+    From the description\n\
+    ---\n\
+    diff --git a/file.c b/file.c\n",
+    "From: Reviewer <reviewer@foo.bar>\n\
+    Subject: Re: [PATCH 1/1] file: Fix something\n\
+    Date: Fri, 19 Jul 2024 10:00:00 +0200\n\
+    Message-ID: <review@foo.bar>\n\
+    In-Reply-To: <patch@foo.bar>\n\
+    References: <cover@foo.bar> <patch@foo.bar>\n\
+    \n\
+    Reviewed-by: Reviewer <reviewer@foo.bar>\n",
+    "From: \"Baz Qux\" <baz@qux.foo.bar>\n\
+    Subject: [RFC PATCH] other: Add feature & more\n\
+    Date: Mon, 22 Jul 2024 08:00:00 +0000\n\
+    Message-ID: <rfc@foo.bar>\n\
+    \n\
+    diff --git a/other.c b/other.c\n",
+];
+
+/// Creates a public-inbox v2 repository at `path` with the first two messages
+/// in the epoch 0 and the others in the epoch 1.
+fn create_inbox(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    fs::create_dir_all(path.join("git")).unwrap();
+    fs::write(path.join("description"), "Some list\n").unwrap();
+
+    for (epoch, messages) in [(0, &MESSAGES[..2]), (1, &MESSAGES[2..])] {
+        let epoch = path.join(format!("git/{epoch}.git"));
+        run_git(&epoch, &["init", "--quiet", "--bare", "."], "");
+
+        let mut parent: Option<String> = None;
+        for message in messages {
+            let blob = run_git(&epoch, &["hash-object", "-w", "--stdin"], message);
+            let tree = run_git(&epoch, &["mktree"], &format!("100644 blob {blob}\tm\n"));
+            let mut args = vec!["commit-tree", &tree, "-m", "message"];
+            if let Some(parent) = &parent {
+                args.extend(["-p", parent]);
+            }
+            let commit = run_git(&epoch, &args, "");
+            run_git(&epoch, &["update-ref", "HEAD", &commit], "");
+            parent = Some(commit);
+        }
+    }
+}
+
+fn run_git(dir: &Path, args: &[&str], stdin: &str) -> String {
+    fs::create_dir_all(dir).unwrap();
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_DIR", ".")
+        .env("GIT_AUTHOR_NAME", "patch-hub")
+        .env("GIT_AUTHOR_EMAIL", "patch-hub@foo.bar")
+        .env("GIT_COMMITTER_NAME", "patch-hub")
+        .env("GIT_COMMITTER_EMAIL", "patch-hub@foo.bar")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn should_build_patch_feed_from_local_inbox() {
+    let path = std::env::temp_dir().join("patch_hub_local_inbox_feed_test");
+    create_inbox(&path);
+    let local_inbox = LocalInbox::new("some-list", &path, "https://lore.kernel.org/");
+
+    let feed = local_inbox
+        .request_patch_feed("some-list", &LoreQuery::default(), 0)
+        .unwrap();
+    let patch_feed: PatchFeed = from_str(&feed).unwrap();
+    let patches = patch_feed.patches();
+    assert_eq!(3, patches.len(), "Replies shouldn't be in the feed");
+    assert_eq!("[RFC PATCH] other: Add feature & more", patches[0].title());
+    assert_eq!("Baz Qux", patches[0].author().name);
+    assert_eq!(
+        &crate::lore::timestamp::parse_timestamp("2024-07-22T08:00:00Z"),
+        patches[0].updated()
+    );
+    assert_eq!(
+        "https://lore.kernel.org/some-list/rfc@foo.bar/",
+        patches[0].message_id().href
+    );
+    assert_eq!("[PATCH 1/1] file: Fix something", patches[1].title());
+    assert_eq!(
+        "https://lore.kernel.org/some-list/cover@foo.bar/",
+        patches[1].in_reply_to().as_ref().unwrap().href
+    );
+    assert_eq!("foo@bar.foo.bar", patches[2].author().email);
+
+    let query = LoreQuery {
+        touched_file: Some("file.c".to_string()),
+        date_until: Some("2024-07-20".to_string()),
+        ..Default::default()
+    };
+    let patch_feed: PatchFeed = from_str(
+        &local_inbox
+            .request_patch_feed("some-list", &query, 0)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(1, patch_feed.patches().len());
+    assert_eq!("patch@foo.bar", patch_feed.patches()[0].message_id().id());
+
+    assert!(matches!(
+        local_inbox.request_patch_feed("some-list", &LoreQuery::default(), 3),
+        Err(ClientError::EndOfFeed)
+    ));
+    assert!(matches!(
+        local_inbox.request_patch_feed("other-list", &LoreQuery::default(), 0),
+        Err(ClientError::Status(404))
+    ));
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn should_build_thread_mbox_from_local_inbox() {
+    let path = std::env::temp_dir().join("patch_hub_local_inbox_thread_test");
+    create_inbox(&path);
+    let local_inbox = LocalInbox::new("some-list", &path, "https://lore.kernel.org");
+
+    let thread_mbox = local_inbox
+        .request_thread_mbox("all", "cover@foo.bar")
+        .unwrap();
+    let message_ids: Vec<String> = parse_mbox(&thread_mbox)
+        .iter()
+        .map(|message| message.message_id().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        vec!["cover@foo.bar", "patch@foo.bar", "review@foo.bar"],
+        message_ids,
+        "Thread should have its messages from the oldest to the newest"
+    );
+    assert!(
+        thread_mbox.contains("\n>From the description\n"),
+        "`From ` lines should be escaped"
+    );
+
+    assert!(matches!(
+        local_inbox.request_thread_mbox("all", "missing@foo.bar"),
+        Err(ClientError::Status(404))
+    ));
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn should_rebuild_index_once_inbox_gets_new_messages() {
+    let path = std::env::temp_dir().join("patch_hub_local_inbox_update_test");
+    create_inbox(&path);
+    let local_inbox = LocalInbox::new("some-list", &path, "https://lore.kernel.org");
+    let count_patches = || {
+        let feed = local_inbox
+            .request_patch_feed("some-list", &LoreQuery::default(), 0)
+            .unwrap();
+        from_str::<PatchFeed>(&feed).unwrap().patches().len()
+    };
+    assert_eq!(3, count_patches());
+
+    let epoch = path.join("git/1.git");
+    let blob = run_git(
+        &epoch,
+        &["hash-object", "-w", "--stdin"],
+        "From: Foo Bar <foo@bar.foo.bar>\n\
+        Subject: [PATCH v2] file: Fix something\n\
+        Date: Tue, 23 Jul 2024 08:00:00 +0000\n\
+        Message-ID: <v2@foo.bar>\n\
+        \n\
+        diff --git a/file.c b/file.c\n",
+    );
+    let tree = run_git(&epoch, &["mktree"], &format!("100644 blob {blob}\tm\n"));
+    let head = run_git(&epoch, &["rev-parse", "HEAD"], "");
+    let commit = run_git(
+        &epoch,
+        &["commit-tree", &tree, "-p", &head, "-m", "message"],
+        "",
+    );
+    run_git(&epoch, &["update-ref", "HEAD", &commit], "");
+
+    assert_eq!(4, count_patches(), "New messages should be in the feed");
+    assert!(local_inbox.request_thread_mbox("all", "v2@foo.bar").is_ok());
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn should_tell_patches_by_their_tag() {
+    let is_subject_patch =
+        |subject: &str| is_patch(&Message::parse(&format!("Subject: {subject}\n\nbody\n")));
+
+    assert!(is_subject_patch("[PATCH v2 1/3] file: Fix something"));
+    assert!(is_subject_patch("[RFC] file: Add something"));
+    assert!(!is_subject_patch("Re: [PATCH] file: Fix something"));
+    assert!(!is_subject_patch("sched: dispatch the tasks patchily"));
+}
+
+#[test]
+fn should_match_each_touched_file_separately() {
+    let message = Message::parse(
+        "Subject: [PATCH] Fix things\n\
+        \n\
+        diff --git a/drivers/foo.c b/drivers/foo.c\n\
+        diff --git a/net/bar.c b/net/bar.c\n",
+    );
+    let matches_file = |touched_file: &str| {
+        let query = LoreQuery {
+            touched_file: Some(touched_file.to_string()),
+            ..Default::default()
+        };
+        matches_query(&message, &query)
+    };
+
+    assert!(matches_file("drivers/foo.c"));
+    assert!(matches_file("net/bar.c"));
+    assert!(
+        !matches_file("foo.cdiff"),
+        "Queries shouldn't match across files"
+    );
+}
+
+#[test]
+fn should_list_local_inboxes() {
+    let path = std::env::temp_dir().join("patch_hub_local_inbox_lists_test");
+    create_inbox(&path);
+    let local_inboxes = LocalInboxes(vec![LocalInbox::new(
+        "some-list",
+        &path,
+        "https://lore.kernel.org",
+    )]);
+
+    assert_eq!(
+        vec![MailingList::new("some-list", "Some list")],
+        fetch_available_lists(&local_inboxes).unwrap()
+    );
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn should_parse_local_inboxes() {
+    let local_inboxes =
+        parse_local_inboxes("some-list=/srv/some-list, other-list = ~/other list").unwrap();
+    assert_eq!(
+        Some(&"~/other list".to_string()),
+        local_inboxes.get("other-list")
+    );
+    assert_eq!(
+        "other-list=~/other list,some-list=/srv/some-list",
+        format_local_inboxes(&local_inboxes)
+    );
+    assert_eq!(None, parse_local_inboxes("some-list"));
+    assert_eq!(None, parse_local_inboxes("some-list=/srv/some-list,=/srv"));
+}
@@ -0,0 +1,51 @@
+use super::{
+    lei::LeiSearch,
+    local_inbox::LocalInbox,
+    lore_api_client::{ClientError, PatchFeedRequest, ThreadRequest},
+    query::LoreQuery,
+};
+
+/// Where the feed and the threads of a list are read from when they come
+/// from the machine instead of an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalSource {
+    /// A public-inbox v2 repository on disk
+    Inbox(LocalInbox),
+    /// A search saved in `lei`, as a pseudo list
+    LeiSearch(LeiSearch),
+}
+
+impl PatchFeedRequest for LocalSource {
+    fn request_patch_feed(
+        &self,
+        target_list: &str,
+        query: &LoreQuery,
+        min_index: usize,
+    ) -> Result<String, ClientError> {
+        match self {
+            LocalSource::Inbox(local_inbox) => {
+                local_inbox.request_patch_feed(target_list, query, min_index)
+            }
+            LocalSource::LeiSearch(lei_search) => {
+                lei_search.request_patch_feed(target_list, query, min_index)
+            }
+        }
+    }
+}
+
+impl ThreadRequest for LocalSource {
+    fn request_thread_mbox(
+        &self,
+        target_list: &str,
+        message_id: &str,
+    ) -> Result<String, ClientError> {
+        match self {
+            LocalSource::Inbox(local_inbox) => {
+                local_inbox.request_thread_mbox(target_list, message_id)
+            }
+            LocalSource::LeiSearch(lei_search) => {
+                lei_search.request_thread_mbox(target_list, message_id)
+            }
+        }
+    }
+}
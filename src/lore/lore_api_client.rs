@@ -1,41 +1,140 @@
+use std::io::Read;
+use std::sync::Arc;
+use std::thread;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
 use mockall::automock;
 use thiserror::Error;
-use ureq::tls::TlsConfig;
-use ureq::Agent;
+use ureq::http::Response;
+use ureq::Body;
+
+use super::http_agent::{self, AgentOptions};
+use super::http_cache::{CachedResponse, HttpCache};
+use super::instance::{Instance, DEFAULT_INSTANCE_URL};
+use super::query::LoreQuery;
+use super::request_policy::{RateLimiter, RetryPolicy};
 
 #[cfg(test)]
 mod tests;
 
-const LORE_DOMAIN: &str = r"https://lore.kernel.org";
-const BASE_QUERY_FOR_FEED_REQUEST: &str = r"?x=A&q=((s:patch+OR+s:rfc)+AND+NOT+s:re:)";
-
 #[derive(Error, Debug)]
 pub enum ClientError {
     #[error(transparent)]
-    FromUreq(#[from] ureq::Error),
+    FromUreq(ureq::Error),
 
     #[error("Feed ended")]
     EndOfFeed,
+
+    #[error("Failed to decompress response: {0}")]
+    Decompress(#[from] std::io::Error),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Request failed with HTTP status {0}")]
+    Status(u16),
+
+    #[error("Too many requests, retry after {retry_after:?}")]
+    Throttled { retry_after: Option<Duration> },
+
+    #[error("Not available offline")]
+    Offline,
+
+    #[error("Failed to read local inbox: {0}")]
+    LocalInbox(std::io::Error),
+
+    #[error("Failed to run lei: {0}")]
+    Lei(std::io::Error),
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(ureq_error: ureq::Error) -> Self {
+        match ureq_error {
+            ureq::Error::Timeout(_) => ClientError::Timeout,
+            ureq::Error::StatusCode(429) => ClientError::Throttled { retry_after: None },
+            ureq::Error::StatusCode(status) => ClientError::Status(status),
+            ureq_error => ClientError::FromUreq(ureq_error),
+        }
+    }
 }
 
+impl ClientError {
+    /// Whether the request may succeed if retried, like on timeouts, lost
+    /// connections and server errors.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Timeout | ClientError::Throttled { .. } => true,
+            ClientError::Status(status) => (500..600).contains(status),
+            ClientError::FromUreq(ureq_error) => matches!(
+                ureq_error,
+                ureq::Error::Io(_)
+                    | ureq::Error::ConnectionFailed
+                    | ureq::Error::HostNotFound
+                    | ureq::Error::Protocol(_)
+            ),
+            ClientError::EndOfFeed
+            | ClientError::Decompress(_)
+            | ClientError::Offline
+            | ClientError::LocalInbox(_)
+            | ClientError::Lei(_) => false,
+        }
+    }
+
+    /// Whether the request failed because the instance couldn't be reached,
+    /// be it due to the network or to the client being offline. Transient
+    /// failures count as such, as they persisted through the retries, except
+    /// for throttling, as the instance was reached and only asked to slow
+    /// down.
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            ClientError::Offline => true,
+            ClientError::Throttled { .. } => false,
+            client_error => client_error.is_transient(),
+        }
+    }
+}
+
+/// Notable events of the requests of a [BlockingLoreAPIClient], reported to
+/// its observer (see [BlockingLoreAPIClient::with_observer]).
+#[derive(Debug)]
+pub enum RequestEvent<'a> {
+    /// A request to `url` failed with the transient `error` and will be
+    /// retried for the `retry`-th time after `delay`
+    Retrying {
+        url: &'a str,
+        retry: u32,
+        delay: Duration,
+        error: &'a ClientError,
+    },
+    /// A request to `url` succeeded after `retries` retries
+    Recovered { url: &'a str, retries: u32 },
+}
+
+pub type RequestObserver = Arc<dyn Fn(&RequestEvent) + Send + Sync>;
+
 #[derive(Clone)]
 pub struct BlockingLoreAPIClient {
     pub lore_domain: String,
     client: ureq::Agent,
+    /// Cache for the responses to feed, available lists and patch HTML
+    /// requests, if any
+    http_cache: Option<HttpCache>,
+    /// If true, cached responses are always revalidated, regardless of their
+    /// age
+    force_refresh: bool,
+    /// If true, no request is sent and only cached responses are returned,
+    /// no matter how old they are
+    offline: bool,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    observer: Option<RequestObserver>,
 }
 impl Default for BlockingLoreAPIClient {
     fn default() -> Self {
-        let agent: Agent = Agent::config_builder()
-            .user_agent(Some(format!(
-                "kworkflow/patch-hub/{}",
-                env!("CARGO_PKG_VERSION")
-            )))
-            .timeout_per_call(Some(Duration::from_secs(120)))
-            .tls_config(TlsConfig::builder().build())
-            .build()
-            .into();
+        let agent = http_agent::build_agent(&AgentOptions::default())
+            .expect("Default agent options should be valid");
         Self::new(agent)
     }
 }
@@ -43,8 +142,241 @@ impl Default for BlockingLoreAPIClient {
 impl BlockingLoreAPIClient {
     pub fn new(client: ureq::Agent) -> BlockingLoreAPIClient {
         BlockingLoreAPIClient {
-            lore_domain: LORE_DOMAIN.to_string(),
+            lore_domain: DEFAULT_INSTANCE_URL.to_string(),
             client,
+            http_cache: None,
+            force_refresh: false,
+            offline: false,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            observer: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> BlockingLoreAPIClient {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> BlockingLoreAPIClient {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Reports retries and recoveries of requests to `observer`, e.g., to log
+    /// them.
+    pub fn with_observer(mut self, observer: RequestObserver) -> BlockingLoreAPIClient {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Returns a copy of the client that sends its requests to `instance`.
+    /// The copy shares the cache and the rate limiter of the client.
+    pub fn for_instance(&self, instance: &Instance) -> BlockingLoreAPIClient {
+        BlockingLoreAPIClient {
+            lore_domain: instance.url().to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Caches the responses of the client in `http_cache`.
+    pub fn with_http_cache(mut self, http_cache: HttpCache) -> BlockingLoreAPIClient {
+        self.http_cache = Some(http_cache);
+        self
+    }
+
+    /// Returns a copy of the client that doesn't trust cached responses
+    /// without asking lore if they changed, no matter how recent they are.
+    pub fn force_refresh(&self) -> BlockingLoreAPIClient {
+        BlockingLoreAPIClient {
+            force_refresh: true,
+            ..self.clone()
+        }
+    }
+
+    /// Makes the client send no requests, answering only with cached
+    /// responses (see [ClientError::Offline]).
+    pub fn with_offline(mut self, offline: bool) -> BlockingLoreAPIClient {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Requests `url` and returns the response body as text, going through
+    /// the HTTP cache, if any.
+    ///
+    /// Fresh cached responses are returned right away. Stale ones are
+    /// revalidated with a conditional request, so lore only sends the body
+    /// again if it changed. When offline, any cached response is returned.
+    fn get_text(&self, url: &str, accept: &str) -> Result<String, ClientError> {
+        self.get_cached(url, accept, |response| {
+            Ok(response.body_mut().read_to_string()?)
+        })
+    }
+
+    /// Like [BlockingLoreAPIClient::get_text], but the body is turned into
+    /// text, and cached as such, by `read_body`.
+    fn get_cached(
+        &self,
+        url: &str,
+        accept: &str,
+        read_body: impl Fn(&mut Response<Body>) -> Result<String, ClientError>,
+    ) -> Result<String, ClientError> {
+        if self.offline {
+            return self
+                .http_cache
+                .as_ref()
+                .and_then(|http_cache| http_cache.load(url))
+                .map(|cached_response| cached_response.body)
+                .ok_or(ClientError::Offline);
+        }
+
+        let Some(http_cache) = &self.http_cache else {
+            return self.with_retries(url, || read_body(&mut self.send_get(url, accept, &[])?));
+        };
+
+        let cached_response = http_cache.load(url);
+        if let Some(cached_response) = cached_response.as_ref() {
+            if !self.force_refresh && http_cache.is_fresh(cached_response) {
+                return Ok(cached_response.body.clone());
+            }
+        }
+
+        let mut headers = Vec::new();
+        if let Some(cached_response) = cached_response.as_ref() {
+            if let Some(etag) = &cached_response.etag {
+                headers.push(("If-None-Match", etag.as_str()));
+            }
+            if let Some(last_modified) = &cached_response.last_modified {
+                headers.push(("If-Modified-Since", last_modified.as_str()));
+            }
+        }
+
+        let (fetched_response, is_not_modified) = self.with_retries(url, || {
+            let mut response = self.send_get(url, accept, &headers)?;
+            if response.status() == 304 {
+                return Ok((None, true));
+            }
+
+            let header_value = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string)
+            };
+            let etag = header_value("ETag");
+            let last_modified = header_value("Last-Modified");
+            let body = read_body(&mut response)?;
+            Ok((
+                Some(CachedResponse::new(url, etag, last_modified, body)),
+                false,
+            ))
+        })?;
+
+        let cached_response = match (fetched_response, cached_response) {
+            (Some(fetched_response), _) => fetched_response,
+            (None, Some(mut cached_response)) if is_not_modified => {
+                cached_response.touch();
+                cached_response
+            }
+            // Lore shouldn't answer "not modified" to unconditional requests
+            (None, _) => return Err(ClientError::Status(304)),
+        };
+
+        // The cache is an optimization, so failing to update it shouldn't
+        // fail the request
+        let _ = http_cache.store(&cached_response);
+        Ok(cached_response.body)
+    }
+
+    /// Sends a GET request to `url` with the given extra `headers`, waiting
+    /// for the rate limiter. HTTP error statuses become errors.
+    fn send_get(
+        &self,
+        url: &str,
+        accept: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<Response<Body>, ClientError> {
+        self.rate_limiter.acquire();
+
+        let mut request = self.client.get(url).header("Accept", accept);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = request
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .call()?;
+
+        match response.status().as_u16() {
+            429 => Err(ClientError::Throttled {
+                retry_after: response
+                    .headers()
+                    .get("Retry-After")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after),
+            }),
+            status if status >= 400 => Err(ClientError::Status(status)),
+            _ => Ok(response),
+        }
+    }
+
+    /// Runs the idempotent `request` to `url`, retrying it with backoff while
+    /// it fails for transient reasons, as defined by the retry policy.
+    fn with_retries<T>(
+        &self,
+        url: &str,
+        mut request: impl FnMut() -> Result<T, ClientError>,
+    ) -> Result<T, ClientError> {
+        let mut retry = 0;
+        loop {
+            let error = match request() {
+                Ok(response) => {
+                    if retry > 0 {
+                        self.notify(&RequestEvent::Recovered {
+                            url,
+                            retries: retry,
+                        });
+                    }
+                    return Ok(response);
+                }
+                Err(error) => error,
+            };
+            if !error.is_transient() || retry >= self.retry_policy.max_retries {
+                return Err(error);
+            }
+
+            let mut delay = self.retry_policy.backoff(retry);
+            if let ClientError::Throttled {
+                retry_after: Some(retry_after),
+            } = error
+            {
+                // Waiting too long would leave the user hanging
+                if retry_after > self.retry_policy.max_delay {
+                    return Err(error);
+                }
+                delay = delay.max(retry_after);
+            }
+
+            retry += 1;
+            self.notify(&RequestEvent::Retrying {
+                url,
+                retry,
+                delay,
+                error: &error,
+            });
+            thread::sleep(delay);
+        }
+    }
+
+    fn notify(&self, event: &RequestEvent) {
+        if let Some(observer) = &self.observer {
+            observer(event);
         }
     }
 }
@@ -54,6 +386,7 @@ pub trait PatchFeedRequest {
     fn request_patch_feed(
         &self,
         target_list: &str,
+        query: &LoreQuery,
         min_index: usize,
     ) -> Result<String, ClientError>;
 }
@@ -62,19 +395,17 @@ impl PatchFeedRequest for BlockingLoreAPIClient {
     fn request_patch_feed(
         &self,
         target_list: &str,
+        query: &LoreQuery,
         min_index: usize,
     ) -> Result<String, ClientError> {
         let feed_url: String = format!(
-            "{}/{target_list}/{BASE_QUERY_FOR_FEED_REQUEST}&o={min_index}",
-            self.lore_domain
+            "{}/{target_list}/?x=A&q={}&o={min_index}",
+            self.lore_domain,
+            query.to_url_query()
         );
 
-        let request_builder = self
-            .client
-            .get(feed_url)
-            .header("Accept", "text/html,application/xhtml+xml,application/xml");
-
-        let feed_response_body = request_builder.call()?.body_mut().read_to_string()?;
+        let feed_response_body =
+            self.get_text(&feed_url, "text/html,application/xhtml+xml,application/xml")?;
 
         if feed_response_body.eq(r"</feed>") {
             return Err(ClientError::EndOfFeed);
@@ -93,12 +424,10 @@ impl AvailableListsRequest for BlockingLoreAPIClient {
     fn request_available_lists(&self, min_index: usize) -> Result<String, ClientError> {
         let available_lists_url = format!("{}/?&o={min_index}", self.lore_domain);
 
-        let body: String = ureq::get(&available_lists_url)
-            .header("Accept", "text/html,application/xhtml+xml,application/xml")
-            .call()?
-            .body_mut()
-            .read_to_string()?;
-        Ok(body)
+        self.get_text(
+            &available_lists_url,
+            "text/html,application/xhtml+xml,application/xml",
+        )
     }
 }
 
@@ -119,12 +448,65 @@ impl PatchHTMLRequest for BlockingLoreAPIClient {
     ) -> Result<String, ClientError> {
         let patch_html_url = format!("{}/{target_list}/{message_id}/", self.lore_domain);
 
-        let body: String = ureq::get(&patch_html_url)
-            .header("Accept", "text/html,application/xhtml+xml,application/xml")
-            .call()?
-            .body_mut()
-            .read_to_string()?;
+        self.get_text(
+            &patch_html_url,
+            "text/html,application/xhtml+xml,application/xml",
+        )
+    }
+}
 
-        Ok(body)
+#[automock]
+pub trait ThreadRequest {
+    /// Requests the whole thread that contains `message_id` as a (decompressed)
+    /// mbox, i.e., every message of the conversation with its full body.
+    fn request_thread_mbox(
+        &self,
+        target_list: &str,
+        message_id: &str,
+    ) -> Result<String, ClientError>;
+}
+
+impl ThreadRequest for BlockingLoreAPIClient {
+    fn request_thread_mbox(
+        &self,
+        target_list: &str,
+        message_id: &str,
+    ) -> Result<String, ClientError> {
+        let thread_mbox_url = format!("{}/{target_list}/{message_id}/t.mbox.gz", self.lore_domain);
+
+        // Threads are cached decompressed
+        self.get_cached(
+            &thread_mbox_url,
+            "application/gzip,application/mbox",
+            |response| decompress_gzip(&response.body_mut().read_to_vec()?),
+        )
+    }
+}
+
+/// Parses the value of a `Retry-After` header, which is either a number of
+/// seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
+
+    let retry_date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some(
+        (retry_date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Decompresses a gzip `body`. Bodies without the gzip magic number are
+/// assumed to have been already decompressed (e.g. by a proxy).
+fn decompress_gzip(body: &[u8]) -> Result<String, ClientError> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return Ok(String::from_utf8_lossy(body).into_owned());
+    }
+
+    let mut decompressed_body = Vec::new();
+    GzDecoder::new(body).read_to_end(&mut decompressed_body)?;
+
+    Ok(String::from_utf8_lossy(&decompressed_body).into_owned())
 }
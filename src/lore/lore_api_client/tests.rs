@@ -1,12 +1,20 @@
 use super::*;
-use crate::lore::patch::PatchFeed;
+use crate::lore::{
+    http_cache::HttpCache,
+    patch::PatchFeed,
+    query::LoreQuery,
+    request_policy::{RateLimiter, RetryPolicy},
+};
+use std::sync::Mutex;
 
 #[test]
 #[ignore = "network-io"]
 fn blocking_client_can_request_valid_patch_feed() {
     let lore_api_client = BlockingLoreAPIClient::default();
 
-    let patch_feed = lore_api_client.request_patch_feed("amd-gfx", 0).unwrap();
+    let patch_feed = lore_api_client
+        .request_patch_feed("amd-gfx", &LoreQuery::default(), 0)
+        .unwrap();
     let patch_feed: PatchFeed = serde_xml_rs::from_str(&patch_feed).unwrap();
     let patches = patch_feed.patches();
 
@@ -22,9 +30,11 @@ fn blocking_client_can_request_valid_patch_feed() {
 fn blocking_client_should_detect_failed_patch_feed_request() {
     let lore_api_client = BlockingLoreAPIClient::default();
 
-    if let Err(client_error) = lore_api_client.request_patch_feed("invalid-list", 0) {
+    if let Err(client_error) =
+        lore_api_client.request_patch_feed("invalid-list", &LoreQuery::default(), 0)
+    {
         match client_error {
-            ClientError::FromUreq(_) => (),
+            ClientError::Status(_) => (),
             _ => {
                 panic!("Invalid request should return non 200 OK status.\n{client_error:#?}")
             }
@@ -33,7 +43,9 @@ fn blocking_client_should_detect_failed_patch_feed_request() {
         panic!("Invalid request shouldn't be successful");
     }
 
-    if let Err(client_error) = lore_api_client.request_patch_feed("amd-gfx", 300000) {
+    if let Err(client_error) =
+        lore_api_client.request_patch_feed("amd-gfx", &LoreQuery::default(), 300000)
+    {
         match client_error {
             ClientError::EndOfFeed => (),
             _ => {
@@ -67,3 +79,296 @@ fn blocking_client_can_request_valid_patch_html() {
         panic!("Valid request should be successful");
     }
 }
+
+#[test]
+#[ignore = "network-io"]
+fn blocking_client_can_request_valid_thread_mbox() {
+    let lore_api_client = BlockingLoreAPIClient::default();
+
+    let thread_mbox = lore_api_client
+        .request_thread_mbox("all", "Pine.LNX.4.58.0507282031180.3307@g5.osdl.org")
+        .unwrap();
+
+    assert!(
+        thread_mbox.contains("Message-ID: <Pine.LNX.4.58.0507282031180.3307@g5.osdl.org>"),
+        "Thread mbox should contain the requested message"
+    );
+}
+
+#[test]
+fn should_decompress_gzip_body() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"From mboxrd@z\nSubject: foo\n").unwrap();
+    let compressed_body = encoder.finish().unwrap();
+
+    assert_eq!(
+        "From mboxrd@z\nSubject: foo\n",
+        decompress_gzip(&compressed_body).unwrap()
+    );
+    assert_eq!(
+        "already plain",
+        decompress_gzip(b"already plain").unwrap(),
+        "Bodies without gzip magic number should be kept as is"
+    );
+}
+
+/// Minimal HTTP server that answers each connection with the next of the
+/// given `responses` and hands back the requests it received.
+fn spawn_stub_server(responses: Vec<&'static str>) -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let (request_sender, request_receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            request_sender.send(request.to_lowercase()).unwrap();
+
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (address, request_receiver)
+}
+
+/// Client for the stub server at `address`, without rate limit and with
+/// short retry delays.
+fn stub_client(address: &str) -> BlockingLoreAPIClient {
+    let mut lore_api_client = BlockingLoreAPIClient::default()
+        .with_rate_limiter(RateLimiter::new(0))
+        .with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(1),
+        });
+    lore_api_client.lore_domain = address.to_string();
+    lore_api_client
+}
+
+fn cached_client(address: &str, cache_name: &str, ttl: Duration) -> BlockingLoreAPIClient {
+    let cache_dir = std::env::temp_dir().join(cache_name);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    stub_client(address).with_http_cache(HttpCache::new(cache_dir, ttl))
+}
+
+#[test]
+fn should_serve_fresh_responses_from_cache() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nlists",
+        "HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nrefresh",
+    ]);
+    let lore_api_client = cached_client(
+        &address,
+        "patch_hub_fresh_cache_test",
+        Duration::from_secs(3600),
+    );
+
+    assert_eq!("lists", lore_api_client.request_available_lists(0).unwrap());
+    assert_eq!(
+        "lists",
+        lore_api_client.request_available_lists(0).unwrap(),
+        "Fresh response should come from the cache"
+    );
+    requests.recv().unwrap();
+    assert!(
+        requests.try_recv().is_err(),
+        "Only one request should be sent"
+    );
+
+    assert_eq!(
+        "refresh",
+        lore_api_client
+            .force_refresh()
+            .request_available_lists(0)
+            .unwrap(),
+        "Forced refresh should ignore the age of the cached response"
+    );
+}
+
+#[test]
+fn should_revalidate_stale_responses() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nLast-Modified: Tue, 16 Jul 2024 16:51:00 GMT\r\n\
+        Content-Length: 4\r\nConnection: close\r\n\r\nhtml",
+        "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+    ]);
+    let lore_api_client = cached_client(&address, "patch_hub_stale_cache_test", Duration::ZERO);
+
+    assert_eq!(
+        "html",
+        lore_api_client
+            .request_patch_html("all", "1234@foo.bar")
+            .unwrap()
+    );
+    assert_eq!(
+        "html",
+        lore_api_client
+            .request_patch_html("all", "1234@foo.bar")
+            .unwrap(),
+        "Not modified response should be served from the cache"
+    );
+
+    let first_request = requests.recv().unwrap();
+    assert!(first_request.starts_with("get /all/1234@foo.bar/ "));
+    assert!(!first_request.contains("if-none-match"));
+
+    let second_request = requests.recv().unwrap();
+    assert!(second_request.contains("if-none-match: \"v1\""));
+    assert!(second_request.contains("if-modified-since: tue, 16 jul 2024 16:51:00 gmt"));
+}
+
+#[test]
+fn should_serve_threads_from_cache() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\nthread",
+    ]);
+    let lore_api_client = cached_client(
+        &address,
+        "patch_hub_thread_cache_test",
+        Duration::from_secs(3600),
+    );
+
+    for _ in 0..2 {
+        assert_eq!(
+            "thread",
+            lore_api_client
+                .request_thread_mbox("some-list", "1234@foo.bar")
+                .unwrap()
+        );
+    }
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("get /some-list/1234@foo.bar/t.mbox.gz "));
+    assert!(
+        requests.try_recv().is_err(),
+        "Thread should be downloaded only once"
+    );
+}
+
+#[test]
+fn offline_client_should_only_serve_cached_responses() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nlists",
+    ]);
+    let lore_api_client = cached_client(&address, "patch_hub_offline_cache_test", Duration::ZERO);
+    lore_api_client.request_available_lists(0).unwrap();
+    requests.recv().unwrap();
+
+    let offline_client = lore_api_client.with_offline(true);
+    assert_eq!(
+        "lists",
+        offline_client.request_available_lists(0).unwrap(),
+        "Stale responses should be served while offline"
+    );
+    assert!(matches!(
+        offline_client.request_patch_html("all", "1234@foo.bar"),
+        Err(ClientError::Offline)
+    ));
+    assert!(matches!(
+        offline_client.request_thread_mbox("all", "1234@foo.bar"),
+        Err(ClientError::Offline)
+    ));
+    assert!(
+        requests.try_recv().is_err(),
+        "No request should be sent while offline"
+    );
+}
+
+#[test]
+fn should_retry_transient_failures() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\n\
+        Connection: close\r\n\r\n",
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nlists",
+    ]);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let observed_events = Arc::clone(&events);
+    let lore_api_client =
+        stub_client(&address).with_observer(Arc::new(move |event: &RequestEvent| {
+            observed_events.lock().unwrap().push(match event {
+                RequestEvent::Retrying { retry, error, .. } => format!("retry {retry}: {error}"),
+                RequestEvent::Recovered { retries, .. } => format!("recovered after {retries}"),
+            });
+        }));
+
+    assert_eq!("lists", lore_api_client.request_available_lists(0).unwrap());
+    assert_eq!(3, requests.iter().count());
+    assert_eq!(
+        vec![
+            "retry 1: Request failed with HTTP status 503",
+            "retry 2: Too many requests, retry after Some(0ns)",
+            "recovered after 2",
+        ],
+        *events.lock().unwrap()
+    );
+}
+
+#[test]
+fn should_not_retry_client_errors() {
+    let (address, requests) = spawn_stub_server(vec![
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    ]);
+    let lore_api_client = stub_client(&address);
+
+    match lore_api_client.request_patch_html("all", "1234@foo.bar") {
+        Err(ClientError::Status(404)) => (),
+        result => panic!("Request should fail with status 404, got {result:?}"),
+    }
+    assert_eq!(1, requests.iter().count());
+}
+
+#[test]
+fn should_give_up_when_throttled_for_too_long() {
+    let (address, _requests) = spawn_stub_server(vec![
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 3600\r\nContent-Length: 0\r\n\
+        Connection: close\r\n\r\n",
+    ]);
+    let lore_api_client = stub_client(&address);
+
+    match lore_api_client.request_available_lists(0) {
+        Err(client_error @ ClientError::Throttled { retry_after }) => {
+            assert_eq!(Some(Duration::from_secs(3600)), retry_after);
+            assert!(client_error.is_transient());
+            assert!(
+                !client_error.is_unreachable(),
+                "Throttling shouldn't take the app offline"
+            );
+        }
+        result => panic!("Request should be throttled, got {result:?}"),
+    }
+}
+
+#[test]
+fn should_parse_retry_after() {
+    assert_eq!(Some(Duration::from_secs(120)), parse_retry_after("120"));
+    assert_eq!(
+        Some(Duration::ZERO),
+        parse_retry_after("Tue, 16 Jul 2024 16:51:00 GMT"),
+        "Dates in the past shouldn't be waited for"
+    );
+    assert_eq!(None, parse_retry_after("soon"));
+}
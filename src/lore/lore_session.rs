@@ -1,14 +1,19 @@
+use crate::lore::feed_snapshot::FeedSnapshot;
+use crate::lore::json_file;
 use crate::lore::lore_api_client::{
-    AvailableListsRequest, ClientError, PatchFeedRequest, PatchHTMLRequest,
+    AvailableListsRequest, ClientError, PatchFeedRequest, PatchHTMLRequest, ThreadRequest,
 };
-use crate::lore::mailing_list::MailingList;
+use crate::lore::mailing_list::{ListPreferences, MailingList};
+use crate::lore::mbox::{self, Message, MBOX_SEPARATOR};
 use crate::lore::patch::{Patch, PatchFeed, PatchRegex};
+use crate::lore::query::LoreQuery;
+use crate::lore::series::SeriesParts;
+use crate::lore::thread::{Thread, ThreadMessage};
+use chrono::{DateTime, Utc};
 use derive_getters::Getters;
 use regex::Regex;
 use serde_xml_rs::from_str;
-use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
-use std::mem::swap;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::LazyLock;
@@ -21,121 +26,396 @@ use thiserror::Error;
 #[cfg(test)]
 mod tests;
 
-const LORE_PAGE_SIZE: usize = 200;
+/// How many entries lore returns per page of feeds and listings
+pub const LORE_PAGE_SIZE: usize = 200;
 
-#[derive(Getters)]
+#[derive(Getters, Clone)]
 pub struct LoreSession {
     representative_patches_ids: Vec<String>,
     #[getter(skip)]
     processed_patches_map: HashMap<String, Patch>,
     #[getter(skip)]
     patch_regex: PatchRegex,
+    /// Feeds of the lists merged into the session, usually only one
     #[getter(skip)]
-    target_list: String,
+    list_feeds: Vec<ListFeed>,
+    query: LoreQuery,
+    /// Archive URL under which each bare message-id was processed, so
+    /// messages cross-posted to several of the lists are processed once
     #[getter(skip)]
+    href_of_id: HashMap<String, String>,
+    /// Lists each processed patch was seen in
+    #[getter(skip)]
+    lists_of: HashMap<String, Vec<String>>,
+    /// Series the processed patches were assembled into
+    #[getter(skip)]
+    series_groups: Vec<SeriesGroup>,
+    /// Index in `series_groups` of the series of each processed patch
+    #[getter(skip)]
+    series_group_of: HashMap<String, usize>,
+}
+
+/// Feed of one of the lists of a [LoreSession], as fetched so far
+#[derive(Clone)]
+struct ListFeed {
+    list: String,
     min_index: usize,
+    /// Date of the oldest patch fetched from the list, if any
+    oldest: Option<DateTime<Utc>>,
+    is_exhausted: bool,
+}
+
+impl ListFeed {
+    fn new(list: String) -> Self {
+        ListFeed {
+            list,
+            min_index: 0,
+            oldest: None,
+            is_exhausted: false,
+        }
+    }
+}
+
+/// Patches of the feed taken as parts of the same series
+#[derive(Clone)]
+struct SeriesGroup {
+    author_email: String,
+    version: usize,
+    parts: SeriesParts,
+    /// Message-id of the patch listed for the series, if any was seen
+    representative: Option<String>,
+}
+
+impl SeriesGroup {
+    /// Whether `patch` has the author and the tag of the parts of the series
+    fn matches(&self, patch: &Patch) -> bool {
+        self.author_email
+            .eq_ignore_ascii_case(&patch.author().email)
+            && self.version == patch.version()
+            && self.parts.total() == patch.total_in_series()
+    }
 }
 
 #[derive(Error, Debug)]
 pub enum LoreSessionError {
     #[error(transparent)]
     FromLoreAPIClient(#[from] ClientError),
+    #[error(transparent)]
+    FromIo(#[from] io::Error),
+    #[error("No patches found in the thread")]
+    NoPatchesFound,
 }
 
 impl LoreSession {
     pub fn new(target_list: String) -> LoreSession {
+        Self::with_query(target_list, LoreQuery::default())
+    }
+
+    /// Creates a session whose feed is restricted by `query`. The results go
+    /// through the same representative patch and paging logic as the plain
+    /// latest feed.
+    pub fn with_query(target_list: String, query: LoreQuery) -> LoreSession {
+        Self::merged(vec![target_list], query)
+    }
+
+    /// Creates a session whose feed merges the ones of `target_lists`, with
+    /// the patches sent to several of them listed once and the patchsets
+    /// sorted by date, the newest first.
+    pub fn merged(target_lists: Vec<String>, query: LoreQuery) -> LoreSession {
         LoreSession {
-            target_list,
+            list_feeds: target_lists.into_iter().map(ListFeed::new).collect(),
+            query,
             representative_patches_ids: Vec::new(),
             processed_patches_map: HashMap::new(),
             patch_regex: PatchRegex::new(),
-            min_index: 0,
+            href_of_id: HashMap::new(),
+            lists_of: HashMap::new(),
+            series_groups: Vec::new(),
+            series_group_of: HashMap::new(),
+        }
+    }
+
+    /// Creates a session with the representative patches saved in
+    /// `feed_snapshot`, as if they were just fetched.
+    pub fn from_snapshot(
+        target_list: String,
+        query: LoreQuery,
+        feed_snapshot: &FeedSnapshot,
+    ) -> LoreSession {
+        Self::with_query(target_list, query).with_snapshot(feed_snapshot)
+    }
+
+    /// Fills the session, which should be fresh, with the representative
+    /// patches saved in `feed_snapshot`, as if they were just fetched.
+    pub fn with_snapshot(mut self, feed_snapshot: &FeedSnapshot) -> LoreSession {
+        for patch in feed_snapshot.representative_patches() {
+            let message_id = patch.message_id().href.clone();
+            self.representative_patches_ids.push(message_id.clone());
+            self.processed_patches_map
+                .insert(message_id.clone(), patch.clone());
+            if let Some(lists) = feed_snapshot.lists().get(&message_id) {
+                self.lists_of.insert(message_id, lists.clone());
+            }
+        }
+        self
+    }
+
+    /// Takes a snapshot of the representative patches processed so far.
+    pub fn snapshot(&self) -> FeedSnapshot {
+        let feed_snapshot = FeedSnapshot::new(
+            self.representative_patches_ids
+                .iter()
+                .map(|message_id| self.processed_patches_map[message_id].clone())
+                .collect(),
+        );
+        if !self.is_merged() {
+            return feed_snapshot;
         }
+        feed_snapshot.with_lists(
+            self.representative_patches_ids
+                .iter()
+                .filter_map(|message_id| {
+                    let lists = self.lists_of.get(message_id)?;
+                    Some((message_id.clone(), lists.clone()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Whether the feeds of several lists are merged into the session
+    pub fn is_merged(&self) -> bool {
+        self.list_feeds.len() > 1
+    }
+
+    /// Lists the patch `message_id` was seen in, in the order they were
+    /// fetched. Patches restored from snapshots of single lists have none.
+    pub fn lists_of(&self, message_id: &str) -> &[String] {
+        self.lists_of
+            .get(message_id)
+            .map_or(&[], |lists| lists.as_slice())
     }
 
     pub fn get_processed_patch(&self, message_id: &str) -> Option<&Patch> {
         self.processed_patches_map.get(message_id)
     }
 
+    /// Parts seen in the feed of the series of the patch `message_id`, if it
+    /// was processed. Sessions restored from snapshots know no parts, and
+    /// neither do feeds restricted by a query, as the query may leave parts
+    /// of a series out.
+    pub fn series_parts(&self, message_id: &str) -> Option<&SeriesParts> {
+        if !self.query.is_empty() {
+            return None;
+        }
+        self.series_group_of
+            .get(message_id)
+            .map(|&series_group| &self.series_groups[series_group].parts)
+    }
+
     pub fn process_n_representative_patches<T: PatchFeedRequest>(
         &mut self,
         lore_api_client: &T,
         n: usize,
     ) -> Result<(), LoreSessionError> {
         while self.representative_patches_ids.len() < n {
-            let feed_response_body =
-                lore_api_client.request_patch_feed(&self.target_list, self.min_index)?;
+            // Merged feeds only end once all of their lists do
+            let Some(list_feed) = self.next_list_feed() else {
+                return Err(ClientError::EndOfFeed.into());
+            };
+            let ListFeed {
+                list, min_index, ..
+            } = self.list_feeds[list_feed].clone();
 
-            let patch_feed = from_str(&feed_response_body).unwrap();
+            let feed_response_body =
+                match lore_api_client.request_patch_feed(&list, &self.query, min_index) {
+                    Ok(feed_response_body) => feed_response_body,
+                    Err(ClientError::EndOfFeed) if self.is_merged() => {
+                        self.list_feeds[list_feed].is_exhausted = true;
+                        continue;
+                    }
+                    Err(client_error) => return Err(client_error.into()),
+                };
 
-            let processed_patches_ids = self.process_patches(patch_feed);
-            self.update_representative_patches(processed_patches_ids);
+            let patch_feed: PatchFeed = from_str(&feed_response_body).unwrap();
+            let page_oldest = patch_feed
+                .patches()
+                .iter()
+                .filter_map(|patch| *patch.updated())
+                .min();
+
+            let processed_patches_ids = self.process_patches(patch_feed, &list);
+            self.assemble_series(processed_patches_ids);
+            if self.is_merged() {
+                self.sort_representatives_by_date();
+            }
 
-            self.min_index += LORE_PAGE_SIZE;
+            let list_feed = &mut self.list_feeds[list_feed];
+            list_feed.oldest = page_oldest.or(list_feed.oldest);
+            list_feed.min_index += LORE_PAGE_SIZE;
         }
 
         Ok(())
     }
 
-    fn process_patches(&mut self, patch_feed: PatchFeed) -> Vec<String> {
+    /// Picks the list whose feed should be fetched next: one that wasn't
+    /// fetched yet or, otherwise, the one whose oldest fetched patch is the
+    /// newest, as it's the one that may still hold newer patches than the
+    /// others. Returns `None` once every feed is exhausted.
+    fn next_list_feed(&self) -> Option<usize> {
+        self.list_feeds
+            .iter()
+            .enumerate()
+            .filter(|(_, list_feed)| !list_feed.is_exhausted)
+            // Ties go to the first of the lists
+            .rev()
+            .max_by_key(|(_, list_feed)| (list_feed.min_index == 0, list_feed.oldest))
+            .map(|(index, _)| index)
+    }
+
+    /// Sorts the representative patches by date, the newest first and the
+    /// undated last.
+    fn sort_representatives_by_date(&mut self) {
+        let processed_patches_map = &self.processed_patches_map;
+        self.representative_patches_ids.sort_by_key(|message_id| {
+            let updated = *processed_patches_map[message_id].updated();
+            (updated.is_none(), std::cmp::Reverse(updated))
+        });
+    }
+
+    /// Processes the patches of a page of the feed of `list`, returning the
+    /// message-ids of the ones not processed before. Patches already
+    /// processed, maybe from the feed of another list, are only recorded as
+    /// seen in `list`.
+    fn process_patches(&mut self, patch_feed: PatchFeed, list: &str) -> Vec<String> {
         let mut processed_patches_ids: Vec<String> = Vec::new();
 
         for mut patch in patch_feed.patches().clone() {
             patch.update_patch_metadata(&self.patch_regex);
 
-            if !self
-                .processed_patches_map
-                .contains_key(&patch.message_id().href)
-            {
-                processed_patches_ids.push(patch.message_id().href.clone());
-                self.processed_patches_map
-                    .insert(patch.message_id().href.clone(), patch);
+            let message_id = self
+                .href_of_id
+                .entry(patch.message_id().id().to_string())
+                .or_insert_with(|| patch.message_id().href.clone())
+                .clone();
+            let lists = self.lists_of.entry(message_id.clone()).or_default();
+            if !lists.iter().any(|seen_list| seen_list == list) {
+                lists.push(list.to_string());
+            }
+
+            if let Entry::Vacant(entry) = self.processed_patches_map.entry(message_id.clone()) {
+                processed_patches_ids.push(message_id);
+                entry.insert(patch);
             }
         }
 
         processed_patches_ids
     }
 
-    fn update_representative_patches(&mut self, processed_patches_ids: Vec<String>) {
-        let mut patch: &Patch;
-        let mut patch_number_in_series: usize;
-
-        for message_id in processed_patches_ids {
-            patch = self.processed_patches_map.get(&message_id).unwrap();
-            patch_number_in_series = patch.number_in_series();
-
-            if patch_number_in_series > 1 {
+    /// Assembles the patches just processed into series, listing the cover
+    /// letter of each series (or its first patch, if there's no cover letter)
+    /// as its representative patch.
+    ///
+    /// Patches join the series of the message they reply to. Patches that
+    /// aren't threaded, or reply to messages that weren't processed (yet),
+    /// join the latest series of the same author and tag still missing their
+    /// part, so a series whose patches aren't threaded to the cover letter is
+    /// listed once.
+    fn assemble_series(&mut self, processed_patches_ids: Vec<String>) {
+        let mut new_representatives: Vec<String> = Vec::new();
+
+        // The feed lists the newest first, so the messages that patches reply
+        // to come after them
+        for message_id in processed_patches_ids.iter().rev() {
+            let patch = self.processed_patches_map[message_id].clone();
+            let series_group = match self.find_series_group(&patch) {
+                Some(series_group) => series_group,
+                None => {
+                    self.series_groups.push(SeriesGroup {
+                        author_email: patch.author().email.clone(),
+                        version: patch.version(),
+                        parts: SeriesParts::new(patch.total_in_series()),
+                        representative: None,
+                    });
+                    self.series_groups.len() - 1
+                }
+            };
+            self.series_group_of
+                .insert(message_id.clone(), series_group);
+
+            let series_group = &mut self.series_groups[series_group];
+            series_group
+                .parts
+                .add(patch.number_in_series(), message_id.clone());
+
+            let is_better_representative = match &series_group.representative {
+                None => patch.number_in_series() <= 1,
+                Some(representative) => {
+                    patch.number_in_series() == 0
+                        && self.processed_patches_map[representative].number_in_series() != 0
+                }
+            };
+            if !is_better_representative {
                 continue;
             }
-
-            if patch_number_in_series == 1 {
-                if let Some(in_reply_to) = &patch.in_reply_to() {
-                    if let Some(patch_in_reply_to) =
-                        self.processed_patches_map.get(&in_reply_to.href)
+            match series_group.representative.replace(message_id.clone()) {
+                Some(previous_representative) => {
+                    for representative in self
+                        .representative_patches_ids
+                        .iter_mut()
+                        .chain(new_representatives.iter_mut())
                     {
-                        if (patch_in_reply_to.number_in_series() == 0)
-                            && (patch.version() == patch_in_reply_to.version())
-                        {
-                            continue;
-                        };
-                    };
-                };
+                        if *representative == previous_representative {
+                            *representative = message_id.clone();
+                        }
+                    }
+                }
+                None => new_representatives.push(message_id.clone()),
             }
+        }
 
-            self.representative_patches_ids
-                .push(patch.message_id().href.clone());
+        new_representatives.sort_by_key(|representative| {
+            processed_patches_ids
+                .iter()
+                .position(|message_id| message_id == representative)
+        });
+        self.representative_patches_ids.extend(new_representatives);
+    }
+
+    /// Finds the series `patch` is a part of, among the ones assembled so far.
+    fn find_series_group(&self, patch: &Patch) -> Option<usize> {
+        if let Some(in_reply_to) = patch.in_reply_to() {
+            // Messages are processed under the URL of the first list they
+            // were seen in, which may not be the one of the reply
+            let in_reply_to = self
+                .href_of_id
+                .get(in_reply_to.id())
+                .unwrap_or(&in_reply_to.href);
+            if let Some(&series_group) = self.series_group_of.get(in_reply_to) {
+                if self.series_groups[series_group].matches(patch) {
+                    return Some(series_group);
+                }
+            }
+            // Replies to processed messages of other series start new ones
+            if self.processed_patches_map.contains_key(in_reply_to) {
+                return None;
+            }
         }
+
+        self.series_groups.iter().rposition(|series_group| {
+            series_group.matches(patch) && !series_group.parts.contains(patch.number_in_series())
+        })
     }
 
     pub fn get_patch_feed_page(&self, page_size: usize, page_number: usize) -> Option<Vec<&Patch>> {
         let mut patch_feed_page: Vec<&Patch> = Vec::new();
-        let representative_patches_ids_max_index: usize = self.representative_patches_ids.len() - 1;
         let lower_end: usize = page_size * (page_number - 1);
         let mut upper_end: usize = page_size * page_number;
 
-        if representative_patches_ids_max_index <= lower_end {
+        // Narrow search queries may yield less patchsets than a page
+        if self.representative_patches_ids.len() <= lower_end {
             return None;
         }
+        let representative_patches_ids_max_index: usize = self.representative_patches_ids.len() - 1;
 
         if representative_patches_ids_max_index < upper_end - 1 {
             upper_end = representative_patches_ids_max_index + 1;
@@ -153,7 +433,11 @@ impl LoreSession {
     }
 }
 
-pub fn download_patchset(output_dir: &str, patch: &Patch) -> io::Result<String> {
+/// Downloads the patchset represented by `patch` using `b4 am`.
+///
+/// Returns the path where `b4` was told to write the patchset. Note that `b4`
+/// failures aren't reported, so callers should check if the file exists.
+pub fn download_patchset_with_b4(output_dir: &str, patch: &Patch) -> io::Result<String> {
     let message_id: &str = &patch.message_id().href;
     let mbox_name: String = extract_mbox_name_from_message_id(message_id);
 
@@ -181,11 +465,151 @@ pub fn download_patchset(output_dir: &str, patch: &Patch) -> io::Result<String>
     Ok(filepath)
 }
 
+/// Downloads the patchset represented by `patch` without depending on `b4`.
+///
+/// The whole thread of the patchset is fetched and the messages of the same
+/// version as `patch` are written to `output_dir` in the same layout `b4 am`
+/// produces: the patches, ordered by their number in the series, go to the
+/// `.mbx` file and the cover letter, if any, goes to the `.cover` file beside
+/// it. Replies are left out and, if a patch was sent more than once, the last
+/// copy wins. Fails if no patch is found, instead of writing an empty `.mbx`.
+pub fn download_patchset<T>(
+    lore_api_client: &T,
+    output_dir: &str,
+    patch: &Patch,
+) -> Result<String, LoreSessionError>
+where
+    T: ThreadRequest,
+{
+    let mbox_name: String = extract_mbox_name_from_message_id(&patch.message_id().href);
+
+    if !Path::new(output_dir).exists() {
+        fs::create_dir_all(output_dir)?;
+    }
+
+    let filepath: String = format!("{output_dir}/{mbox_name}");
+    if Path::new(&filepath).exists() {
+        return Ok(filepath);
+    }
+
+    let thread_mbox =
+        lore_api_client.request_thread_mbox(patch.message_id().list(), patch.message_id().id())?;
+    let thread = Thread::from_mbox(&thread_mbox);
+    let (cover_letter, patches) = select_patchset_messages(&thread, patch);
+    // An empty file would be taken as the downloaded patchset from then on
+    if patches.is_empty() {
+        return Err(LoreSessionError::NoPatchesFound);
+    }
+
+    // The cover letter goes first, as the patchset counts as downloaded once
+    // its `.mbx` file exists
+    if let Some(cover_letter) = cover_letter {
+        let cover_letter_path = filepath.replace(".mbx", ".cover");
+        json_file::write_atomically(cover_letter_path, format_mbx_message(cover_letter))?;
+    }
+
+    let patchset_mbx: String = patches.into_values().map(format_mbx_message).collect();
+    json_file::write_atomically(&filepath, patchset_mbx)?;
+
+    Ok(filepath)
+}
+
+/// Picks, from the messages of `thread`, the cover letter and the patches of
+/// the same version as `patch`, by their number in the series. If no message
+/// of the thread looks like part of the patchset, the message of `patch`
+/// itself is taken as a single patch.
+pub(crate) fn select_patchset_messages<'a>(
+    thread: &'a Thread,
+    patch: &Patch,
+) -> (
+    Option<&'a ThreadMessage>,
+    BTreeMap<usize, &'a ThreadMessage>,
+) {
+    let patch_regex = PatchRegex::new();
+    let mut cover_letter: Option<&ThreadMessage> = None;
+    let mut patches: BTreeMap<usize, &ThreadMessage> = BTreeMap::new();
+
+    for message in thread.messages() {
+        let subject = message.subject();
+        if is_reply_subject(subject) {
+            continue;
+        }
+
+        let Some(patch_tag) = patch_regex.re_patch_tag.find(subject) else {
+            continue;
+        };
+        let patch_tag = patch_tag.as_str();
+
+        let version: usize = patch_regex
+            .re_patch_version
+            .captures(patch_tag)
+            .and_then(|capture| capture.get(1))
+            .and_then(|version| version.as_str().parse().ok())
+            .unwrap_or(1);
+        if version != patch.version() {
+            continue;
+        }
+
+        let number_in_series: usize = patch_regex
+            .re_patch_series
+            .captures(patch_tag)
+            .and_then(|capture| capture.get(1))
+            .and_then(|number| number.as_str().parse().ok())
+            .unwrap_or(1);
+
+        if number_in_series == 0 {
+            cover_letter = Some(message);
+        } else {
+            patches.insert(number_in_series, message);
+        }
+    }
+
+    if patches.is_empty() {
+        if let Some(message) = thread
+            .messages()
+            .iter()
+            .find(|message| message.message_id() == patch.message_id().id())
+        {
+            if cover_letter.is_none_or(|cover_letter| cover_letter != message) {
+                patches.insert(1, message);
+            }
+        }
+    }
+
+    (cover_letter, patches)
+}
+
+/// Whether `subject` is the one of a patch (or RFC), told apart by its
+/// `[PATCH ...]` tag, and not of a reply to one.
+pub(crate) fn is_patch_subject(subject: &str) -> bool {
+    static PATCH_REGEX: LazyLock<PatchRegex> = LazyLock::new(PatchRegex::new);
+
+    !is_reply_subject(subject) && PATCH_REGEX.re_patch_tag.is_match(subject)
+}
+
+fn is_reply_subject(subject: &str) -> bool {
+    subject
+        .trim_start()
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+}
+
+/// Formats a message the way `b4 am` does, so that [split_patchset] and the
+/// reply logic can handle the downloaded patchset.
+fn format_mbx_message(message: &ThreadMessage) -> String {
+    format!("{MBOX_SEPARATOR}\n{}\n", message.message().to_patch_text())
+}
+
+/// Derives the name of the mbox file of a patchset from the URL of its
+/// message-id, dropping the scheme and host of the public-inbox instance.
 fn extract_mbox_name_from_message_id(message_id: &str) -> String {
-    let mut mbox_name: String = message_id
-        .replace(r#"http://lore.kernel.org/"#, "")
-        .replace(r#"https://lore.kernel.org/"#, "")
-        .replace('/', ".");
+    let message_path = message_id
+        .split_once("://")
+        .map_or(message_id, |(_, url_without_scheme)| url_without_scheme);
+    let message_path = message_path
+        .split_once('/')
+        .map_or(message_path, |(_, message_path)| message_path);
+    let mut mbox_name: String = message_path.replace('/', ".");
 
     if !mbox_name.ends_with('.') {
         mbox_name.push('.');
@@ -208,87 +632,51 @@ pub fn split_patchset(patchset_path_str: &str) -> Result<Vec<String>, String> {
     }
 
     if cover_letter_path.exists() && cover_letter_path.is_file() {
-        extract_patches(cover_letter_path, &mut patches);
+        extract_patches(cover_letter_path, &mut patches)
+            .map_err(|error| format!("{}: {error}", cover_letter_path.display()))?;
     }
 
-    extract_patches(patchset_path, &mut patches);
+    extract_patches(patchset_path, &mut patches)
+        .map_err(|error| format!("{}: {error}", patchset_path.display()))?;
 
     Ok(patches)
 }
 
 /// Takes the string that represents a patch and splits it into the cover and the actual diff.
 ///
-/// The cover is everything before the first "---" line.
+/// The cover is everything before the first "---" line of the message body.
 pub fn split_cover(patch: &str) -> (&str, &str) {
-    let mut cover: &str = patch;
-    let mut diff: &str = "";
-
-    if let Some(cover_end) = patch.find("\n---\n") {
-        cover = &patch[..cover_end + 1];
-        diff = &patch[cover_end + 5..];
+    let (_, body) = mbox::split_header_block(patch);
+    let body_start = patch.len() - body.len();
+
+    let cover_end = if body.starts_with("---\n") {
+        Some(body_start)
+    } else {
+        body.find("\n---\n").map(|index| body_start + index + 1)
+    };
+
+    match cover_end {
+        Some(cover_end) => (&patch[..cover_end], &patch[cover_end + 4..]),
+        None => (patch, ""),
     }
-
-    (cover, diff)
 }
 
-fn extract_patches(mbox_path: &Path, patches: &mut Vec<String>) {
-    let mut current_patch: String = String::new();
-    let mut is_reading_patch: bool = false;
-    let mut is_last_line: bool = false;
-
-    let mbox_reader: BufReader<fs::File> = io::BufReader::new(fs::File::open(mbox_path).unwrap());
-
-    for line in mbox_reader.lines() {
-        let line = line.unwrap();
-
-        if line.starts_with("Subject: ") {
-            is_reading_patch = true;
-        } else if is_reading_patch && line.trim_end().eq("--") {
-            is_last_line = true;
-        } else if is_last_line {
-            current_patch.push_str(&line);
-            current_patch.push('\n');
+fn extract_patches(mbox_path: &Path, patches: &mut Vec<String>) -> io::Result<()> {
+    let mbox = fs::read_to_string(mbox_path)?;
 
-            let mut patch_to_add = String::new();
-            swap(&mut patch_to_add, &mut current_patch);
-            patches.push(patch_to_add);
-
-            is_reading_patch = false;
-            is_last_line = false;
-        } else if is_reading_patch && line.trim_end().eq("From git@z Thu Jan  1 00:00:00 1970") {
-            let mut patch_to_add = String::new();
-            swap(&mut patch_to_add, &mut current_patch);
-            patches.push(patch_to_add);
-
-            is_reading_patch = false;
-        }
-
-        if is_reading_patch {
-            current_patch.push_str(&line);
-            current_patch.push('\n');
-        }
+    for mut message in mbox::parse_mbox(&mbox) {
+        message.trim_after_signature();
+        patches.push(message.to_patch_text());
     }
 
-    if !current_patch.is_empty() {
-        patches.push(current_patch);
-    }
+    Ok(())
 }
 
 pub fn save_bookmarked_patchsets(
     bookmarked_patchsets: &Vec<Patch>,
     filepath: &str,
 ) -> io::Result<()> {
-    if let Some(parent) = Path::new(filepath).parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let tmp_filename = format!("{}.tmp", filepath);
-    {
-        let tmp_file = File::create(&tmp_filename)?;
-        serde_json::to_writer(tmp_file, &bookmarked_patchsets)?;
-    }
-    fs::rename(tmp_filename, filepath)?;
-    Ok(())
+    json_file::save_json_atomically(filepath, bookmarked_patchsets)
 }
 
 pub fn load_bookmarked_patchsets(filepath: &str) -> io::Result<Vec<Patch>> {
@@ -363,17 +751,7 @@ fn process_available_lists(available_lists_str: String) -> Vec<MailingList> {
 }
 
 pub fn save_available_lists(available_lists: &Vec<MailingList>, filepath: &str) -> io::Result<()> {
-    if let Some(parent) = Path::new(filepath).parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let tmp_filename = format!("{}.tmp", filepath);
-    {
-        let tmp_file = File::create(&tmp_filename)?;
-        serde_json::to_writer(tmp_file, &available_lists)?;
-    }
-    fs::rename(tmp_filename, filepath)?;
-    Ok(())
+    json_file::save_json_atomically(filepath, available_lists)
 }
 
 pub fn load_available_lists(filepath: &str) -> io::Result<Vec<MailingList>> {
@@ -382,6 +760,16 @@ pub fn load_available_lists(filepath: &str) -> io::Result<Vec<MailingList>> {
     Ok(available_lists)
 }
 
+pub fn save_list_preferences(list_preferences: &ListPreferences, filepath: &str) -> io::Result<()> {
+    json_file::save_json_atomically(filepath, list_preferences)
+}
+
+pub fn load_list_preferences(filepath: &str) -> io::Result<ListPreferences> {
+    let list_preferences_file = File::open(filepath)?;
+    let list_preferences = serde_json::from_reader(list_preferences_file)?;
+    Ok(list_preferences)
+}
+
 pub fn prepare_reply_patchset_with_reviewed_by<T>(
     lore_api_client: &T,
     tmp_dir: &Path,
@@ -395,16 +783,9 @@ where
 {
     let mut git_reply_commands: Vec<Command> = Vec::new();
 
-    static RE_MESSAGE_ID: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r#"(?m)^Message-Id: <(.*?)>"#).unwrap());
-
     for patch in patches.iter() {
-        let message_id = RE_MESSAGE_ID
-            .captures(patch)
-            .unwrap()
-            .get(1)
-            .unwrap()
-            .as_str();
+        let parsed_patch = Message::parse(patch);
+        let message_id = parsed_patch.message_id().unwrap();
 
         let reply_path = tmp_dir.join(format!("{message_id}-reply.mbx"));
         let mut reply = generate_patch_reply_template(patch);
@@ -422,33 +803,77 @@ where
     Ok(git_reply_commands)
 }
 
+/// Writes the patches of a downloaded patchset (see [split_patchset]) to
+/// `tmp_dir` and returns the commands that apply them with `git am` to the
+/// repository in the current directory. If `base_commit` is known, the
+/// patches are applied on top of it, in a detached `HEAD`, so no branch is
+/// touched. Cover letters, which carry no diff, are left out.
+///
+/// # Errors
+///
+/// Returns an error if a patch can't be written to `tmp_dir`.
+pub fn prepare_apply_patchset(
+    tmp_dir: &Path,
+    patches: &[String],
+    base_commit: Option<&str>,
+) -> io::Result<Vec<Command>> {
+    let mut git_apply_commands: Vec<Command> = Vec::new();
+
+    if let Some(base_commit) = base_commit {
+        let mut git_checkout_command = Command::new("git");
+        git_checkout_command.args(["checkout", "--detach", base_commit]);
+        git_apply_commands.push(git_checkout_command);
+    }
+
+    let mut git_am_command = Command::new("git");
+    git_am_command.args(["am", "--3way"]);
+    for (index, patch) in patches
+        .iter()
+        .filter(|patch| patch.contains("\ndiff --git "))
+        .enumerate()
+    {
+        let patch_path = tmp_dir.join(format!("{:04}.patch", index + 1));
+        fs::write(&patch_path, patch)?;
+        git_am_command.arg(patch_path);
+    }
+    git_apply_commands.push(git_am_command);
+
+    Ok(git_apply_commands)
+}
+
 fn generate_patch_reply_template(patch_contents: &str) -> String {
+    let patch = Message::parse(patch_contents);
     let mut reply_template = String::new();
-    let mut patch_lines_iterator = patch_contents.lines();
 
-    // Process the headers
-    for line in patch_lines_iterator.by_ref() {
-        let mut line_to_push = String::new();
+    let subject = patch.subject();
+    if is_reply_subject(subject) {
+        reply_template.push_str(&format!("Subject: {subject}\n"));
+    } else {
+        reply_template.push_str(&format!("Subject: Re: {subject}\n"));
+    }
 
-        if line.starts_with("Subject: ") {
-            line_to_push = line.replace("Subject: ", "Subject: Re: ") + "\n";
-        } else if line.starts_with("From: ")
-            || line.starts_with("Date: ")
-            || line.starts_with("Message-Id: ")
+    // The reply is composed by the reviewer, so only keep the headers that
+    // don't identify the original message
+    let mut skipped_headers = vec!["Subject", "From", "Date", "Message-Id"];
+    if patch.transcoded() {
+        skipped_headers.extend(["Content-Type", "Content-Transfer-Encoding"]);
+    }
+    for (name, value) in patch.headers() {
+        if !skipped_headers
+            .iter()
+            .any(|skipped_header| name.eq_ignore_ascii_case(skipped_header))
         {
-            continue;
-        } else if !line.trim().is_empty() {
-            line_to_push = format!("{}\n", line);
-        } else if line.trim().is_empty() && !reply_template.is_empty() {
-            reply_template.push('\n');
-            break;
+            reply_template.push_str(&format!("{name}: {value}\n"));
         }
-
-        reply_template.push_str(&line_to_push);
     }
+    if patch.transcoded() {
+        reply_template.push_str("Content-Type: text/plain; charset=\"utf-8\"\n");
+        reply_template.push_str("Content-Transfer-Encoding: 8bit\n");
+    }
+    reply_template.push('\n');
 
-    // After processing headers, just quote-reply remaining lines
-    for line in patch_lines_iterator {
+    // After processing headers, just quote-reply the body
+    for line in patch.body().trim_end().lines() {
         reply_template.push_str(&format!("> {}\n", line));
     }
 
@@ -517,17 +942,7 @@ pub fn save_reviewed_patchsets(
     reviewed_patchsets: &HashMap<String, Vec<usize>>,
     filepath: &str,
 ) -> io::Result<()> {
-    if let Some(parent) = Path::new(filepath).parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let tmp_filename = format!("{}.tmp", filepath);
-    {
-        let tmp_file = File::create(&tmp_filename)?;
-        serde_json::to_writer(tmp_file, &reviewed_patchsets)?;
-    }
-    fs::rename(tmp_filename, filepath)?;
-    Ok(())
+    json_file::save_json_atomically(filepath, reviewed_patchsets)
 }
 
 pub fn load_reviewed_patchsets(filepath: &str) -> io::Result<HashMap<String, Vec<usize>>> {
@@ -1,7 +1,7 @@
 use io::Read;
 
 use super::*;
-use crate::lore::patch::Author;
+use crate::lore::patch::{Author, MessageID};
 
 use mockall::mock;
 use std::fs;
@@ -12,6 +12,7 @@ mock! {
         fn request_patch_feed(
                     &self,
                     target_list: &str,
+                    query: &LoreQuery,
                     min_index: usize,
                 ) -> Result<String, ClientError>;
     }
@@ -28,6 +29,13 @@ mock! {
             message_id: &str,
         ) -> Result<String, ClientError>;
     }
+    impl ThreadRequest for BlockingLoreAPIClient {
+        fn request_thread_mbox(
+            &self,
+            target_list: &str,
+            message_id: &str,
+        ) -> Result<String, ClientError>;
+    }
 }
 
 #[test]
@@ -50,11 +58,11 @@ fn should_process_one_representative_patch() {
 
     lore_api_client
         .expect_request_patch_feed()
-        .withf(move |target_list_arg, min_index_arg| {
-            target_list_arg == target_list && *min_index_arg == 0
+        .withf(move |target_list_arg, query_arg, min_index_arg| {
+            target_list_arg == target_list && query_arg.is_empty() && *min_index_arg == 0
         })
         .times(1)
-        .returning(move |_, _| Ok(fs::read_to_string(src_path).unwrap()));
+        .returning(move |_, _, _| Ok(fs::read_to_string(src_path).unwrap()));
 
     let mut lore_session: LoreSession = LoreSession::new(target_list.to_string());
 
@@ -115,11 +123,11 @@ fn should_process_multiple_representative_patches() {
 
     lore_api_client
         .expect_request_patch_feed()
-        .withf(move |target_list_arg, min_index_arg| {
-            target_list_arg == target_list && *min_index_arg == 0
+        .withf(move |target_list_arg, query_arg, min_index_arg| {
+            target_list_arg == target_list && query_arg.is_empty() && *min_index_arg == 0
         })
         .times(1)
-        .returning(move |_, _| Ok(fs::read_to_string(src_path).unwrap()));
+        .returning(move |_, _, _| Ok(fs::read_to_string(src_path).unwrap()));
 
     let mut lore_session: LoreSession = LoreSession::new(target_list.to_string());
 
@@ -156,6 +164,186 @@ fn should_process_multiple_representative_patches() {
     );
 }
 
+#[test]
+fn should_assemble_series_and_detect_missing_or_duplicated_parts() {
+    let src_path = "src/test_samples/lore_session/assemble_series/patch_feed_sample.xml";
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index == 0)
+        .returning(move |_, _, _| Ok(fs::read_to_string(src_path).unwrap()));
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+    let mut lore_session = LoreSession::new("some-list".to_string());
+
+    let _ = lore_session.process_n_representative_patches(&lore_api_client, 10);
+
+    let message_id = |id: &str| format!("http://lore.kernel.org/some-subsystem/{id}/");
+    assert_eq!(
+        &vec![
+            message_id("unthreaded-0@foo.bar"),
+            message_id("threaded-0@johnson.com"),
+            message_id("v1-threaded-0@johnson.com"),
+        ],
+        lore_session.representative_patches_ids(),
+        "Unthreaded series should be listed once, by their cover letter"
+    );
+
+    let unthreaded_parts = lore_session
+        .series_parts(&message_id("unthreaded-3@foo.bar"))
+        .unwrap();
+    assert_eq!(vec![2], unthreaded_parts.missing());
+    assert!(unthreaded_parts.duplicated().is_empty());
+
+    let threaded_parts = lore_session
+        .series_parts(&message_id("threaded-0@johnson.com"))
+        .unwrap();
+    assert!(threaded_parts.missing().is_empty());
+    assert_eq!(vec![1], threaded_parts.duplicated());
+    assert_eq!(
+        Some("duplicated 1/2".to_string()),
+        threaded_parts.problems()
+    );
+
+    assert_eq!(
+        vec![1, 2],
+        lore_session
+            .series_parts(&message_id("v1-threaded-0@johnson.com"))
+            .unwrap()
+            .missing(),
+        "Replies to other versions should start series of their own"
+    );
+}
+
+#[test]
+fn should_not_tell_series_parts_of_feeds_restricted_by_query() {
+    let src_path = "src/test_samples/lore_session/assemble_series/patch_feed_sample.xml";
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index == 0)
+        .returning(move |_, _, _| Ok(fs::read_to_string(src_path).unwrap()));
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+    let query = LoreQuery {
+        subject: Some("unthreaded".to_string()),
+        ..Default::default()
+    };
+    let mut lore_session = LoreSession::with_query("some-list".to_string(), query);
+
+    let _ = lore_session.process_n_representative_patches(&lore_api_client, 10);
+
+    assert_eq!(
+        None,
+        lore_session.series_parts("http://lore.kernel.org/some-subsystem/unthreaded-3@foo.bar/"),
+        "Parts left out by the query shouldn't be told as missing"
+    );
+}
+
+#[test]
+fn should_restore_session_from_snapshot() {
+    let src_path =
+        "src/test_samples/lore_session/process_representative_patch/patch_feed_sample_2.xml";
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .returning(move |_, _, _| Ok(fs::read_to_string(src_path).unwrap()));
+    let mut lore_session = LoreSession::new("some-list".to_string());
+    lore_session
+        .process_n_representative_patches(&lore_api_client, 3)
+        .unwrap();
+
+    let restored_lore_session = LoreSession::from_snapshot(
+        "some-list".to_string(),
+        LoreQuery::default(),
+        &lore_session.snapshot(),
+    );
+
+    assert_eq!(
+        lore_session.representative_patches_ids(),
+        restored_lore_session.representative_patches_ids()
+    );
+    assert_eq!(
+        lore_session.get_patch_feed_page(2, 2),
+        restored_lore_session.get_patch_feed_page(2, 2),
+        "Restored session should serve the same pages"
+    );
+}
+
+#[test]
+fn should_merge_feeds_of_several_lists() {
+    let src_dir = "src/test_samples/lore_session/merged_feed";
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index == 0)
+        .times(2)
+        .returning(move |target_list, _, _| {
+            Ok(fs::read_to_string(format!("{src_dir}/{target_list}_feed.xml")).unwrap())
+        });
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .times(2)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+    let mut lore_session = LoreSession::merged(
+        vec!["netdev".to_string(), "linux-kselftest".to_string()],
+        LoreQuery::default(),
+    );
+
+    let process_n_representative_patches_result =
+        lore_session.process_n_representative_patches(&lore_api_client, 10);
+
+    assert!(
+        matches!(
+            process_n_representative_patches_result,
+            Err(LoreSessionError::FromLoreAPIClient(ClientError::EndOfFeed))
+        ),
+        "Merged feeds should only end once all of their lists do"
+    );
+    let message_id = |list: &str, id: &str| format!("http://lore.kernel.org/{list}/{id}/");
+    assert_eq!(
+        &vec![
+            message_id("netdev", "fix-net@johnson.com"),
+            message_id("netdev", "tests-0@luma.rs"),
+            message_id("linux-kselftest", "fix-runner@silva.br"),
+            message_id("netdev", "old-net@johnson.com"),
+        ],
+        lore_session.representative_patches_ids(),
+        "Patchsets should be listed once, sorted by date"
+    );
+    assert_eq!(
+        &["netdev".to_string(), "linux-kselftest".to_string()],
+        lore_session.lists_of(&message_id("netdev", "tests-0@luma.rs"))
+    );
+    assert_eq!(
+        &["linux-kselftest".to_string()],
+        lore_session.lists_of(&message_id("linux-kselftest", "fix-runner@silva.br"))
+    );
+    assert!(
+        lore_session
+            .series_parts(&message_id("netdev", "tests-0@luma.rs"))
+            .unwrap()
+            .missing()
+            .is_empty(),
+        "Parts replying to the cover letter in another list should join its series"
+    );
+
+    let restored_lore_session = LoreSession::merged(
+        vec!["netdev".to_string(), "linux-kselftest".to_string()],
+        LoreQuery::default(),
+    )
+    .with_snapshot(&lore_session.snapshot());
+    assert_eq!(
+        lore_session.lists_of(&message_id("netdev", "tests-0@luma.rs")),
+        restored_lore_session.lists_of(&message_id("netdev", "tests-0@luma.rs"))
+    );
+}
+
 #[test]
 fn test_split_patchset_invalid_cases() {
     let ret: Result<Vec<String>, String> = split_patchset("invalid/path");
@@ -249,6 +437,148 @@ fn should_split_patchset_complete() {
     );
 }
 
+#[test]
+fn should_split_patchset_without_signatures() {
+    let patches = split_patchset(
+        "src/test_samples/lore_session/split_patchset/patchset_sample_without_signatures.mbx",
+    )
+    .unwrap();
+
+    assert_eq!(2, patches.len(), "Wrong number of patches");
+    assert!(
+        patches[0].ends_with(" SELECT 1;\n--\n SELECT 2;\n"),
+        "A `--` line in the diff shouldn't end the patch"
+    );
+    assert_eq!(
+        "Subject: [PATCH 2/2] file: Do bar\n\
+        From: Foo Bar <foo@bar.foo.bar>\n\
+        Date: Tue, 16 Jul 2024 16:52:00 +0000\n\
+        Message-Id: <1234.567-2-foo@bar.foo.bar>\n\
+        Content-Type: text/plain; charset=\"utf-8\"\n\
+        Content-Transfer-Encoding: 8bit\n\
+        \n\
+        Patch 2 description, café\n\
+        ---\n \
+        file.rs | 2 +-\n",
+        patches[1],
+        "Patch without signature should be decoded and kept whole"
+    );
+
+    let (cover, diff) = split_cover(&patches[0]);
+    assert!(cover.ends_with("\nPatch 1 description\n"));
+    assert!(diff.starts_with(" query.sql | 1 -\n"));
+}
+
+#[test]
+fn should_download_patchset_natively() {
+    let tmp_dir = Command::new("mktemp").arg("--directory").output().unwrap();
+    let tmp_dir = Path::new(std::str::from_utf8(&tmp_dir.stdout).unwrap().trim());
+    let output_dir = tmp_dir.to_str().unwrap();
+
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_thread_mbox()
+        .withf(|target_list: &str, message_id: &str| {
+            target_list == "some-list" && message_id == "1234.567-v2-0-foo@bar.foo.bar"
+        })
+        .returning(|_, _| {
+            Ok(fs::read_to_string(
+                "src/test_samples/lore_session/download_patchset/thread_sample.mbx",
+            )
+            .unwrap())
+        })
+        .once();
+
+    let mut patch = Patch::new(
+        "[PATCH v2 0/2] file: Implement something".to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.foo.bar".to_string(),
+        },
+        MessageID {
+            href: "http://lore.kernel.org/some-list/1234.567-v2-0-foo@bar.foo.bar/".to_string(),
+        },
+        None,
+        "2024-07-18T16:49:00Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+
+    let patchset_path = download_patchset(&lore_api_client, output_dir, &patch).unwrap();
+
+    assert_eq!(
+        format!("{output_dir}/some-list.1234.567-v2-0-foo@bar.foo.bar.mbx"),
+        patchset_path,
+        "Wrong patchset path"
+    );
+    assert_eq!(
+        fs::read_to_string("src/test_samples/lore_session/download_patchset/expected_patchset.mbx")
+            .unwrap(),
+        fs::read_to_string(&patchset_path).unwrap(),
+        "Patchset should have only the v2 patches, in series order"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            "src/test_samples/lore_session/download_patchset/expected_cover_letter.cover"
+        )
+        .unwrap(),
+        fs::read_to_string(patchset_path.replace(".mbx", ".cover")).unwrap(),
+        "Wrong cover letter"
+    );
+    assert_eq!(
+        3,
+        split_patchset(&patchset_path).unwrap().len(),
+        "Downloaded patchset should be splittable"
+    );
+
+    assert_eq!(
+        patchset_path,
+        download_patchset(&lore_api_client, output_dir, &patch).unwrap(),
+        "Already downloaded patchset shouldn't be requested again"
+    );
+
+    fs::remove_dir_all(tmp_dir).unwrap();
+}
+
+#[test]
+fn should_not_write_patchset_without_patches() {
+    let tmp_dir = Command::new("mktemp").arg("--directory").output().unwrap();
+    let tmp_dir = Path::new(std::str::from_utf8(&tmp_dir.stdout).unwrap().trim());
+    let output_dir = tmp_dir.to_str().unwrap();
+
+    let mut lore_api_client = MockBlockingLoreAPIClient::new();
+    lore_api_client
+        .expect_request_thread_mbox()
+        .returning(|_, _| Ok(String::new()))
+        .times(2);
+
+    let mut patch = Patch::new(
+        "[PATCH] file: Implement something".to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.foo.bar".to_string(),
+        },
+        MessageID {
+            href: "http://lore.kernel.org/some-list/1234.567-1-foo@bar.foo.bar/".to_string(),
+        },
+        None,
+        "2024-07-18T16:49:00Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+
+    for _ in 0..2 {
+        assert!(
+            matches!(
+                download_patchset(&lore_api_client, output_dir, &patch),
+                Err(LoreSessionError::NoPatchesFound)
+            ),
+            "Threads without patches should be requested again, not cached empty"
+        );
+    }
+    assert_eq!(0, fs::read_dir(tmp_dir).unwrap().count());
+
+    fs::remove_dir_all(tmp_dir).unwrap();
+}
+
 #[test]
 fn should_process_available_lists() {
     let available_lists_response = fs::read_to_string(
@@ -502,7 +832,7 @@ fn should_prepare_reply_patchset_with_reviewed_by() {
             tmp_dir.display()
         ));
 
-    let expected_git_reply_commands = vec![
+    let expected_git_reply_commands = [
         expected_git_reply_command_0,
         expected_git_reply_command_1,
         expected_git_reply_command_2,
@@ -627,3 +957,51 @@ fn should_get_local_git_signature() {
 
     fs::remove_dir_all(mocked_git_repo).unwrap();
 }
+
+#[test]
+fn should_prepare_apply_patchset_on_base_commit() {
+    let tmp_dir = Command::new("mktemp").arg("--directory").output().unwrap();
+    let tmp_dir = Path::new(std::str::from_utf8(&tmp_dir.stdout).unwrap().trim());
+    let patches: Vec<String> = ["cover_letter.cover", "patch_1.mbx", "patch_2.mbx"]
+        .iter()
+        .map(|file| {
+            fs::read_to_string(format!(
+                "src/test_samples/lore_session/prepare_reply_w_reviewed_by/{file}"
+            ))
+            .unwrap()
+        })
+        .collect();
+
+    let mut expected_git_checkout_command = Command::new("git");
+    expected_git_checkout_command.args(["checkout", "--detach", "0123abcd"]);
+    let mut expected_git_am_command = Command::new("git");
+    expected_git_am_command
+        .args(["am", "--3way"])
+        .arg(tmp_dir.join("0001.patch"))
+        .arg(tmp_dir.join("0002.patch"));
+
+    let git_apply_commands = prepare_apply_patchset(tmp_dir, &patches, Some("0123abcd")).unwrap();
+
+    assert_eq!(2, git_apply_commands.len());
+    assert!(commands_eq(
+        &expected_git_checkout_command,
+        &git_apply_commands[0]
+    ));
+    assert!(
+        commands_eq(&expected_git_am_command, &git_apply_commands[1]),
+        "The cover letter shouldn't be applied"
+    );
+    assert_eq!(
+        patches[2],
+        fs::read_to_string(tmp_dir.join("0002.patch")).unwrap()
+    );
+    assert_eq!(
+        1,
+        prepare_apply_patchset(tmp_dir, &patches, None)
+            .unwrap()
+            .len(),
+        "Without a base-commit, the patches should be applied on HEAD"
+    );
+
+    fs::remove_dir_all(tmp_dir).unwrap();
+}
@@ -1,6 +1,11 @@
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
+use super::{
+    fuzzy::{self, FuzzyMatch},
+    instance::DEFAULT_INSTANCE_NAME,
+};
+
 #[cfg(test)]
 mod tests;
 
@@ -8,6 +13,108 @@ mod tests;
 pub struct MailingList {
     name: String,
     description: String,
+    /// Name of the public-inbox instance that archives the list
+    #[serde(default = "default_instance_name")]
+    instance: String,
+}
+
+/// Most lists kept in [ListPreferences::recent]
+pub const MAX_RECENT_LISTS: usize = 5;
+
+/// Name and instance of a [MailingList], which identify it
+#[derive(Getters, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct ListRef {
+    name: String,
+    instance: String,
+}
+
+impl ListRef {
+    pub fn refers_to(&self, mailing_list: &MailingList) -> bool {
+        self.name == mailing_list.name && self.instance == mailing_list.instance
+    }
+}
+
+impl From<&MailingList> for ListRef {
+    fn from(mailing_list: &MailingList) -> Self {
+        ListRef {
+            name: mailing_list.name.clone(),
+            instance: mailing_list.instance.clone(),
+        }
+    }
+}
+
+/// Lists pinned by the user and the ones opened last, kept across sessions
+#[derive(Getters, Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct ListPreferences {
+    /// Pinned lists, in the order they were pinned
+    #[serde(default)]
+    favorites: Vec<ListRef>,
+    /// Lists opened last, the most recent first
+    #[serde(default)]
+    recent: Vec<ListRef>,
+}
+
+impl ListPreferences {
+    pub fn is_favorite(&self, mailing_list: &MailingList) -> bool {
+        self.favorites
+            .iter()
+            .any(|list_ref| list_ref.refers_to(mailing_list))
+    }
+
+    pub fn is_recent(&self, mailing_list: &MailingList) -> bool {
+        self.recent
+            .iter()
+            .any(|list_ref| list_ref.refers_to(mailing_list))
+    }
+
+    /// Pins `mailing_list` or, if it's already pinned, unpins it.
+    pub fn toggle_favorite(&mut self, mailing_list: &MailingList) {
+        if self.is_favorite(mailing_list) {
+            self.favorites
+                .retain(|list_ref| !list_ref.refers_to(mailing_list));
+        } else {
+            self.favorites.push(ListRef::from(mailing_list));
+        }
+    }
+
+    /// Moves `mailing_list` to the front of the recent lists, dropping the
+    /// oldest ones past [MAX_RECENT_LISTS].
+    pub fn record_use(&mut self, mailing_list: &MailingList) {
+        self.recent
+            .retain(|list_ref| !list_ref.refers_to(mailing_list));
+        self.recent.insert(0, ListRef::from(mailing_list));
+        self.recent.truncate(MAX_RECENT_LISTS);
+    }
+
+    pub fn last_used(&self) -> Option<&ListRef> {
+        self.recent.first()
+    }
+
+    /// Position of `mailing_list` among the pinned section: favorites first,
+    /// in the order they were pinned, then the recent lists. Returns `None`
+    /// if it's neither.
+    pub fn pinned_rank(&self, mailing_list: &MailingList) -> Option<usize> {
+        self.favorites
+            .iter()
+            .chain(self.recent.iter())
+            .position(|list_ref| list_ref.refers_to(mailing_list))
+    }
+}
+
+/// How well a [MailingList] matches a search, with the matched characters of
+/// its name and description (see [MailingList::fuzzy_match])
+#[derive(Getters, Debug, Clone, PartialEq, Eq, Default)]
+pub struct MailingListMatch {
+    score: i64,
+    name_indices: Vec<usize>,
+    description_indices: Vec<usize>,
+}
+
+/// Extra score of matches in the name, which is what users usually type
+const NAME_MATCH_BONUS: i64 = 32;
+
+fn default_instance_name() -> String {
+    DEFAULT_INSTANCE_NAME.to_string()
 }
 
 impl MailingList {
@@ -15,13 +122,44 @@ impl MailingList {
         MailingList {
             name: name.to_string(),
             description: description.to_string(),
+            instance: default_instance_name(),
         }
     }
+
+    /// Sets the public-inbox instance that archives the list.
+    pub fn with_instance(mut self, instance: &str) -> Self {
+        self.instance = instance.to_string();
+        self
+    }
+
+    /// Fuzzily matches `pattern` against the name and the description of the
+    /// list, scoring it by the best of them, with matches in the name
+    /// favoured. Returns `None` if neither matches.
+    pub fn fuzzy_match(&self, pattern: &str) -> Option<MailingListMatch> {
+        let name_match = fuzzy::fuzzy_match(pattern, &self.name);
+        let description_match = fuzzy::fuzzy_match(pattern, &self.description);
+
+        let name_score = name_match
+            .as_ref()
+            .map(|name_match| name_match.score() + NAME_MATCH_BONUS);
+        let description_score = description_match.as_ref().map(FuzzyMatch::score);
+        let score = name_score.max(description_score)?;
+
+        Some(MailingListMatch {
+            score,
+            name_indices: name_match.map(FuzzyMatch::into_indices).unwrap_or_default(),
+            description_indices: description_match
+                .map(FuzzyMatch::into_indices)
+                .unwrap_or_default(),
+        })
+    }
 }
 
 impl Ord for MailingList {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.name.cmp(&other.name)
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.instance.cmp(&other.instance))
     }
 }
 
@@ -10,14 +10,20 @@ fn can_deserialize_mailing_list() {
     assert_eq!(
         expected_mailing_list, deserialized_mailing_list,
         "Wrong deserialization of mailing list"
-    )
+    );
+
+    let serialized_mailing_list =
+        r#"{"name":"list-name","description":"List Description","instance":"mirror"}"#;
+    let deserialized_mailing_list: MailingList =
+        serde_json::from_str(serialized_mailing_list).unwrap();
+    assert_eq!("mirror", deserialized_mailing_list.instance());
 }
 
 #[test]
 fn can_serialize_mailing_list() {
     let expected_serialized_mailing_list =
-        r#"{"name":"list-name","description":"List Description"}"#;
-    let mailing_list = MailingList::new("list-name", "List Description");
+        r#"{"name":"list-name","description":"List Description","instance":"mirror"}"#;
+    let mailing_list = MailingList::new("list-name", "List Description").with_instance("mirror");
     let serialized_mailing_list = serde_json::to_string(&mailing_list).unwrap();
 
     assert_eq!(
@@ -59,3 +65,89 @@ fn should_sort_mailing_list_vec() {
         "Wrong mailing list at index 4"
     );
 }
+
+#[test]
+fn should_fuzzy_match_name_and_description() {
+    let mailing_list = MailingList::new("linux-i2c", "Linux I2C subsystem development");
+
+    let name_and_description_match = mailing_list.fuzzy_match("i2c").unwrap();
+    assert_eq!(&vec![6, 7, 8], name_and_description_match.name_indices());
+    assert_eq!(
+        &vec![6, 7, 8],
+        name_and_description_match.description_indices()
+    );
+
+    let description_match = mailing_list.fuzzy_match("subsys").unwrap();
+    assert!(description_match.name_indices().is_empty());
+    assert_eq!(
+        &vec![10, 11, 12, 13, 14, 15],
+        description_match.description_indices()
+    );
+
+    let other_mailing_list = MailingList::new("linux-iio", "Sensors on I2C and SPI buses");
+    assert!(
+        name_and_description_match.score() > other_mailing_list.fuzzy_match("i2c").unwrap().score(),
+        "Matches in the name should be favoured"
+    );
+
+    assert_eq!(None, mailing_list.fuzzy_match("rust"));
+}
+
+#[test]
+fn should_pin_and_unpin_favorite_lists() {
+    let netdev = MailingList::new("netdev", "description");
+    let mirrored_netdev = MailingList::new("netdev", "description").with_instance("mirror");
+    let mut list_preferences = ListPreferences::default();
+
+    list_preferences.toggle_favorite(&netdev);
+    assert!(list_preferences.is_favorite(&netdev));
+    assert!(
+        !list_preferences.is_favorite(&mirrored_netdev),
+        "Lists of other instances shouldn't be pinned"
+    );
+
+    list_preferences.toggle_favorite(&netdev);
+    assert!(!list_preferences.is_favorite(&netdev));
+}
+
+#[test]
+fn should_keep_most_recently_used_lists_first() {
+    let mut list_preferences = ListPreferences::default();
+    for index in 0..MAX_RECENT_LISTS + 2 {
+        list_preferences.record_use(&MailingList::new(&format!("list-{index}"), ""));
+    }
+    list_preferences.record_use(&MailingList::new("list-3", ""));
+
+    let recent_names: Vec<&String> = list_preferences
+        .recent()
+        .iter()
+        .map(ListRef::name)
+        .collect();
+    assert_eq!(
+        vec!["list-3", "list-6", "list-5", "list-4", "list-2"],
+        recent_names
+    );
+    assert_eq!("list-3", list_preferences.last_used().unwrap().name());
+}
+
+#[test]
+fn should_rank_favorites_before_recent_lists() {
+    let amd_gfx = MailingList::new("amd-gfx", "");
+    let rust = MailingList::new("rust-for-linux", "");
+    let mut list_preferences = ListPreferences::default();
+    list_preferences.record_use(&amd_gfx);
+    list_preferences.toggle_favorite(&rust);
+
+    assert_eq!(Some(0), list_preferences.pinned_rank(&rust));
+    assert_eq!(Some(1), list_preferences.pinned_rank(&amd_gfx));
+    assert_eq!(
+        None,
+        list_preferences.pinned_rank(&MailingList::new("netdev", ""))
+    );
+
+    let serialized_list_preferences = serde_json::to_string(&list_preferences).unwrap();
+    assert_eq!(
+        list_preferences,
+        serde_json::from_str(&serialized_list_preferences).unwrap()
+    );
+}
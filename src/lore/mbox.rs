@@ -0,0 +1,483 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use derive_getters::Getters;
+use encoding_rs::{Encoding, UTF_8};
+use regex::Regex;
+use std::sync::LazyLock;
+
+#[cfg(test)]
+mod tests;
+
+/// Separator line used between the messages of the mboxes `b4` and
+/// `git format-patch` produce.
+pub const MBOX_SEPARATOR: &str = "From git@z Thu Jan  1 00:00:00 1970";
+
+/// An RFC 5322 message with its headers unfolded and its body decoded to text.
+#[derive(Getters, Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Headers in the order they appear, with folded lines joined and RFC 2047
+    /// encoded words decoded
+    headers: Vec<(String, String)>,
+    /// Text of the message, decoded from its transfer encoding and charset.
+    /// For multipart messages, this is the text of its textual parts.
+    body: String,
+    /// If true, `body` was decoded, so the MIME headers of the message don't
+    /// describe it anymore
+    transcoded: bool,
+}
+
+impl Message {
+    /// Parses a single message. Blank lines before the headers are ignored
+    /// and, if the text doesn't start with a header block, it is all taken as
+    /// the body.
+    pub fn parse(raw_message: &str) -> Message {
+        let raw_message = raw_message.replace("\r\n", "\n");
+        parse_part(raw_message.trim_start_matches('\n'))
+    }
+
+    /// Drops whatever follows the `-- ` signature line and the line after
+    /// it, where `git format-patch` puts its version, like text appended to
+    /// a patch file after the message. Only the last `-- ` line is taken as
+    /// the signature, and not even that one if a diff follows it, as diffs
+    /// removing a `- ` line have such lines too.
+    pub fn trim_after_signature(&mut self) {
+        let Some(signature_start) = self
+            .body
+            .rfind("\n-- \n")
+            .map(|index| index + 1)
+            .or_else(|| self.body.starts_with("-- \n").then_some(0))
+        else {
+            return;
+        };
+        let version_start = signature_start + "-- \n".len();
+        let after_signature = &self.body[version_start..];
+        let has_diff = ["diff --git ", "@@ "].iter().any(|diff_line_start| {
+            after_signature.starts_with(diff_line_start)
+                || after_signature.contains(&format!("\n{diff_line_start}"))
+        });
+        if has_diff {
+            return;
+        }
+        let version_end = after_signature
+            .find('\n')
+            .map_or(self.body.len(), |index| version_start + index + 1);
+        self.body.truncate(version_end);
+    }
+
+    /// Returns the value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn subject(&self) -> &str {
+        self.header("Subject").unwrap_or_default()
+    }
+
+    /// Returns the id of the message, without the enclosing `<>`.
+    pub fn message_id(&self) -> Option<&str> {
+        self.header("Message-ID")
+            .and_then(|value| extract_message_ids(value).into_iter().next())
+    }
+
+    /// Renders the message as a patch, in the layout `b4` uses: the headers
+    /// that matter for reviewing and replying, a blank line and the body.
+    pub fn to_patch_text(&self) -> String {
+        let mut text = format!("Subject: {}\n", self.subject());
+
+        for header_name in ["From", "Date"] {
+            if let Some(value) = self.header(header_name) {
+                text.push_str(&format!("{header_name}: {value}\n"));
+            }
+        }
+        if let Some(message_id) = self.message_id() {
+            text.push_str(&format!("Message-Id: <{message_id}>\n"));
+        }
+        if let Some(value) = self.header("MIME-Version") {
+            text.push_str(&format!("MIME-Version: {value}\n"));
+        }
+        if self.transcoded {
+            text.push_str("Content-Type: text/plain; charset=\"utf-8\"\n");
+            text.push_str("Content-Transfer-Encoding: 8bit\n");
+        } else {
+            for header_name in ["Content-Type", "Content-Transfer-Encoding"] {
+                if let Some(value) = self.header(header_name) {
+                    text.push_str(&format!("{header_name}: {value}\n"));
+                }
+            }
+        }
+
+        text.push('\n');
+        text.push_str(self.body.trim_end());
+        text.push('\n');
+
+        text
+    }
+}
+
+/// Parses all messages of an mbox. See [split_mbox] for how messages are
+/// delimited. Paragraphs before the header block of a message, told apart
+/// by lacking both a `From:` and a `Subject:` header, are skipped as
+/// garbage.
+pub fn parse_mbox(mbox: &str) -> Vec<Message> {
+    split_mbox(mbox)
+        .iter()
+        .map(|raw_message| Message::parse(skip_leading_garbage(raw_message)))
+        .filter(|message| !message.headers.is_empty())
+        .collect()
+}
+
+/// Splits an mbox into its raw messages.
+///
+/// A `From ` line separates messages only if it is the first line or follows
+/// a blank line, and mboxrd escaped lines (`>From `) are unescaped. Text
+/// without any separator line is taken as a single message.
+pub fn split_mbox(mbox: &str) -> Vec<String> {
+    let mut raw_messages: Vec<String> = Vec::new();
+    let mut current_message = String::new();
+    let mut previous_line_is_blank = true;
+
+    for line in mbox.lines() {
+        if previous_line_is_blank && line.starts_with("From ") {
+            if !current_message.trim().is_empty() {
+                raw_messages.push(std::mem::take(&mut current_message));
+            }
+            current_message.clear();
+            previous_line_is_blank = false;
+            continue;
+        }
+
+        previous_line_is_blank = line.trim().is_empty();
+        current_message.push_str(unescape_mboxrd_line(line));
+        current_message.push('\n');
+    }
+
+    if !current_message.trim().is_empty() {
+        raw_messages.push(current_message);
+    }
+
+    raw_messages
+}
+
+fn skip_leading_garbage(raw_message: &str) -> &str {
+    let mut rest = raw_message.trim_start_matches('\n');
+
+    loop {
+        let (raw_headers, body) = split_header_block(rest);
+        let headers = parse_headers(raw_headers);
+        let is_message_start = ["From", "Subject"]
+            .iter()
+            .any(|name| find_header(&headers, name).is_some());
+        let next = body.trim_start_matches('\n');
+        if is_message_start || next.is_empty() || split_header_block(next).0.is_empty() {
+            return rest;
+        }
+        rest = next;
+    }
+}
+
+/// Splits a message into its header block and its body, dropping the blank
+/// line between them. If the message doesn't start with a header block, the
+/// header block is empty.
+pub fn split_header_block(raw_message: &str) -> (&str, &str) {
+    let mut offset = 0;
+
+    for line in raw_message.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() {
+            return (&raw_message[..offset], &raw_message[offset + line.len()..]);
+        }
+
+        let is_continuation = offset > 0 && content.starts_with([' ', '\t']);
+        if !is_continuation && !is_header_line(content) {
+            return ("", raw_message);
+        }
+
+        offset += line.len();
+    }
+
+    (raw_message, "")
+}
+
+/// Extracts the message ids enclosed in `<>` from a header value.
+pub fn extract_message_ids(value: &str) -> Vec<&str> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| id.trim())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Decodes the RFC 2047 encoded words (`=?charset?Q?...?=`) of a header value.
+/// Whitespace between adjacent encoded words is dropped, as the RFC mandates.
+pub fn decode_encoded_words(value: &str) -> String {
+    static RE_ENCODED_WORD: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"=\?([^?\s]+)\?([bBqQ])\?([^?\s]*)\?=").unwrap());
+
+    let mut decoded = String::new();
+    let mut last_end = 0;
+    // Bytes of consecutive encoded words with the same charset are decoded
+    // together, as some mailers split multibyte characters between words
+    let mut pending: Option<(String, Vec<u8>)> = None;
+
+    for capture in RE_ENCODED_WORD.captures_iter(value) {
+        let encoded_word = capture.get(0).unwrap();
+        let gap = &value[last_end..encoded_word.start()];
+        let charset = capture[1].split('*').next().unwrap_or_default();
+        let encoded_text = &capture[3];
+
+        let bytes = match &capture[2] {
+            "B" | "b" => STANDARD.decode(encoded_text).ok(),
+            _ => Some(decode_q_encoding(encoded_text)),
+        };
+
+        let Some(bytes) = bytes else {
+            flush_pending(&mut decoded, &mut pending);
+            decoded.push_str(gap);
+            decoded.push_str(encoded_word.as_str());
+            last_end = encoded_word.end();
+            continue;
+        };
+
+        match &mut pending {
+            Some((pending_charset, pending_bytes))
+                if gap.trim().is_empty() && pending_charset.eq_ignore_ascii_case(charset) =>
+            {
+                pending_bytes.extend(bytes);
+            }
+            _ => {
+                let gap_is_between_words = pending.is_some() && gap.trim().is_empty();
+                flush_pending(&mut decoded, &mut pending);
+                if !gap_is_between_words {
+                    decoded.push_str(gap);
+                }
+                pending = Some((charset.to_string(), bytes));
+            }
+        }
+
+        last_end = encoded_word.end();
+    }
+
+    flush_pending(&mut decoded, &mut pending);
+    decoded.push_str(&value[last_end..]);
+
+    decoded
+}
+
+fn flush_pending(decoded: &mut String, pending: &mut Option<(String, Vec<u8>)>) {
+    if let Some((charset, bytes)) = pending.take() {
+        decoded.push_str(&decode_charset(&bytes, &charset));
+    }
+}
+
+/// The Q encoding of RFC 2047 is quoted-printable with `_` standing for space.
+fn decode_q_encoding(encoded_text: &str) -> Vec<u8> {
+    let encoded_text = encoded_text.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded_text.len());
+    let mut i = 0;
+
+    while i < encoded_text.len() {
+        match encoded_text[i] {
+            b'_' => bytes.push(b' '),
+            b'=' if i + 2 < encoded_text.len() => {
+                match std::str::from_utf8(&encoded_text[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        bytes.push(byte);
+                        i += 2;
+                    }
+                    None => bytes.push(b'='),
+                }
+            }
+            byte => bytes.push(byte),
+        }
+        i += 1;
+    }
+
+    bytes
+}
+
+fn parse_part(raw_part: &str) -> Message {
+    let (raw_headers, raw_body) = split_header_block(raw_part);
+    let headers = parse_headers(raw_headers);
+    let (body, transcoded) = decode_body(&headers, raw_body);
+
+    Message {
+        headers,
+        body,
+        transcoded,
+    }
+}
+
+/// Parses the header block, joining continuation lines with their header.
+fn parse_headers(raw_headers: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+
+    for line in raw_headers.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    for (_, value) in headers.iter_mut() {
+        if value.contains("=?") {
+            *value = decode_encoded_words(value);
+        }
+    }
+
+    headers
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn is_header_line(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(name, _)| {
+        !name.is_empty() && name.bytes().all(|byte| byte.is_ascii_graphic())
+    })
+}
+
+/// Returns the body as text and whether it had to be decoded.
+fn decode_body(headers: &[(String, String)], raw_body: &str) -> (String, bool) {
+    let content_type = find_header(headers, "Content-Type").unwrap_or("text/plain");
+    let mime_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if mime_type.starts_with("multipart/") {
+        if let Some(boundary) = content_type_parameter(content_type, "boundary") {
+            return (decode_multipart(&mime_type, raw_body, &boundary), true);
+        }
+    }
+
+    let transfer_encoding = find_header(headers, "Content-Transfer-Encoding")
+        .unwrap_or("7bit")
+        .trim()
+        .to_ascii_lowercase();
+
+    let bytes: Vec<u8> = match transfer_encoding.as_str() {
+        "quoted-printable" => {
+            quoted_printable::decode(raw_body, quoted_printable::ParseMode::Robust)
+                .unwrap_or_else(|_| raw_body.as_bytes().to_vec())
+        }
+        "base64" => {
+            let encoded: String = raw_body.split_whitespace().collect();
+            match STANDARD.decode(encoded) {
+                Ok(bytes) => bytes,
+                Err(_) => return (raw_body.to_string(), false),
+            }
+        }
+        // The raw body is already text
+        _ => return (raw_body.to_string(), false),
+    };
+
+    let charset = content_type_parameter(content_type, "charset").unwrap_or_default();
+    let body = decode_charset(&bytes, &charset).replace("\r\n", "\n");
+
+    (body, true)
+}
+
+/// The text of a multipart body is its first textual part, for
+/// `multipart/alternative`, or all its textual parts otherwise (e.g. a
+/// message with a patch attached).
+fn decode_multipart(mime_type: &str, raw_body: &str, boundary: &str) -> String {
+    let raw_parts = split_multipart(raw_body, boundary);
+    let text_parts = raw_parts
+        .iter()
+        .map(|raw_part| parse_part(raw_part))
+        .filter(is_textual_part);
+
+    if mime_type == "multipart/alternative" {
+        return text_parts.map(|part| part.body).next().unwrap_or_default();
+    }
+
+    let mut body = String::new();
+    for part in text_parts {
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        body.push_str(&part.body);
+    }
+
+    body
+}
+
+fn is_textual_part(part: &Message) -> bool {
+    let mime_type = part
+        .header("Content-Type")
+        .unwrap_or("text/plain")
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    mime_type.starts_with("multipart/")
+        || (mime_type.starts_with("text/") && mime_type != "text/html")
+}
+
+fn split_multipart(raw_body: &str, boundary: &str) -> Vec<String> {
+    let delimiter = format!("--{boundary}");
+    let close_delimiter = format!("--{boundary}--");
+    let mut parts: Vec<String> = Vec::new();
+    let mut current_part: Option<String> = None;
+
+    for line in raw_body.lines() {
+        let trimmed_line = line.trim_end();
+        if trimmed_line == delimiter || trimmed_line == close_delimiter {
+            parts.extend(current_part.take());
+            if trimmed_line == close_delimiter {
+                break;
+            }
+            current_part = Some(String::new());
+            continue;
+        }
+
+        if let Some(part) = current_part.as_mut() {
+            part.push_str(line);
+            part.push('\n');
+        }
+    }
+    parts.extend(current_part);
+
+    parts
+}
+
+fn content_type_parameter(content_type: &str, parameter: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case(parameter)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Converts `bytes` in `charset` to UTF-8. Unknown charsets are taken as
+/// UTF-8 and invalid sequences are replaced.
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    let encoding = Encoding::for_label(charset.trim().as_bytes()).unwrap_or(UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn unescape_mboxrd_line(line: &str) -> &str {
+    let unquoted = line.trim_start_matches('>');
+    if unquoted.len() < line.len() && unquoted.starts_with("From ") {
+        &line[1..]
+    } else {
+        line
+    }
+}
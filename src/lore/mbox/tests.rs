@@ -0,0 +1,232 @@
+use super::*;
+
+#[test]
+fn should_split_mbox_only_on_separator_lines() {
+    let mbox = "From mboxrd@z Thu Jan  1 00:00:00 1970\n\
+        Message-Id: <a@b>\n\
+        \n\
+        First line\n\
+        From here on, this isn't a separator\n\
+        >From this one was escaped\n\
+        >>From this one was escaped twice\n\
+        \n\
+        From mboxrd@z Thu Jan  1 00:00:00 1970\n\
+        Message-Id: <c@d>\n\
+        \n\
+        Second message\n";
+
+    let messages = parse_mbox(mbox);
+
+    assert_eq!(2, messages.len(), "Wrong number of messages");
+    assert_eq!(Some("a@b"), messages[0].message_id());
+    assert_eq!(
+        "First line\n\
+        From here on, this isn't a separator\n\
+        From this one was escaped\n\
+        >From this one was escaped twice\n\
+        \n",
+        messages[0].body()
+    );
+    assert_eq!(Some("c@d"), messages[1].message_id());
+}
+
+#[test]
+fn text_without_separator_should_be_a_single_message() {
+    let messages = parse_mbox("\n\nSubject: [PATCH] foo\n\nbody\n");
+
+    assert_eq!(1, messages.len());
+    assert_eq!("[PATCH] foo", messages[0].subject());
+    assert_eq!("body\n", messages[0].body());
+}
+
+#[test]
+fn should_skip_garbage_around_message() {
+    let mut messages = parse_mbox(
+        "GARB: leading garbage\n\
+        \n\
+        Subject: [PATCH] foo\n\
+        \n\
+        body\n\
+        -- \n\
+        2.34.1\n\
+        \n\
+        GARB: trailing garbage\n",
+    );
+
+    assert_eq!(1, messages.len());
+    assert_eq!("[PATCH] foo", messages[0].subject());
+    messages[0].trim_after_signature();
+    assert_eq!("body\n-- \n2.34.1\n", messages[0].body());
+}
+
+#[test]
+fn should_not_trim_diff_removing_signature_like_line() {
+    let diff = "Description\n\
+        ---\n\
+        \x20file | 2 +-\n\
+        \n\
+        diff --git a/file b/file\n\
+        --- a/file\n\
+        +++ b/file\n\
+        @@ -1,3 +1,2 @@\n\
+        \x20foo\n\
+        -- \n\
+        \x20bar\n\
+        @@ -10,2 +9,2 @@\n\
+        -baz\n\
+        +qux\n";
+    let mut message = Message::parse(&format!("Subject: [PATCH] foo\n\n{diff}"));
+
+    message.trim_after_signature();
+    assert_eq!(
+        diff,
+        message.body(),
+        "Patch without signature was truncated"
+    );
+
+    let signed_diff = format!("{diff}-- \n2.34.1\n");
+    let mut message = Message::parse(&format!(
+        "Subject: [PATCH] foo\n\n{signed_diff}\nGARB: trailing garbage\n"
+    ));
+
+    message.trim_after_signature();
+    assert_eq!(&signed_diff, message.body());
+}
+
+#[test]
+fn should_unfold_and_decode_headers() {
+    let message = Message::parse(
+        "Subject: =?UTF-8?Q?[PATCH]_Jos=C3=A9's?=\n \
+        =?UTF-8?B?UmV2aXPDo28=?= of\n \
+        the =?iso-8859-1?q?m=F3dulo?=\n\
+        From: =?utf-8?q?Jo=C3=A3o?= <joao@foo.bar>\n\
+        \n\
+        body\n",
+    );
+
+    assert_eq!(
+        "[PATCH] José'sRevisão of the módulo",
+        message.subject(),
+        "Whitespace between adjacent encoded words should be dropped"
+    );
+    assert_eq!(Some("João <joao@foo.bar>"), message.header("from"));
+}
+
+#[test]
+fn should_decode_quoted_printable_body() {
+    let message = Message::parse(
+        "Subject: [PATCH] foo\n\
+        Content-Type: text/plain; charset=\"utf-8\"\n\
+        Content-Transfer-Encoding: quoted-printable\n\
+        \n\
+        if (a =3D=3D b)\n\
+        A long line that was =\n\
+        soft broken, with caf=C3=A9\n",
+    );
+
+    assert_eq!(
+        "if (a == b)\nA long line that was soft broken, with café\n",
+        message.body()
+    );
+    assert!(message.transcoded());
+}
+
+#[test]
+fn should_decode_base64_body_in_its_charset() {
+    let message = Message::parse(
+        "Subject: foo\n\
+        Content-Type: text/plain; charset=ISO-8859-1\n\
+        Content-Transfer-Encoding: base64\n\
+        \n\
+        T2zhLCBwYXRjaCBh\n\
+        bmV4YWRvLgo=\n",
+    );
+
+    assert_eq!("Olá, patch anexado.\n", message.body());
+    assert!(message.transcoded());
+}
+
+#[test]
+fn should_take_only_text_parts_of_multipart_body() {
+    let message = Message::parse(
+        "Subject: [PATCH] foo\n\
+        Content-Type: multipart/mixed; boundary=\"outer\"\n\
+        \n\
+        This is a multi-part message in MIME format.\n\
+        --outer\n\
+        Content-Type: multipart/alternative; boundary=inner\n\
+        \n\
+        --inner\n\
+        Content-Type: text/plain\n\
+        \n\
+        Description\n\
+        --inner\n\
+        Content-Type: text/html\n\
+        \n\
+        <p>Description</p>\n\
+        --inner--\n\
+        --outer\n\
+        Content-Type: text/x-patch; name=\"foo.patch\"\n\
+        Content-Transfer-Encoding: base64\n\
+        \n\
+        ZGlmZiAtLWdpdCBhL2ZpbGUucnMgYi9maWxlLnJzCithZGRpdGlvbgo=\n\
+        --outer\n\
+        Content-Type: application/octet-stream\n\
+        \n\
+        binary\n\
+        --outer--\n\
+        Epilogue\n",
+    );
+
+    assert_eq!(
+        "Description\ndiff --git a/file.rs b/file.rs\n+addition\n",
+        message.body()
+    );
+}
+
+#[test]
+fn should_split_header_block() {
+    let raw_message = "Subject: foo\n bar\nFrom: Foo\n\nbody\n";
+    assert_eq!(
+        ("Subject: foo\n bar\nFrom: Foo\n", "body\n"),
+        split_header_block(raw_message)
+    );
+
+    let raw_message = "---\n file.rs | 2 +-\n\ndiff\n";
+    assert_eq!(
+        ("", raw_message),
+        split_header_block(raw_message),
+        "Text that doesn't start with headers shouldn't have a header block"
+    );
+}
+
+#[test]
+fn should_render_transcoded_message_as_patch() {
+    let message = Message::parse(
+        "Received: from somewhere\n\
+        Subject: [PATCH] foo\n\
+        From: Foo Bar <foo@bar.foo.bar>\n\
+        To: some-list@list.org\n\
+        Date: Tue, 16 Jul 2024 16:51:00 +0000\n\
+        Message-ID: <1234@bar.foo.bar>\n\
+        MIME-Version: 1.0\n\
+        Content-Type: text/plain; charset=\"utf-8\"\n\
+        Content-Transfer-Encoding: quoted-printable\n\
+        \n\
+        caf=C3=A9\n\
+        \n",
+    );
+
+    assert_eq!(
+        "Subject: [PATCH] foo\n\
+        From: Foo Bar <foo@bar.foo.bar>\n\
+        Date: Tue, 16 Jul 2024 16:51:00 +0000\n\
+        Message-Id: <1234@bar.foo.bar>\n\
+        MIME-Version: 1.0\n\
+        Content-Type: text/plain; charset=\"utf-8\"\n\
+        Content-Transfer-Encoding: 8bit\n\
+        \n\
+        café\n",
+        message.to_patch_text()
+    );
+}
@@ -1,7 +1,12 @@
+use std::{cmp::Reverse, collections::BTreeSet, fmt::Display};
+
+use chrono::{DateTime, TimeDelta, Utc};
 use derive_getters::Getters;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use super::timestamp::{self, optional_rfc3339};
+
 #[cfg(test)]
 mod tests;
 
@@ -28,7 +33,11 @@ pub struct Patch {
     message_id: MessageID,
     #[serde(rename = "in-reply-to")]
     in_reply_to: Option<MessageID>,
-    updated: String,
+    /// When the message was sent, if its date could be parsed
+    #[serde(default, with = "optional_rfc3339")]
+    updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tag: PatchTag,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -42,6 +51,27 @@ pub struct MessageID {
     pub href: String,
 }
 
+impl MessageID {
+    /// Extracts the bare message-id from the archive URL stored in `href`
+    pub fn id(&self) -> &str {
+        self.href
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Extracts the list the message is archived in from the archive URL
+    /// stored in `href`
+    pub fn list(&self) -> &str {
+        self.href
+            .trim_end_matches('/')
+            .rsplit('/')
+            .nth(1)
+            .unwrap_or_default()
+    }
+}
+
 fn default_version() -> usize {
     1
 }
@@ -68,7 +98,8 @@ impl Patch {
             total_in_series: 1,
             message_id,
             in_reply_to,
-            updated,
+            updated: timestamp::parse_timestamp(&updated),
+            tag: PatchTag::default(),
         }
     }
 
@@ -85,6 +116,8 @@ impl Patch {
     }
 
     pub fn update_patch_metadata(&mut self, patch_regex: &PatchRegex) {
+        self.tag = PatchTag::parse(&self.title, patch_regex);
+
         let patch_tag: String = match self.get_patch_tag(&patch_regex.re_patch_tag) {
             Some(value) => value.to_string(),
             None => return,
@@ -132,10 +165,292 @@ impl Patch {
     }
 }
 
+/// What the bracketed tags at the start of the subject of a patch, like
+/// `[RFC PATCH net-next v2 1/3]`, and the subsystem prefix of its title tell
+/// besides the version and the position in the series
+#[derive(Getters, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PatchTag {
+    rfc: bool,
+    resend: bool,
+    /// Tree the patch is meant to be applied to, like `net-next`
+    target_tree: Option<String>,
+    /// Subsystem prefix of the title, like `drm/amd` in `drm/amd: Fix foo`
+    subsystem: Option<String>,
+    /// Keywords of the tags that mean nothing else, like `GSoC`
+    extra: Vec<String>,
+}
+
+/// Trees that don't follow the naming patterns told apart by
+/// [PatchTag::parse]
+const KNOWN_TARGET_TREES: [&str; 4] = ["net", "bpf", "stable", "next"];
+
+impl PatchTag {
+    /// Parses every bracketed tag at the start of `title`, not only the one
+    /// with `PATCH` or `RFC`, and the subsystem prefix that follows them.
+    pub fn parse(title: &str, patch_regex: &PatchRegex) -> PatchTag {
+        let mut patch_tag = PatchTag::default();
+        let mut rest = title;
+        while let Some(capture) = patch_regex.re_leading_tag.captures(rest) {
+            capture[1]
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|keyword| !keyword.is_empty())
+                .for_each(|keyword| patch_tag.add_keyword(keyword));
+            rest = &rest[capture.get(0).unwrap().end()..];
+        }
+        patch_tag.subsystem = patch_regex
+            .re_subsystem
+            .captures(rest)
+            .map(|capture| capture[1].to_string());
+
+        patch_tag
+    }
+
+    fn add_keyword(&mut self, keyword: &str) {
+        let is_version = |keyword: &str| {
+            keyword
+                .strip_prefix(['v', 'V'])
+                .is_some_and(|version| version.parse::<usize>().is_ok())
+        };
+        let is_number_in_series = |keyword: &str| {
+            keyword.split_once('/').is_some_and(|(number, total)| {
+                number.parse::<usize>().is_ok() && total.parse::<usize>().is_ok()
+            })
+        };
+        // Versions may be glued to the keyword, like in `PATCHv2`
+        let is_keyword = |name: &str| {
+            keyword.get(..name.len()).is_some_and(|prefix| {
+                prefix.eq_ignore_ascii_case(name) && {
+                    let version = &keyword[name.len()..];
+                    version.is_empty() || is_version(version)
+                }
+            })
+        };
+
+        // Already stored in the fields of the patch
+        if is_keyword("PATCH") || is_version(keyword) || is_number_in_series(keyword) {
+            return;
+        }
+
+        if is_keyword("RFC") {
+            self.rfc = true;
+        } else if is_keyword("RESEND") {
+            self.resend = true;
+        } else if self.target_tree.is_none() && is_target_tree(keyword) {
+            self.target_tree = Some(keyword.to_lowercase());
+        } else {
+            self.extra.push(keyword.to_string());
+        }
+    }
+}
+
+/// Whether `keyword` names a tree, like `net-next`, `drm-misc-fixes` or
+/// `for-linus`
+fn is_target_tree(keyword: &str) -> bool {
+    let keyword = keyword.to_lowercase();
+    keyword.ends_with("-next")
+        || keyword.ends_with("-fixes")
+        || keyword.starts_with("for-")
+        || KNOWN_TARGET_TREES.contains(&keyword.as_str())
+}
+
+impl Display for PatchTag {
+    /// Compact form of the tags, like `RFC RESEND net-next GSoC`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut labels: Vec<&str> = Vec::new();
+        if self.rfc {
+            labels.push("RFC");
+        }
+        if self.resend {
+            labels.push("RESEND");
+        }
+        if let Some(target_tree) = &self.target_tree {
+            labels.push(target_tree);
+        }
+        labels.extend(self.extra.iter().map(String::as_str));
+
+        write!(f, "{}", labels.join(" "))
+    }
+}
+
+/// Maximum ages of the patchsets shown in a list to cycle through, in days
+const MAX_AGES_IN_DAYS: [i64; 3] = [1, 7, 30];
+
+/// Which patchsets to show in a list, by their [PatchTag] and age
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatchFilter {
+    pub hide_rfc: bool,
+    /// Show only the patchsets for this tree
+    pub target_tree: Option<String>,
+    /// Show only the patchsets of this subsystem, or of its children, like
+    /// `drm/amd` for `drm`
+    pub subsystem: Option<String>,
+    /// Show only the patchsets updated this recently. Patchsets without a
+    /// known date are hidden.
+    pub max_age: Option<TimeDelta>,
+}
+
+impl PatchFilter {
+    /// Whether `patch` should be shown at `now`.
+    pub fn matches(&self, patch: &Patch, now: DateTime<Utc>) -> bool {
+        if let Some(max_age) = self.max_age {
+            if patch.updated.is_none_or(|updated| now - updated > max_age) {
+                return false;
+            }
+        }
+
+        let tag = patch.tag();
+        if self.hide_rfc && tag.rfc {
+            return false;
+        }
+        if self.target_tree.is_some() && tag.target_tree != self.target_tree {
+            return false;
+        }
+        if let Some(subsystem) = &self.subsystem {
+            let Some(patch_subsystem) = &tag.subsystem else {
+                return false;
+            };
+            let is_child = patch_subsystem
+                .strip_prefix(subsystem.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', ':']));
+            if !is_child {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != PatchFilter::default()
+    }
+
+    /// Whether the patchsets older than `patches` would all be filtered out
+    /// at `now`, as the oldest of `patches` is already past
+    /// [PatchFilter::max_age]. Feeds come newest first, so they needn't be
+    /// fetched any further.
+    pub fn rules_out_older<'a>(
+        &self,
+        patches: impl IntoIterator<Item = &'a Patch>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let Some(max_age) = self.max_age else {
+            return false;
+        };
+        patches
+            .into_iter()
+            .filter_map(|patch| patch.updated)
+            .min()
+            .is_some_and(|oldest| now - oldest > max_age)
+    }
+
+    /// Moves to the next of `target_trees`, going back to showing every tree
+    /// after the last one.
+    pub fn cycle_target_tree(&mut self, target_trees: &[String]) {
+        self.target_tree = match &self.target_tree {
+            None => target_trees.first().cloned(),
+            Some(target_tree) => target_trees
+                .iter()
+                .skip_while(|other| *other != target_tree)
+                .nth(1)
+                .cloned(),
+        };
+    }
+
+    /// Moves to the next of the maximum ages in [MAX_AGES_IN_DAYS], going
+    /// back to showing every patchset after the last one.
+    pub fn cycle_max_age(&mut self) {
+        let max_ages = MAX_AGES_IN_DAYS.map(TimeDelta::days);
+        self.max_age = match self.max_age {
+            None => max_ages.first().copied(),
+            Some(max_age) => max_ages
+                .iter()
+                .skip_while(|other| **other != max_age)
+                .nth(1)
+                .copied(),
+        };
+    }
+}
+
+impl Display for PatchFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut conditions = Vec::new();
+        if self.hide_rfc {
+            conditions.push("no RFCs".to_string());
+        }
+        if let Some(target_tree) = &self.target_tree {
+            conditions.push(format!("tree {target_tree}"));
+        }
+        if let Some(subsystem) = &self.subsystem {
+            conditions.push(format!("subsystem {subsystem}"));
+        }
+        if let Some(max_age) = self.max_age {
+            conditions.push(format!("last {}", timestamp::format_age(max_age)));
+        }
+
+        write!(f, "{}", conditions.join(", "))
+    }
+}
+
+/// Order of the patchsets in a list
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PatchOrder {
+    /// As they came, from the feed or as they were bookmarked
+    #[default]
+    Unsorted,
+    NewestFirst,
+    OldestFirst,
+}
+
+impl PatchOrder {
+    pub fn next(&self) -> PatchOrder {
+        match self {
+            PatchOrder::Unsorted => PatchOrder::NewestFirst,
+            PatchOrder::NewestFirst => PatchOrder::OldestFirst,
+            PatchOrder::OldestFirst => PatchOrder::Unsorted,
+        }
+    }
+
+    /// Sorts `patches` by when they were updated, keeping the ones without a
+    /// known date last, in their original order.
+    pub fn sort(&self, patches: &mut [&Patch]) {
+        match self {
+            PatchOrder::Unsorted => {}
+            PatchOrder::NewestFirst => {
+                patches.sort_by_key(|patch| (patch.updated.is_none(), Reverse(patch.updated)));
+            }
+            PatchOrder::OldestFirst => {
+                patches.sort_by_key(|patch| (patch.updated.is_none(), patch.updated));
+            }
+        }
+    }
+}
+
+impl Display for PatchOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchOrder::Unsorted => write!(f, "unsorted"),
+            PatchOrder::NewestFirst => write!(f, "newest first"),
+            PatchOrder::OldestFirst => write!(f, "oldest first"),
+        }
+    }
+}
+
+/// Distinct trees targeted by `patches`, sorted
+pub fn target_trees<'a>(patches: impl IntoIterator<Item = &'a Patch>) -> Vec<String> {
+    patches
+        .into_iter()
+        .filter_map(|patch| patch.tag().target_tree.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[derive(Clone)]
 pub struct PatchRegex {
     pub re_patch_tag: Regex,
     pub re_patch_version: Regex,
     pub re_patch_series: Regex,
+    pub re_leading_tag: Regex,
+    pub re_subsystem: Regex,
 }
 
 impl Default for PatchRegex {
@@ -149,11 +464,15 @@ impl PatchRegex {
         let re_patch_tag = Regex::new(r"(?i)\[[^\]]*(PATCH|RFC)[^\[]*\]").unwrap();
         let re_patch_version = Regex::new(r"[v|V] *(\d+)").unwrap();
         let re_patch_series = Regex::new(r"(\d+) */ *(\d+)").unwrap();
+        let re_leading_tag = Regex::new(r"^\s*\[([^\]]*)\]").unwrap();
+        let re_subsystem = Regex::new(r"^\s*((?:[^\s:\[\]]+:\s+)*[^\s:\[\]]+):\s").unwrap();
 
         PatchRegex {
             re_patch_tag,
             re_patch_version,
             re_patch_series,
+            re_leading_tag,
+            re_subsystem,
         }
     }
 }
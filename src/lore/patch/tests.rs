@@ -109,3 +109,244 @@ fn test_update_patch_metadata() {
     assert_eq!(3, patch.number_in_series(), "Wrong number in series!");
     assert_eq!(42, patch.total_in_series(), "Wrong total in series!");
 }
+
+#[test]
+fn should_extract_bare_message_id_from_href() {
+    let message_id = MessageID {
+        href: "http://lore.kernel.org/some-list/1234-1-foo@bar.foo.bar/".to_string(),
+    };
+    assert_eq!("1234-1-foo@bar.foo.bar", message_id.id());
+
+    let message_id = MessageID {
+        href: "http://lore.kernel.org/some-list/1234-1-foo@bar.foo.bar".to_string(),
+    };
+    assert_eq!(
+        "1234-1-foo@bar.foo.bar",
+        message_id.id(),
+        "Should extract the message-id from hrefs without trailing slash"
+    );
+}
+
+#[test]
+fn should_extract_list_from_href() {
+    let message_id = MessageID {
+        href: "http://lore.kernel.org/some-list/1234-1-foo@bar.foo.bar/".to_string(),
+    };
+    assert_eq!("some-list", message_id.list());
+}
+
+fn tagged_patch(title: &str) -> Patch {
+    let mut patch = Patch::new(
+        title.to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.foo.bar".to_string(),
+        },
+        MessageID {
+            href: "http://lore.kernel.org/some-list/1234-1-foo@bar.foo.bar".to_string(),
+        },
+        None,
+        "2024-07-06T19:15:48Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+    patch
+}
+
+#[test]
+fn should_parse_every_leading_tag() {
+    let patch = tagged_patch("[GSoC][RFC PATCH net-next v2 1/3] net: phy: Add foo");
+
+    assert!(patch.tag().rfc());
+    assert!(!patch.tag().resend());
+    assert_eq!(&Some("net-next".to_string()), patch.tag().target_tree());
+    assert_eq!(
+        &Some("net: phy".to_string()),
+        patch.tag().subsystem(),
+        "Chained subsystem prefixes should be kept together"
+    );
+    assert_eq!(&vec!["GSoC".to_string()], patch.tag().extra());
+    assert_eq!("RFC net-next GSoC", patch.tag().to_string());
+    assert_eq!(2, patch.version());
+    assert_eq!(1, patch.number_in_series());
+}
+
+#[test]
+fn should_parse_tags_glued_to_their_version() {
+    let patch = tagged_patch("[PATCHv3 RESEND] drm/amd: Fix bar");
+
+    assert!(patch.tag().resend());
+    assert_eq!(&None, patch.tag().target_tree());
+    assert_eq!(&Some("drm/amd".to_string()), patch.tag().subsystem());
+    assert!(patch.tag().extra().is_empty());
+    assert_eq!(3, patch.version());
+}
+
+#[test]
+fn should_parse_subsystem_of_untagged_title() {
+    let patch = tagged_patch("Revert \"drm/amd: Fix bar\"");
+
+    assert_eq!(
+        &None,
+        patch.tag().subsystem(),
+        "Colons after the first word shouldn't make a subsystem"
+    );
+    assert_eq!(
+        &Some("mm".to_string()),
+        tagged_patch("mm: Do baz").tag().subsystem()
+    );
+}
+
+#[test]
+fn should_filter_patches_by_tag() {
+    let rfc_net_next = tagged_patch("[RFC PATCH net-next] net: phy: Add foo");
+    let bpf = tagged_patch("[PATCH bpf] bpf: Fix bar");
+    let drm = tagged_patch("[PATCH] drm/amd: Fix baz");
+
+    let filter = PatchFilter {
+        hide_rfc: true,
+        ..Default::default()
+    };
+    assert!(!filter.matches(&rfc_net_next, Utc::now()));
+    assert!(filter.matches(&bpf, Utc::now()));
+
+    let filter = PatchFilter {
+        target_tree: Some("net-next".to_string()),
+        ..Default::default()
+    };
+    assert!(filter.matches(&rfc_net_next, Utc::now()));
+    assert!(!filter.matches(&bpf, Utc::now()));
+
+    let filter = PatchFilter {
+        subsystem: Some("drm".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        filter.matches(&drm, Utc::now()),
+        "Child subsystems should match"
+    );
+    assert!(!filter.matches(&bpf, Utc::now()));
+    assert!(!PatchFilter {
+        subsystem: Some("dr".to_string()),
+        ..Default::default()
+    }
+    .matches(&drm, Utc::now()));
+}
+
+#[test]
+fn should_cycle_target_trees() {
+    let target_trees = target_trees(&[
+        tagged_patch("[PATCH net-next] foo: Bar"),
+        tagged_patch("[PATCH bpf] foo: Baz"),
+        tagged_patch("[PATCH net-next v2] foo: Bar"),
+    ]);
+    assert_eq!(
+        vec!["bpf".to_string(), "net-next".to_string()],
+        target_trees
+    );
+
+    let mut filter = PatchFilter::default();
+    filter.cycle_target_tree(&target_trees);
+    assert_eq!(Some("bpf".to_string()), filter.target_tree);
+    filter.cycle_target_tree(&target_trees);
+    assert_eq!(Some("net-next".to_string()), filter.target_tree);
+    filter.cycle_target_tree(&target_trees);
+    assert_eq!(None, filter.target_tree, "Should go back to every tree");
+}
+
+#[test]
+fn should_deserialize_bookmarks_without_tag() {
+    let bookmark = r#"{"title":"foo: Bar","version":2,"number_in_series":1,"total_in_series":1,"author":{"name":"Foo Bar","email":"foo@bar.foo.bar"},"link":{"href":"http://lore.kernel.org/some-list/1234-1-foo@bar.foo.bar"},"in-reply-to":null,"updated":"2024-07-06T19:15:48Z"}"#;
+
+    let patch: Patch = serde_json::from_str(bookmark).unwrap();
+
+    assert_eq!(&PatchTag::default(), patch.tag());
+    assert_eq!(2, patch.version());
+}
+
+fn patch_updated_at(title: &str, updated: &str) -> Patch {
+    Patch::new(
+        title.to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.foo.bar".to_string(),
+        },
+        MessageID {
+            href: format!("http://lore.kernel.org/some-list/{title}"),
+        },
+        None,
+        updated.to_string(),
+    )
+}
+
+#[test]
+fn should_filter_patches_by_age() {
+    let now = timestamp::parse_timestamp("2024-07-18T16:49:00Z").unwrap();
+    let recent = patch_updated_at("recent", "2024-07-18T10:00:00Z");
+    let old = patch_updated_at("old", "2024-07-10T10:00:00Z");
+    let undated = patch_updated_at("undated", "");
+
+    let mut filter = PatchFilter::default();
+    assert!(filter.matches(&undated, now));
+
+    filter.cycle_max_age();
+    assert_eq!(Some(TimeDelta::days(1)), filter.max_age);
+    assert!(filter.matches(&recent, now));
+    assert!(!filter.matches(&old, now));
+    assert!(
+        !filter.matches(&undated, now),
+        "Patches without a known date should be hidden"
+    );
+
+    filter.cycle_max_age();
+    filter.cycle_max_age();
+    assert_eq!(Some(TimeDelta::days(30)), filter.max_age);
+    assert!(filter.matches(&old, now));
+    filter.cycle_max_age();
+    assert_eq!(None, filter.max_age);
+}
+
+#[test]
+fn should_rule_out_patches_older_than_max_age() {
+    let now = timestamp::parse_timestamp("2024-07-18T16:49:00Z").unwrap();
+    let recent = patch_updated_at("recent", "2024-07-18T10:00:00Z");
+    let old = patch_updated_at("old", "2024-07-10T10:00:00Z");
+    let undated = patch_updated_at("undated", "");
+
+    let mut filter = PatchFilter::default();
+    assert!(!filter.rules_out_older([&recent, &old], now));
+
+    filter.cycle_max_age();
+    assert!(!filter.rules_out_older([&recent, &undated], now));
+    assert!(filter.rules_out_older([&recent, &old, &undated], now));
+    assert!(!filter.rules_out_older([], now));
+}
+
+#[test]
+fn should_sort_patches_by_date() {
+    let older = patch_updated_at("older", "2024-07-10T10:00:00Z");
+    let newer = patch_updated_at("newer", "2024-07-18T10:00:00Z");
+    let undated = patch_updated_at("undated", "");
+
+    let mut patches = vec![&undated, &older, &newer];
+    PatchOrder::NewestFirst.sort(&mut patches);
+    assert_eq!(vec![&newer, &older, &undated], patches);
+    PatchOrder::OldestFirst.sort(&mut patches);
+    assert_eq!(
+        vec![&older, &newer, &undated],
+        patches,
+        "Patches without a known date should come last"
+    );
+}
+
+#[test]
+fn should_serialize_updated_as_in_feeds() {
+    let patch = patch_updated_at("foo", "Thu, 18 Jul 2024 13:49:00 -0300");
+
+    let serialized_patch = serde_json::to_string(&patch).unwrap();
+
+    assert!(serialized_patch.contains(r#""updated":"2024-07-18T16:49:00Z""#));
+    assert_eq!(
+        patch,
+        serde_json::from_str::<Patch>(&serialized_patch).unwrap()
+    );
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// Base term of every query, restricting the results to patches (and RFCs)
+/// that aren't replies.
+const BASE_QUERY_TERM: &str = "(s:patch OR s:rfc) AND NOT s:re:";
+
+/// Typed model of a public-inbox search query.
+///
+/// Every non-empty field is translated to its public-inbox search prefix and
+/// combined with the others (and the base patch term) using `AND`. Values
+/// containing whitespace are quoted, so they are matched as phrases.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoreQuery {
+    /// Author of the patch (`f:`)
+    pub author: Option<String>,
+    /// Term contained in the subject (`s:`)
+    pub subject: Option<String>,
+    /// File name touched by the diff (`dfn:`)
+    pub touched_file: Option<String>,
+    /// Lower end of the date range (`d:SINCE..`)
+    pub date_since: Option<String>,
+    /// Upper end of the date range (`d:..UNTIL`)
+    pub date_until: Option<String>,
+    /// Address or name in the Cc header (`c:`)
+    pub cc: Option<String>,
+    /// Free text appended as is, allowing any public-inbox search syntax
+    pub free_text: Option<String>,
+}
+
+impl LoreQuery {
+    /// Returns `true` if no field restricts the query, i.e., the query only
+    /// has the base patch term.
+    pub fn is_empty(&self) -> bool {
+        self.terms().is_empty()
+    }
+
+    /// Builds the value of the `q` URL parameter with all the terms of the
+    /// query already percent-encoded.
+    pub fn to_url_query(&self) -> String {
+        encode_query_value(&self.to_search_terms())
+    }
+
+    /// Builds the search terms of the query, combined with the base patch
+    /// term, in the public-inbox search syntax that `lei` also takes.
+    pub fn to_search_terms(&self) -> String {
+        let mut query = format!("({BASE_QUERY_TERM})");
+
+        for term in self.terms() {
+            query.push_str(&format!(" AND ({term})"));
+        }
+
+        query
+    }
+
+    /// Collects the prefixed search terms of every non-empty field.
+    fn terms(&self) -> Vec<String> {
+        let mut terms = Vec::new();
+
+        let prefixed_fields = [
+            ("f", &self.author),
+            ("s", &self.subject),
+            ("dfn", &self.touched_file),
+            ("c", &self.cc),
+        ];
+        for (prefix, value) in prefixed_fields {
+            if let Some(value) = non_empty(value) {
+                terms.push(format!("{prefix}:{}", quote_if_needed(value)));
+            }
+        }
+
+        let date_since = non_empty(&self.date_since);
+        let date_until = non_empty(&self.date_until);
+        if date_since.is_some() || date_until.is_some() {
+            terms.push(format!(
+                "d:{}..{}",
+                date_since.unwrap_or_default(),
+                date_until.unwrap_or_default()
+            ));
+        }
+
+        if let Some(free_text) = non_empty(&self.free_text) {
+            terms.push(free_text.to_string());
+        }
+
+        terms
+    }
+}
+
+impl Display for LoreQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms = self.terms();
+        if terms.is_empty() {
+            write!(f, "latest")
+        } else {
+            write!(f, "{}", terms.join(" "))
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn quote_if_needed(value: &str) -> String {
+    if value.contains(char::is_whitespace) && !value.starts_with('"') {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Percent-encodes a query value to be used in a URL, encoding spaces as `+`
+/// like public-inbox itself does.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in value.bytes() {
+        match byte {
+            b' ' => encoded.push('+'),
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~'
+            | b'('
+            | b')'
+            | b':'
+            | b'/'
+            | b'@'
+            | b'*' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+#[test]
+fn default_query_should_only_have_base_term() {
+    let query = LoreQuery::default();
+
+    assert!(query.is_empty(), "Default query shouldn't have any terms");
+    assert_eq!(
+        "((s:patch+OR+s:rfc)+AND+NOT+s:re:)",
+        query.to_url_query(),
+        "Default query should only request patches that aren't replies"
+    );
+}
+
+#[test]
+fn should_build_query_with_all_fields() {
+    let query = LoreQuery {
+        author: Some("john@johnson.com".to_string()),
+        subject: Some("some/subsystem".to_string()),
+        touched_file: Some("drivers/gpu/drm/vkms".to_string()),
+        date_since: Some("2024-01-01".to_string()),
+        date_until: Some("2024-06-30".to_string()),
+        cc: Some("foo@bar.foo.bar".to_string()),
+        free_text: Some("refactor".to_string()),
+    };
+
+    assert!(!query.is_empty());
+    assert_eq!(
+        "((s:patch+OR+s:rfc)+AND+NOT+s:re:)\
+        +AND+(f:john@johnson.com)\
+        +AND+(s:some/subsystem)\
+        +AND+(dfn:drivers/gpu/drm/vkms)\
+        +AND+(c:foo@bar.foo.bar)\
+        +AND+(d:2024-01-01..2024-06-30)\
+        +AND+(refactor)",
+        query.to_url_query(),
+        "Wrong query built from all fields"
+    );
+}
+
+#[test]
+fn should_quote_and_encode_values() {
+    let query = LoreQuery {
+        author: Some("John Johnson".to_string()),
+        date_since: Some("2.weeks.ago".to_string()),
+        free_text: Some("a&b".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        "((s:patch+OR+s:rfc)+AND+NOT+s:re:)\
+        +AND+(f:%22John+Johnson%22)\
+        +AND+(d:2.weeks.ago..)\
+        +AND+(a%26b)",
+        query.to_url_query(),
+        "Values with whitespace should be quoted and reserved chars encoded"
+    );
+    assert_eq!(
+        "((s:patch OR s:rfc) AND NOT s:re:) AND (f:\"John Johnson\") AND (d:2.weeks.ago..) AND (a&b)",
+        query.to_search_terms(),
+        "Search terms shouldn't be encoded"
+    );
+}
+
+#[test]
+fn should_ignore_blank_fields() {
+    let query = LoreQuery {
+        author: Some("   ".to_string()),
+        subject: Some(String::new()),
+        ..Default::default()
+    };
+
+    assert!(query.is_empty(), "Blank fields shouldn't become terms");
+    assert_eq!("latest", query.to_string());
+}
+
+#[test]
+fn should_display_human_readable_terms() {
+    let query = LoreQuery {
+        subject: Some("vkms".to_string()),
+        date_until: Some("2024-06-30".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!("s:vkms d:..2024-06-30", query.to_string());
+}
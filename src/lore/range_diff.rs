@@ -0,0 +1,190 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use similar::TextDiff;
+
+use super::{mbox::Message, patch::PatchRegex, series};
+
+#[cfg(test)]
+mod tests;
+
+static RE_HUNK_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^@@ -\d+(,\d+)? \+\d+(,\d+)? @@").unwrap());
+
+/// A patch of one of the versions being compared
+struct VersionPatch {
+    /// Number in the series, as in the subject, with `0` for cover letters
+    number: usize,
+    /// Normalized title without the patch tag, used to pair the patches
+    title: String,
+    /// Message and diff, without the noise that always changes between
+    /// versions, like the line numbers of the hunks
+    content: String,
+}
+
+impl VersionPatch {
+    fn parse(raw_patch: &str, patch_regex: &PatchRegex) -> VersionPatch {
+        let message = Message::parse(raw_patch);
+        let subject = message.subject();
+        let patch_tag = patch_regex.re_patch_tag.find(subject);
+
+        let number = patch_tag
+            .and_then(|patch_tag| patch_regex.re_patch_series.captures(patch_tag.as_str()))
+            .and_then(|capture| capture[1].parse().ok())
+            .unwrap_or(1);
+        let title = match patch_tag {
+            Some(patch_tag) => subject.replace(patch_tag.as_str(), ""),
+            None => subject.to_string(),
+        };
+
+        VersionPatch {
+            number,
+            title: series::normalize_title(&title),
+            content: strip_noise(message.body()),
+        }
+    }
+}
+
+/// Compares two versions of a patchset, given as their raw patches (see
+/// [crate::lore::lore_session::split_patchset]), the way `git range-diff`
+/// does, but comparing the patches as text instead of as commits.
+///
+/// Patches are paired by their titles, cover letter with cover letter. The
+/// first page returned lists the pairs, marking them with `=` if the patch
+/// didn't change, `!` if it did, `<` if it was dropped and `>` if it was
+/// added. Each following page is the diff of a patch that changed, as a
+/// unified diff of the patches themselves, so it can be shown with the same
+/// renderers as patches.
+pub fn range_diff(
+    old_version: usize,
+    old_raw_patches: &[String],
+    new_version: usize,
+    new_raw_patches: &[String],
+) -> Vec<String> {
+    let patch_regex = PatchRegex::new();
+    let old_patches: Vec<VersionPatch> = old_raw_patches
+        .iter()
+        .map(|raw_patch| VersionPatch::parse(raw_patch, &patch_regex))
+        .collect();
+    let new_patches: Vec<VersionPatch> = new_raw_patches
+        .iter()
+        .map(|raw_patch| VersionPatch::parse(raw_patch, &patch_regex))
+        .collect();
+
+    let pairs = pair_patches(&old_patches, &new_patches);
+
+    let mut summary = format!("Range-diff v{old_version}..v{new_version}\n\n");
+    let mut pages = Vec::new();
+    for (old_patch, new_patch) in pairs {
+        let (old_number, old_title) = describe(old_patch);
+        let (new_number, new_title) = describe(new_patch);
+        let old_content = old_patch.map_or("", |old_patch| &old_patch.content);
+        let new_content = new_patch.map_or("", |new_patch| &new_patch.content);
+
+        let marker = match (old_patch, new_patch) {
+            (Some(_), None) => '<',
+            (None, Some(_)) => '>',
+            _ if old_content == new_content => '=',
+            _ => '!',
+        };
+        summary.push_str(&format!(
+            "{old_number:>3}: {old_title} {marker} {new_number:>3}: {new_title}\n"
+        ));
+
+        if marker != '=' {
+            let old_name = format!("v{old_version}/{old_number}");
+            let new_name = format!("v{new_version}/{new_number}");
+            let diff = TextDiff::from_lines(old_content, new_content)
+                .unified_diff()
+                .header(&old_name, &new_name)
+                .to_string();
+            pages.push(format!(
+                "{marker} {old_number}: {old_title} -> {new_number}: {new_title}\n\n{diff}"
+            ));
+        }
+    }
+
+    pages.insert(0, summary);
+    pages
+}
+
+/// Pairs the patches of two versions, in the order of the new version. The
+/// dropped patches go right before the old patch that came after them.
+fn pair_patches<'a>(
+    old_patches: &'a [VersionPatch],
+    new_patches: &'a [VersionPatch],
+) -> Vec<(Option<&'a VersionPatch>, Option<&'a VersionPatch>)> {
+    let mut is_paired = vec![false; old_patches.len()];
+    let mut pairs = Vec::new();
+
+    for new_patch in new_patches {
+        let old_index = (0..old_patches.len()).find(|&index| {
+            !is_paired[index]
+                && if new_patch.number == 0 {
+                    old_patches[index].number == 0
+                } else {
+                    old_patches[index].number != 0 && old_patches[index].title == new_patch.title
+                }
+        });
+
+        match old_index {
+            Some(old_index) => {
+                // Dropped patches show up right before their successor
+                for (index, old_patch) in old_patches.iter().enumerate().take(old_index) {
+                    if !is_paired[index] && !is_pairable(old_patch, new_patches) {
+                        is_paired[index] = true;
+                        pairs.push((Some(old_patch), None));
+                    }
+                }
+                is_paired[old_index] = true;
+                pairs.push((Some(&old_patches[old_index]), Some(new_patch)));
+            }
+            None => pairs.push((None, Some(new_patch))),
+        }
+    }
+
+    for (index, old_patch) in old_patches.iter().enumerate() {
+        if !is_paired[index] {
+            pairs.push((Some(old_patch), None));
+        }
+    }
+
+    pairs
+}
+
+fn is_pairable(old_patch: &VersionPatch, new_patches: &[VersionPatch]) -> bool {
+    new_patches.iter().any(|new_patch| {
+        if old_patch.number == 0 {
+            new_patch.number == 0
+        } else {
+            new_patch.title == old_patch.title
+        }
+    })
+}
+
+/// Number and title of a side of a pair, with dashes for missing patches.
+fn describe(patch: Option<&VersionPatch>) -> (String, &str) {
+    match patch {
+        Some(patch) => (patch.number.to_string(), &patch.title),
+        None => ("-".to_string(), "-"),
+    }
+}
+
+/// Drops, from the body of a patch, what changes between versions even when
+/// the patch doesn't: the hashes of the `index` lines, the line numbers of
+/// the hunks and the signature with the version of `git`.
+fn strip_noise(body: &str) -> String {
+    let body = body
+        .rfind("\n-- \n")
+        .map_or(body, |signature_start| &body[..=signature_start]);
+
+    let mut content = String::new();
+    for line in body.lines() {
+        if line.starts_with("index ") {
+            continue;
+        }
+        content.push_str(&RE_HUNK_HEADER.replace(line, "@@"));
+        content.push('\n');
+    }
+    content
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+fn raw_patch(subject: &str, body: &str) -> String {
+    format!("Subject: {subject}\nFrom: Foo Bar <foo@bar.foo.bar>\n\n{body}")
+}
+
+#[test]
+fn should_pair_patches_and_diff_the_changed_ones() {
+    let old_raw_patches = vec![
+        raw_patch("[PATCH 0/3] file: Do things", "Cover\n"),
+        raw_patch(
+            "[PATCH 1/3] file: Do foo",
+            "Patch 1\n---\ndiff --git a/file.rs b/file.rs\nindex abcdef..fedcba 100644\n@@ -57,6 +57,6 @@ CONTEXT;\n-deletion\n+addition\n-- \n2.34.1\n",
+        ),
+        raw_patch("[PATCH 2/3] file: Do bar", "Patch 2\n"),
+        raw_patch("[PATCH 3/3] file: Do baz", "Patch 3\n"),
+    ];
+    let new_raw_patches = vec![
+        raw_patch("[PATCH v2 0/3] file: Do things", "Cover\n"),
+        raw_patch(
+            "[PATCH v2 1/3] file: Do foo",
+            "Patch 1\n---\ndiff --git a/file.rs b/file.rs\nindex 123456..654321 100644\n@@ -60,6 +60,6 @@ CONTEXT;\n-deletion\n+addition\n-- \n2.45.0\n",
+        ),
+        raw_patch("[PATCH v2 2/3] file: Do baz", "Patch 3, now better\n"),
+        raw_patch("[PATCH v2 3/3] file: Do qux", "Patch 4\n"),
+    ];
+
+    let pages = range_diff(1, &old_raw_patches, 2, &new_raw_patches);
+
+    assert_eq!(
+        "Range-diff v1..v2\n\n\
+         \x20 0: file: do things =   0: file: do things\n\
+         \x20 1: file: do foo =   1: file: do foo\n\
+         \x20 2: file: do bar <   -: -\n\
+         \x20 3: file: do baz !   2: file: do baz\n\
+         \x20 -: - >   3: file: do qux\n",
+        pages[0],
+        "Moved hunks and new hashes shouldn't count as changes"
+    );
+    assert_eq!(4, pages.len(), "Only changed patches should have a page");
+    assert!(pages[1].starts_with("< 2: file: do bar -> -: -\n"));
+    assert_eq!(
+        "! 3: file: do baz -> 2: file: do baz\n\n\
+         --- v1/3\n\
+         +++ v2/2\n\
+         @@ -1 +1 @@\n\
+         -Patch 3\n\
+         +Patch 3, now better\n",
+        pages[2]
+    );
+    assert!(pages[3].contains("+Patch 4\n"));
+}
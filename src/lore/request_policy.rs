@@ -0,0 +1,108 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How many times a failed request is retried, by default
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// How many requests per second are sent to lore, at most, by default
+pub const DEFAULT_MAX_REQUESTS_PER_SECOND: u32 = 4;
+
+/// Defines how requests that failed for transient reasons are retried.
+///
+/// The delay before each retry grows exponentially from `base_delay`, up to
+/// `max_delay`, with a random jitter so that many clients failing at once
+/// don't retry at the same time.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(DEFAULT_MAX_RETRIES)
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Delay before the retry number `retry`, counting from 0. It's at least
+    /// half of the exponential delay, plus a random share of the other half.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponential_delay = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let half_delay = exponential_delay / 2;
+
+        half_delay + half_delay.mul_f64(jitter())
+    }
+}
+
+/// Random number in `[0, 1]`, good enough to spread retries.
+fn jitter() -> f64 {
+    RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64
+}
+
+/// Spaces out requests so that no more than a given number of them are sent
+/// per second. Copies of a limiter share the same pace.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    /// When the next request may be sent
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(DEFAULT_MAX_REQUESTS_PER_SECOND)
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter for `max_requests_per_second`. Zero means no limit.
+    pub fn new(max_requests_per_second: u32) -> RateLimiter {
+        let min_interval = match max_requests_per_second {
+            0 => Duration::ZERO,
+            max_requests_per_second => Duration::from_secs(1) / max_requests_per_second,
+        };
+
+        RateLimiter {
+            min_interval,
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Blocks until a request may be sent.
+    pub fn acquire(&self) {
+        let wait_time = self.reserve(Instant::now());
+        if !wait_time.is_zero() {
+            thread::sleep(wait_time);
+        }
+    }
+
+    /// Reserves the next slot for a request, returning how long to wait from
+    /// `now` until it.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let slot = (*next_slot).max(now);
+        *next_slot = slot + self.min_interval;
+
+        slot - now
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+#[test]
+fn backoff_should_grow_exponentially_with_jitter() {
+    let retry_policy = RetryPolicy {
+        max_retries: 5,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(500),
+    };
+
+    for (retry, exponential_delay) in [(0, 100), (1, 200), (2, 400), (3, 500), (10, 500)] {
+        let exponential_delay = Duration::from_millis(exponential_delay);
+        let backoff = retry_policy.backoff(retry);
+
+        assert!(
+            backoff >= exponential_delay / 2 && backoff <= exponential_delay,
+            "Backoff {backoff:?} of retry {retry} should be between {:?} and {exponential_delay:?}",
+            exponential_delay / 2
+        );
+    }
+}
+
+#[test]
+fn rate_limiter_should_space_out_requests() {
+    let rate_limiter = RateLimiter::new(4);
+    let copied_rate_limiter = rate_limiter.clone();
+    let now = Instant::now();
+
+    assert_eq!(Duration::ZERO, rate_limiter.reserve(now));
+    assert_eq!(
+        Duration::from_millis(250),
+        copied_rate_limiter.reserve(now),
+        "Copies of the limiter should share the pace"
+    );
+    assert_eq!(Duration::from_millis(500), rate_limiter.reserve(now));
+    assert_eq!(
+        Duration::ZERO,
+        rate_limiter.reserve(now + Duration::from_secs(1)),
+        "Idle time shouldn't be saved for later bursts"
+    );
+}
+
+#[test]
+fn rate_limiter_without_limit_should_never_wait() {
+    let rate_limiter = RateLimiter::new(0);
+    let now = Instant::now();
+
+    for _ in 0..10 {
+        assert_eq!(Duration::ZERO, rate_limiter.reserve(now));
+    }
+}
@@ -0,0 +1,49 @@
+use std::{collections::HashSet, fs::File, io};
+
+use serde::{Deserialize, Serialize};
+
+use super::{json_file, patch::Patch};
+
+#[cfg(test)]
+mod tests;
+
+/// Patchsets already opened, told apart by their bare message-id, so
+/// patchsets sent to several lists are read once
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct SeenPatchsets {
+    message_ids: HashSet<String>,
+}
+
+impl SeenPatchsets {
+    pub fn is_seen(&self, patch: &Patch) -> bool {
+        self.message_ids.contains(patch.message_id().id())
+    }
+
+    /// Marks `patch` as seen. Returns `false` if it already was.
+    pub fn mark_seen(&mut self, patch: &Patch) -> bool {
+        self.message_ids.insert(patch.message_id().id().to_string())
+    }
+
+    /// Marks every one of `patches` as seen. Returns `false` if they all
+    /// already were.
+    pub fn mark_all_seen<'a>(&mut self, patches: impl IntoIterator<Item = &'a Patch>) -> bool {
+        let seen_count = self.message_ids.len();
+        self.message_ids.extend(
+            patches
+                .into_iter()
+                .map(|patch| patch.message_id().id().to_string()),
+        );
+        self.message_ids.len() > seen_count
+    }
+}
+
+pub fn save_seen_patchsets(seen_patchsets: &SeenPatchsets, filepath: &str) -> io::Result<()> {
+    json_file::save_json_atomically(filepath, seen_patchsets)
+}
+
+pub fn load_seen_patchsets(filepath: &str) -> io::Result<SeenPatchsets> {
+    let seen_patchsets_file = File::open(filepath)?;
+    let seen_patchsets = serde_json::from_reader(seen_patchsets_file)?;
+    Ok(seen_patchsets)
+}
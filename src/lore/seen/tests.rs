@@ -0,0 +1,50 @@
+use super::*;
+use crate::lore::patch::{Author, MessageID};
+
+fn patch(href: &str) -> Patch {
+    Patch::new(
+        "[PATCH] some/subsystem: Do something".to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: "foo@bar.com".to_string(),
+        },
+        MessageID {
+            href: href.to_string(),
+        },
+        None,
+        "2024-07-18T16:49:00Z".to_string(),
+    )
+}
+
+#[test]
+fn should_tell_seen_patchsets_apart_by_bare_message_id() {
+    let netdev_patch = patch("https://lore.kernel.org/netdev/1234@foo.bar/");
+    let kselftest_patch = patch("https://lore.kernel.org/linux-kselftest/1234@foo.bar/");
+    let other_patch = patch("https://lore.kernel.org/netdev/5678@foo.bar/");
+    let mut seen_patchsets = SeenPatchsets::default();
+
+    assert!(seen_patchsets.mark_seen(&netdev_patch));
+    assert!(!seen_patchsets.mark_seen(&netdev_patch));
+    assert!(
+        seen_patchsets.is_seen(&kselftest_patch),
+        "Patchsets sent to several lists should be seen in all of them"
+    );
+    assert!(!seen_patchsets.is_seen(&other_patch));
+
+    assert!(seen_patchsets.mark_all_seen([&kselftest_patch, &other_patch]));
+    assert!(seen_patchsets.is_seen(&other_patch));
+    assert!(!seen_patchsets.mark_all_seen([&netdev_patch, &other_patch]));
+}
+
+#[test]
+fn should_serialize_seen_patchsets_as_list_of_message_ids() {
+    let mut seen_patchsets = SeenPatchsets::default();
+    seen_patchsets.mark_seen(&patch("https://lore.kernel.org/netdev/1234@foo.bar/"));
+
+    let serialized_seen_patchsets = serde_json::to_string(&seen_patchsets).unwrap();
+    assert_eq!(r#"["1234@foo.bar"]"#, serialized_seen_patchsets);
+    assert_eq!(
+        seen_patchsets,
+        serde_json::from_str(&serialized_seen_patchsets).unwrap()
+    );
+}
@@ -0,0 +1,392 @@
+use std::{collections::BTreeMap, fmt::Display, sync::LazyLock};
+
+use derive_getters::Getters;
+use regex::Regex;
+
+use super::{
+    lore_api_client::{ClientError, PatchFeedRequest},
+    lore_session::{LoreSession, LoreSessionError},
+    mbox::Message,
+    patch::{Patch, PatchRegex},
+    query::LoreQuery,
+};
+
+#[cfg(test)]
+mod tests;
+
+static RE_CHANGE_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^change-id:[ \t]*(\S+)[ \t]*$").unwrap());
+static RE_BASE_COMMIT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^base-commit:[ \t]*([0-9a-fA-F]+)[ \t]*$").unwrap());
+static RE_PREREQUISITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^prerequisite-(patch-id|change-id|message-id):[ \t]*(\S+)[ \t]*$").unwrap()
+});
+
+/// Extracts the `change-id` trailer that `b4` adds to the cover letter (or to
+/// the single patch) of the series it sends, which stays the same across all
+/// versions of the series.
+pub fn extract_change_id(text: &str) -> Option<String> {
+    RE_CHANGE_ID
+        .captures(text)
+        .map(|capture| capture[1].to_string())
+}
+
+/// Series a `b4` series depends on, as listed in its cover letter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prerequisite {
+    /// Stable `git patch-id` of a patch that must be applied first
+    PatchId(String),
+    /// `change-id` of a series that must be applied first, usually with the
+    /// version after a colon, like `20240718-foo-v1-0-abcdef:v2`
+    ChangeId(String),
+    /// Message-id of a series that must be applied first
+    MessageId(String),
+}
+
+impl Prerequisite {
+    /// Search term that finds the messages of the prerequisite
+    fn search_term(&self) -> String {
+        match self {
+            Prerequisite::PatchId(patch_id) => format!("patchid:{patch_id}"),
+            Prerequisite::ChangeId(change_id) => {
+                let (change_id, _) = split_change_id_version(change_id);
+                format!("\"change-id: {change_id}\"")
+            }
+            Prerequisite::MessageId(message_id) => {
+                format!("m:{}", message_id.trim_matches(['<', '>']))
+            }
+        }
+    }
+}
+
+impl Display for Prerequisite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Prerequisite::PatchId(patch_id) => write!(f, "patch-id {patch_id}"),
+            Prerequisite::ChangeId(change_id) => write!(f, "change-id {change_id}"),
+            Prerequisite::MessageId(message_id) => write!(f, "message-id {message_id}"),
+        }
+    }
+}
+
+/// Splits the version off a prerequisite `change-id`, like `v2` in
+/// `20240718-foo-v1-0-abcdef:v2`.
+fn split_change_id_version(change_id: &str) -> (&str, Option<usize>) {
+    match change_id.rsplit_once(':') {
+        Some((change_id, version)) => (
+            change_id,
+            version
+                .strip_prefix(['v', 'V'])
+                .and_then(|version| version.parse().ok()),
+        ),
+        None => (change_id, None),
+    }
+}
+
+/// Metadata that `b4` adds to the cover letter (or to the single patch) of
+/// the series it sends
+#[derive(Getters, Debug, Clone, Default, PartialEq)]
+pub struct SeriesMetadata {
+    /// Commit the series was based on, to apply it on top of
+    base_commit: Option<String>,
+    /// See [extract_change_id]
+    change_id: Option<String>,
+    prerequisites: Vec<Prerequisite>,
+}
+
+impl SeriesMetadata {
+    /// Extracts the metadata from the raw patches of a downloaded patchset
+    /// (see [crate::lore::lore_session::split_patchset]). The first patch
+    /// carrying each field wins, as it is the cover letter, if any.
+    pub fn parse(raw_patches: &[String]) -> SeriesMetadata {
+        let mut prerequisites = Vec::new();
+        for capture in raw_patches
+            .iter()
+            .flat_map(|raw_patch| RE_PREREQUISITE.captures_iter(raw_patch))
+        {
+            let value = capture[2].to_string();
+            let prerequisite = match &capture[1] {
+                "patch-id" => Prerequisite::PatchId(value),
+                "change-id" => Prerequisite::ChangeId(value),
+                _ => Prerequisite::MessageId(value),
+            };
+            if !prerequisites.contains(&prerequisite) {
+                prerequisites.push(prerequisite);
+            }
+        }
+
+        SeriesMetadata {
+            base_commit: raw_patches.iter().find_map(|raw_patch| {
+                RE_BASE_COMMIT
+                    .captures(raw_patch)
+                    .map(|capture| capture[1].to_string())
+            }),
+            change_id: raw_patches
+                .iter()
+                .find_map(|raw_patch| extract_change_id(raw_patch)),
+            prerequisites,
+        }
+    }
+}
+
+/// Looks up, in `target_list`, the series of `prerequisite`. For `change-id`s
+/// with a version, that version is preferred, otherwise the newest version
+/// found is taken.
+///
+/// # Errors
+///
+/// Returns an error if the search fails.
+pub fn fetch_prerequisite<T: PatchFeedRequest>(
+    lore_api_client: &T,
+    target_list: &str,
+    prerequisite: &Prerequisite,
+) -> Result<Option<Patch>, LoreSessionError> {
+    let query = LoreQuery {
+        free_text: Some(prerequisite.search_term()),
+        ..Default::default()
+    };
+    let mut candidates = search_representative_patches(lore_api_client, target_list, query)?;
+
+    let wanted_version = match prerequisite {
+        Prerequisite::ChangeId(change_id) => split_change_id_version(change_id).1,
+        _ => None,
+    };
+    if let Some(position) = wanted_version.and_then(|version| {
+        candidates
+            .iter()
+            .position(|candidate| candidate.version() == version)
+    }) {
+        return Ok(Some(candidates.swap_remove(position)));
+    }
+    Ok(candidates
+        .into_iter()
+        .max_by_key(|candidate| candidate.version()))
+}
+
+/// Title of a patch with case and whitespace differences smoothed out, to
+/// match the titles of different versions of the same series.
+pub fn normalize_title(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Whether `candidate` looks like a version of the same series as `patch`,
+/// i.e., it has the same normalized title and was sent by the same author.
+/// Only used when the series can't be told apart by their `change-id`.
+pub fn is_same_series(patch: &Patch, candidate: &Patch) -> bool {
+    normalize_title(patch.title()) == normalize_title(candidate.title())
+        && patch
+            .author()
+            .email
+            .eq_ignore_ascii_case(&candidate.author().email)
+}
+
+/// Versions of a series, one representative patch per version, ordered from
+/// the oldest to the newest version.
+#[derive(Getters, Debug, Clone, PartialEq)]
+pub struct SeriesHistory {
+    versions: Vec<Patch>,
+}
+
+impl SeriesHistory {
+    /// Builds the history of the series of `patch` out of `candidates`, which
+    /// must already be known to be versions of it. If a version was sent more
+    /// than once, the first candidate of the version wins, except for the
+    /// version of `patch`, which is always `patch` itself.
+    pub fn new(patch: &Patch, candidates: impl IntoIterator<Item = Patch>) -> SeriesHistory {
+        let mut versions: BTreeMap<usize, Patch> = BTreeMap::new();
+        versions.insert(patch.version(), patch.clone());
+        for candidate in candidates {
+            versions.entry(candidate.version()).or_insert(candidate);
+        }
+
+        SeriesHistory {
+            versions: versions.into_values().collect(),
+        }
+    }
+
+    /// Index of `patch` in [SeriesHistory::versions], if it's one of them.
+    pub fn position(&self, patch: &Patch) -> Option<usize> {
+        self.versions
+            .iter()
+            .position(|version| version.message_id() == patch.message_id())
+    }
+
+    pub fn contains(&self, patch: &Patch) -> bool {
+        self.position(patch).is_some()
+    }
+
+    /// Version right before the one of `patch`, if any.
+    pub fn previous(&self, patch: &Patch) -> Option<&Patch> {
+        let position = self.position(patch)?;
+        self.versions.get(position.checked_sub(1)?)
+    }
+
+    /// Version right after the one of `patch`, if any.
+    pub fn next(&self, patch: &Patch) -> Option<&Patch> {
+        let position = self.position(patch)?;
+        self.versions.get(position + 1)
+    }
+}
+
+/// Looks up, in the list of `patch`, the other versions of its series.
+///
+/// If `change_id` is known, the patchsets carrying it are taken as versions
+/// of the series. The versions that still weren't found, like those sent
+/// before the series was managed with `b4`, are looked up by title and author.
+///
+/// # Errors
+///
+/// Returns an error if any of the searches fails.
+pub fn fetch_series_history<T: PatchFeedRequest>(
+    lore_api_client: &T,
+    patch: &Patch,
+    change_id: Option<&str>,
+) -> Result<SeriesHistory, LoreSessionError> {
+    let target_list = patch.message_id().list();
+    let mut candidates = Vec::new();
+
+    if let Some(change_id) = change_id {
+        let query = LoreQuery {
+            free_text: Some(format!("\"change-id: {change_id}\"")),
+            ..Default::default()
+        };
+        candidates.extend(search_representative_patches(
+            lore_api_client,
+            target_list,
+            query,
+        )?);
+    }
+
+    let query = LoreQuery {
+        author: Some(patch.author().email.clone()),
+        subject: Some(patch.title().replace('"', "")),
+        ..Default::default()
+    };
+    candidates.extend(
+        search_representative_patches(lore_api_client, target_list, query)?
+            .into_iter()
+            .filter(|candidate| is_same_series(patch, candidate)),
+    );
+
+    Ok(SeriesHistory::new(patch, candidates))
+}
+
+/// Representative patches of the first page of results of `query`, which is
+/// plenty for searches as narrow as the ones for a single series.
+fn search_representative_patches<T: PatchFeedRequest>(
+    lore_api_client: &T,
+    target_list: &str,
+    query: LoreQuery,
+) -> Result<Vec<Patch>, LoreSessionError> {
+    let mut lore_session = LoreSession::with_query(target_list.to_string(), query);
+    match lore_session.process_n_representative_patches(lore_api_client, 1) {
+        Ok(()) | Err(LoreSessionError::FromLoreAPIClient(ClientError::EndOfFeed)) => {}
+        Err(error) => return Err(error),
+    }
+
+    Ok(lore_session
+        .representative_patches_ids()
+        .iter()
+        .filter_map(|message_id| lore_session.get_processed_patch(message_id).cloned())
+        .collect())
+}
+
+/// Parts of a series, as seen in a feed or in a downloaded patchset, to tell
+/// if any of its patches is missing or came more than once.
+#[derive(Getters, Debug, Clone, Default, PartialEq)]
+pub struct SeriesParts {
+    /// How many patches the series has, as told by the subjects of its parts
+    total: usize,
+    /// Message-ids of the parts, by their number in the series, with `0` for
+    /// the cover letter
+    parts: BTreeMap<usize, Vec<String>>,
+}
+
+impl SeriesParts {
+    pub fn new(total: usize) -> SeriesParts {
+        SeriesParts {
+            total,
+            parts: BTreeMap::new(),
+        }
+    }
+
+    /// Takes the raw patches of a downloaded patchset (see
+    /// [crate::lore::lore_session::split_patchset]) as the parts of a series
+    /// of `total` patches.
+    pub fn from_raw_patches(total: usize, raw_patches: &[String]) -> SeriesParts {
+        let patch_regex = PatchRegex::new();
+        let mut series_parts = SeriesParts::new(total);
+        for raw_patch in raw_patches {
+            let message = Message::parse(raw_patch);
+            let number = patch_regex
+                .re_patch_tag
+                .find(message.subject())
+                .and_then(|patch_tag| patch_regex.re_patch_series.captures(patch_tag.as_str()))
+                .and_then(|capture| capture[1].parse().ok())
+                .unwrap_or(1);
+            let message_id = message.message_id().unwrap_or_default().to_string();
+            series_parts.add(number, message_id);
+        }
+        series_parts
+    }
+
+    /// Records the message `message_id` as the part numbered `number`.
+    pub fn add(&mut self, number: usize, message_id: String) {
+        let messages = self.parts.entry(number).or_default();
+        if !messages.contains(&message_id) {
+            messages.push(message_id);
+        }
+    }
+
+    pub fn contains(&self, number: usize) -> bool {
+        self.parts.contains_key(&number)
+    }
+
+    /// Numbers of the patches that weren't seen. The cover letter is
+    /// optional, so it's never missing.
+    pub fn missing(&self) -> Vec<usize> {
+        (1..=self.total)
+            .filter(|number| !self.contains(*number))
+            .collect()
+    }
+
+    /// Numbers of the parts seen more than once, as different messages.
+    pub fn duplicated(&self) -> Vec<usize> {
+        self.parts
+            .iter()
+            .filter(|(_, messages)| messages.len() > 1)
+            .map(|(number, _)| *number)
+            .collect()
+    }
+
+    /// Whether every patch was seen exactly once.
+    pub fn is_intact(&self) -> bool {
+        self.missing().is_empty() && self.duplicated().is_empty()
+    }
+
+    /// Describes what is wrong with the series, like `missing 2/3`, if
+    /// anything.
+    pub fn problems(&self) -> Option<String> {
+        let describe = |numbers: Vec<usize>| {
+            numbers
+                .iter()
+                .map(|number| format!("{number}/{}", self.total))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut problems = Vec::new();
+        if !self.missing().is_empty() {
+            problems.push(format!("missing {}", describe(self.missing())));
+        }
+        if !self.duplicated().is_empty() {
+            problems.push(format!("duplicated {}", describe(self.duplicated())));
+        }
+
+        (!problems.is_empty()).then(|| problems.join("; "))
+    }
+}
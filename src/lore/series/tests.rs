@@ -0,0 +1,279 @@
+use super::*;
+use crate::lore::{
+    atom::{self, FeedEntry},
+    lore_api_client::MockPatchFeedRequest,
+    patch::{Author, MessageID, PatchRegex},
+};
+
+fn patch(title: &str, email: &str, message_id: &str) -> Patch {
+    let mut patch = Patch::new(
+        title.to_string(),
+        Author {
+            name: "Foo Bar".to_string(),
+            email: email.to_string(),
+        },
+        MessageID {
+            href: format!("https://lore.kernel.org/some-list/{message_id}/"),
+        },
+        None,
+        "2024-07-18T16:49:00Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+    patch
+}
+
+fn feed(patches: &[(&str, &str, &str)]) -> String {
+    let entries: Vec<FeedEntry> = patches
+        .iter()
+        .map(|(title, email, message_id)| FeedEntry {
+            title: title.to_string(),
+            author_name: "Foo Bar".to_string(),
+            author_email: email.to_string(),
+            updated: "2024-07-18T16:49:00Z".to_string(),
+            href: format!("https://lore.kernel.org/some-list/{message_id}/"),
+            in_reply_to_href: None,
+        })
+        .collect();
+    atom::render_feed(&entries)
+}
+
+#[test]
+fn should_extract_change_id() {
+    let cover = "Subject: [PATCH v2 0/2] Fix something\n\nSome text\n\n---\nbase-commit: 0123abcd\nchange-id: 20240718-fix-something-1a2b3c4d\n\nBest regards,\n";
+
+    assert_eq!(
+        Some("20240718-fix-something-1a2b3c4d".to_string()),
+        extract_change_id(cover)
+    );
+    assert_eq!(
+        None,
+        extract_change_id("The change-id: trailer isn't mentioned in a line of its own")
+    );
+}
+
+#[test]
+fn should_match_series_by_title_and_author() {
+    let v1 = patch(
+        "[PATCH 0/2] drm/vkms: Fix  something",
+        "foo@bar.foo.bar",
+        "v1@foo.bar",
+    );
+    let v2 = patch(
+        "[PATCH v2 0/2] drm/vkms: fix something",
+        "Foo@Bar.foo.bar",
+        "v2@foo.bar",
+    );
+    let other_author = patch(
+        "[PATCH v2 0/2] drm/vkms: Fix something",
+        "bar@foo.foo.bar",
+        "other@foo.bar",
+    );
+
+    assert!(is_same_series(&v1, &v2));
+    assert!(!is_same_series(&v1, &other_author));
+}
+
+#[test]
+fn should_order_versions_and_navigate_them() {
+    let v1 = patch("[PATCH 0/2] Fix something", "foo@bar.foo.bar", "v1@foo.bar");
+    let v2 = patch(
+        "[PATCH v2 0/2] Fix something",
+        "foo@bar.foo.bar",
+        "v2@foo.bar",
+    );
+    let v2_resent = patch(
+        "[PATCH RESEND v2 0/2] Fix something",
+        "foo@bar.foo.bar",
+        "v2-resent@foo.bar",
+    );
+    let v3 = patch(
+        "[PATCH v3 0/2] Fix something",
+        "foo@bar.foo.bar",
+        "v3@foo.bar",
+    );
+
+    let series_history = SeriesHistory::new(&v2, vec![v3.clone(), v2_resent.clone(), v1.clone()]);
+
+    assert_eq!(
+        &vec![v1.clone(), v2.clone(), v3.clone()],
+        series_history.versions(),
+        "Versions should be ordered and the one of the patch should be the patch itself"
+    );
+    assert_eq!(Some(&v1), series_history.previous(&v2));
+    assert_eq!(Some(&v3), series_history.next(&v2));
+    assert_eq!(None, series_history.previous(&v1));
+    assert_eq!(None, series_history.next(&v3));
+    assert!(!series_history.contains(&v2_resent));
+}
+
+#[test]
+fn should_fetch_versions_by_change_id_and_by_title() {
+    let mut lore_api_client = MockPatchFeedRequest::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|target_list, query, min_index| {
+            target_list == "some-list"
+                && query.free_text.as_deref()
+                    == Some("\"change-id: 20240718-fix-something-1a2b3c4d\"")
+                && *min_index == 0
+        })
+        .returning(|_, _, _| {
+            Ok(feed(&[
+                (
+                    "[PATCH v3 0/2] Fix something better",
+                    "foo@bar.foo.bar",
+                    "v3@foo.bar",
+                ),
+                (
+                    "[PATCH v2 0/2] Fix something",
+                    "foo@bar.foo.bar",
+                    "v2@foo.bar",
+                ),
+            ]))
+        });
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|target_list, query, min_index| {
+            target_list == "some-list"
+                && query.author.as_deref() == Some("foo@bar.foo.bar")
+                && query.subject.as_deref() == Some("Fix something")
+                && *min_index == 0
+        })
+        .returning(|_, _, _| {
+            Ok(feed(&[
+                (
+                    "[PATCH v2 0/2] Fix something",
+                    "foo@bar.foo.bar",
+                    "v2@foo.bar",
+                ),
+                (
+                    "[PATCH 0/3] Fix something else",
+                    "foo@bar.foo.bar",
+                    "other@foo.bar",
+                ),
+                ("[PATCH 0/2] Fix something", "foo@bar.foo.bar", "v1@foo.bar"),
+            ]))
+        });
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+
+    let v2 = patch(
+        "[PATCH v2 0/2] Fix something",
+        "foo@bar.foo.bar",
+        "v2@foo.bar",
+    );
+    let series_history = fetch_series_history(
+        &lore_api_client,
+        &v2,
+        Some("20240718-fix-something-1a2b3c4d"),
+    )
+    .unwrap();
+
+    let message_ids: Vec<&str> = series_history
+        .versions()
+        .iter()
+        .map(|version| version.message_id().id())
+        .collect();
+    assert_eq!(vec!["v1@foo.bar", "v2@foo.bar", "v3@foo.bar"], message_ids);
+}
+
+#[test]
+fn should_tell_missing_parts_of_downloaded_patchset() {
+    let raw_patches = vec![
+        "Subject: [PATCH v2 0/3] Fix something\nMessage-Id: <cover@foo.bar>\n\nCover\n".to_string(),
+        "Subject: [PATCH v2 1/3] Fix foo\nMessage-Id: <patch-1@foo.bar>\n\nPatch 1\n".to_string(),
+        "Subject: [PATCH v2 3/3] Fix bar\nMessage-Id: <patch-3@foo.bar>\n\nPatch 3\n".to_string(),
+    ];
+
+    let series_parts = SeriesParts::from_raw_patches(3, &raw_patches);
+
+    assert!(series_parts.contains(0));
+    assert_eq!(vec![2], series_parts.missing());
+    assert!(!series_parts.is_intact());
+    assert_eq!(Some("missing 2/3".to_string()), series_parts.problems());
+    assert!(SeriesParts::from_raw_patches(1, &raw_patches[1..2]).is_intact());
+}
+
+#[test]
+fn should_parse_series_metadata() {
+    let raw_patches = vec![
+        "Subject: [PATCH v2 0/2] Fix something\n\nCover\n\n---\nbase-commit: 0123abcd\nchange-id: 20240718-fix-something-1a2b3c4d\nprerequisite-change-id: 20240601-foo-v1-0-abcdef:v3\nprerequisite-patch-id: 4567ef\nprerequisite-message-id: <bar@foo.bar>\n\nBest regards,\n".to_string(),
+        "Subject: [PATCH v2 1/2] Fix foo\n\nPatch 1\n---\nbase-commit: ffffffff\nprerequisite-patch-id: 4567ef\n".to_string(),
+    ];
+
+    let series_metadata = SeriesMetadata::parse(&raw_patches);
+
+    assert_eq!(
+        &Some("0123abcd".to_string()),
+        series_metadata.base_commit(),
+        "The base-commit of the cover letter should win"
+    );
+    assert_eq!(
+        &Some("20240718-fix-something-1a2b3c4d".to_string()),
+        series_metadata.change_id()
+    );
+    assert_eq!(
+        &vec![
+            Prerequisite::ChangeId("20240601-foo-v1-0-abcdef:v3".to_string()),
+            Prerequisite::PatchId("4567ef".to_string()),
+            Prerequisite::MessageId("<bar@foo.bar>".to_string()),
+        ],
+        series_metadata.prerequisites(),
+        "Repeated prerequisites should be listed once"
+    );
+    assert_eq!(
+        SeriesMetadata::default(),
+        SeriesMetadata::parse(&["Subject: [PATCH] Fix foo\n\nPatch\n".to_string()])
+    );
+}
+
+#[test]
+fn should_fetch_prerequisite_in_wanted_version() {
+    let mut lore_api_client = MockPatchFeedRequest::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|target_list, query, min_index| {
+            target_list == "some-list"
+                && query.free_text.as_deref() == Some("\"change-id: 20240601-foo-v1-0-abcdef\"")
+                && *min_index == 0
+        })
+        .returning(|_, _, _| {
+            Ok(feed(&[
+                ("[PATCH v4 0/2] Add foo", "foo@bar.foo.bar", "v4@foo.bar"),
+                ("[PATCH v3 0/2] Add foo", "foo@bar.foo.bar", "v3@foo.bar"),
+            ]))
+        });
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, query, min_index| {
+            query.free_text.as_deref() == Some("m:bar@foo.bar") && *min_index == 0
+        })
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+
+    let prerequisite = fetch_prerequisite(
+        &lore_api_client,
+        "some-list",
+        &Prerequisite::ChangeId("20240601-foo-v1-0-abcdef:v3".to_string()),
+    )
+    .unwrap();
+    assert_eq!(
+        Some("v3@foo.bar"),
+        prerequisite
+            .as_ref()
+            .map(|prerequisite| prerequisite.message_id().id())
+    );
+
+    let prerequisite = fetch_prerequisite(
+        &lore_api_client,
+        "some-list",
+        &Prerequisite::MessageId("<bar@foo.bar>".to_string()),
+    )
+    .unwrap();
+    assert_eq!(None, prerequisite);
+}
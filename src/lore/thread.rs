@@ -0,0 +1,160 @@
+use crate::lore::mbox::{extract_message_ids, parse_mbox, Message};
+use derive_getters::Getters;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// A single message of a thread, as read from the thread mbox.
+#[derive(Getters, Debug, Clone, PartialEq)]
+pub struct ThreadMessage {
+    message_id: String,
+    in_reply_to: Option<String>,
+    references: Vec<String>,
+    author: String,
+    subject: String,
+    date: String,
+    /// The parsed message, with all its headers and decoded body
+    message: Message,
+}
+
+impl ThreadMessage {
+    /// Returns the value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.message.header(name)
+    }
+
+    pub fn body(&self) -> &str {
+        self.message.body()
+    }
+}
+
+impl From<Message> for ThreadMessage {
+    fn from(message: Message) -> Self {
+        let first_id = |header_name: &str| {
+            message
+                .header(header_name)
+                .and_then(|value| extract_message_ids(value).into_iter().next())
+                .map(str::to_string)
+        };
+
+        ThreadMessage {
+            message_id: first_id("Message-ID").unwrap_or_default(),
+            in_reply_to: first_id("In-Reply-To"),
+            references: message
+                .header("References")
+                .map(|value| {
+                    extract_message_ids(value)
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            author: extract_author_name(message.header("From").unwrap_or_default()),
+            subject: message.subject().to_string(),
+            date: message.header("Date").unwrap_or_default().to_string(),
+            message,
+        }
+    }
+}
+
+/// The reply tree of a whole conversation.
+///
+/// Messages are kept in the order they appear in the mbox (public-inbox sorts
+/// them by date) and the tree is represented by the indexes of the children of
+/// each message. A message whose parent isn't part of the thread (e.g. a reply
+/// to a message that wasn't archived) becomes a root.
+#[derive(Getters, Debug, Clone)]
+pub struct Thread {
+    messages: Vec<ThreadMessage>,
+    roots: Vec<usize>,
+    #[getter(skip)]
+    children: Vec<Vec<usize>>,
+}
+
+impl Thread {
+    /// Builds the thread from the mbox returned by a thread request.
+    pub fn from_mbox(mbox: &str) -> Thread {
+        let messages: Vec<ThreadMessage> = parse_mbox(mbox)
+            .into_iter()
+            .map(ThreadMessage::from)
+            .filter(|message| !message.message_id.is_empty())
+            .collect();
+
+        let index_by_id: HashMap<&str, usize> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| (message.message_id.as_str(), index))
+            .collect();
+
+        let mut roots = Vec::new();
+        let mut children = vec![Vec::new(); messages.len()];
+
+        for (index, message) in messages.iter().enumerate() {
+            match find_parent(message, &index_by_id).filter(|&parent| parent != index) {
+                Some(parent) => children[parent].push(index),
+                None => roots.push(index),
+            }
+        }
+
+        Thread {
+            messages,
+            roots,
+            children,
+        }
+    }
+
+    /// Indexes of the direct replies to the message at `index`.
+    pub fn children(&self, index: usize) -> &[usize] {
+        &self.children[index]
+    }
+
+    /// Walks the reply tree depth-first, returning each message index with
+    /// its depth in the tree. This is the order in which a threaded view
+    /// displays the conversation.
+    pub fn flatten(&self) -> Vec<(usize, usize)> {
+        let mut flattened = Vec::with_capacity(self.messages.len());
+        let mut stack: Vec<(usize, usize)> =
+            self.roots.iter().rev().map(|&root| (root, 0)).collect();
+
+        while let Some((index, depth)) = stack.pop() {
+            flattened.push((index, depth));
+            for &child in self.children[index].iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+
+        flattened
+    }
+}
+
+/// The parent of a message is the one it directly replies to. If it isn't in
+/// the thread, the closest ancestor listed in `References` is used instead.
+fn find_parent(message: &ThreadMessage, index_by_id: &HashMap<&str, usize>) -> Option<usize> {
+    if let Some(in_reply_to) = &message.in_reply_to {
+        if let Some(&parent) = index_by_id.get(in_reply_to.as_str()) {
+            return Some(parent);
+        }
+    }
+
+    message
+        .references
+        .iter()
+        .rev()
+        .find_map(|reference| index_by_id.get(reference.as_str()).copied())
+}
+
+/// Extracts the display name of a `From` header, falling back to the address.
+fn extract_author_name(from: &str) -> String {
+    match from.split_once('<') {
+        Some((name, address)) => {
+            let name = name.trim().trim_matches('"').trim();
+            if name.is_empty() {
+                address.trim_end_matches('>').trim().to_string()
+            } else {
+                name.to_string()
+            }
+        }
+        None => from.trim().to_string(),
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+use std::fs;
+
+fn thread_sample() -> Thread {
+    let mbox = fs::read_to_string("src/test_samples/thread/thread_sample.mbx").unwrap();
+    Thread::from_mbox(&mbox)
+}
+
+#[test]
+fn should_parse_all_messages_of_thread() {
+    let thread = thread_sample();
+
+    assert_eq!(6, thread.messages().len(), "Wrong number of messages");
+
+    let review = &thread.messages()[3];
+    assert_eq!("reply-1@bar.foo.bar", review.message_id());
+    assert_eq!(
+        &Some("1234.567-1-john@johnson.com".to_string()),
+        review.in_reply_to()
+    );
+    assert_eq!(
+        &vec![
+            "1234.567-0-john@johnson.com".to_string(),
+            "1234.567-1-john@johnson.com".to_string()
+        ],
+        review.references(),
+        "Folded `References` header should be unfolded"
+    );
+    assert_eq!("Foo Bar", review.author());
+    assert_eq!("Re: [PATCH 1/2] some/subsystem: Do this", review.subject());
+    assert_eq!("Tue, 25 Jun 2024 10:00:00 +0000", review.date());
+
+    assert_eq!(
+        "lima@luma.rs",
+        thread.messages()[5].author(),
+        "Author without display name should fallback to the address"
+    );
+}
+
+#[test]
+fn should_unescape_mboxrd_from_lines() {
+    let thread = thread_sample();
+
+    assert!(
+        thread.messages()[1]
+            .body()
+            .contains("\nFrom the documentation, this is needed.\n"),
+        "Escaped `>From ` line should be unescaped"
+    );
+}
+
+#[test]
+fn should_build_reply_tree() {
+    let thread = thread_sample();
+
+    assert_eq!(&vec![0], thread.roots(), "Only the cover should be a root");
+    assert_eq!(&[1, 2], thread.children(0));
+    assert_eq!(&[3], thread.children(1));
+    assert_eq!(&[4], thread.children(3));
+    assert_eq!(
+        &[5],
+        thread.children(2),
+        "Reply to a missing message should hang from its closest reference"
+    );
+}
+
+#[test]
+fn should_flatten_thread_depth_first() {
+    let thread = thread_sample();
+
+    assert_eq!(
+        vec![(0, 0), (1, 1), (3, 2), (4, 3), (2, 1), (5, 2)],
+        thread.flatten()
+    );
+}
+
+#[test]
+fn orphan_messages_should_become_roots() {
+    let mbox = "From mboxrd@z Thu Jan  1 00:00:00 1970\n\
+        Message-Id: <a@b>\n\
+        In-Reply-To: <missing@b>\n\
+        \n\
+        body\n";
+    let thread = Thread::from_mbox(mbox);
+
+    assert_eq!(&vec![0], thread.roots());
+    assert_eq!(vec![(0, 0)], thread.flatten());
+}
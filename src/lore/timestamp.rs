@@ -0,0 +1,117 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Local, SecondsFormat, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// Parses the date of a message, either in RFC 3339, as in the feeds, or in
+/// RFC 2822, as in the `Date` header of the messages.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_rfc2822(value))
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+/// (De)serializes optional timestamps as the RFC 3339 strings of the feeds,
+/// like `2024-07-18T16:49:00Z`, with an empty string for unknown ones. Dates
+/// that can't be parsed are taken as unknown instead of failing the whole
+/// feed.
+pub mod optional_rfc3339 {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_str(&super::to_rfc3339(timestamp)),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(super::parse_timestamp(&value))
+    }
+}
+
+/// How timestamps are shown
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum TimestampFormat {
+    /// Date and time in the local timezone, like `2024-07-18 13:49`
+    #[default]
+    #[serde(rename = "absolute")]
+    Absolute,
+    /// Time elapsed since then, like `3h ago`
+    #[serde(rename = "relative")]
+    Relative,
+}
+
+impl TimestampFormat {
+    pub fn format(&self, timestamp: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+        let Some(timestamp) = timestamp else {
+            return "unknown".to_string();
+        };
+        match self {
+            TimestampFormat::Absolute => timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            TimestampFormat::Relative => match format_age(now - timestamp).as_str() {
+                "now" => "now".to_string(),
+                age => format!("{age} ago"),
+            },
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "absolute" => Ok(TimestampFormat::Absolute),
+            "relative" => Ok(TimestampFormat::Relative),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for TimestampFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimestampFormat::Absolute => write!(f, "absolute"),
+            TimestampFormat::Relative => write!(f, "relative"),
+        }
+    }
+}
+
+/// Compact form of `age` in its largest whole unit, like `3h` or `2w`.
+/// Ages under a minute, including negative ones from skewed clocks, are
+/// `now`.
+pub fn format_age(age: TimeDelta) -> String {
+    let units = [
+        ("y", TimeDelta::days(365)),
+        ("mo", TimeDelta::days(30)),
+        ("w", TimeDelta::weeks(1)),
+        ("d", TimeDelta::days(1)),
+        ("h", TimeDelta::hours(1)),
+        ("m", TimeDelta::minutes(1)),
+    ];
+    units.iter().find(|(_, unit)| age >= *unit).map_or_else(
+        || "now".to_string(),
+        |(name, unit)| format!("{}{name}", age.num_seconds() / unit.num_seconds()),
+    )
+}
+
+/// Formats `timestamp` as in the feeds, like `2024-07-18T16:49:00Z`.
+pub fn to_rfc3339(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
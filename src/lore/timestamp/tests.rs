@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn should_parse_timestamps_of_feeds_and_messages() {
+    let expected = DateTime::parse_from_rfc3339("2024-07-18T16:49:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    assert_eq!(Some(expected), parse_timestamp("2024-07-18T16:49:00Z"));
+    assert_eq!(
+        Some(expected),
+        parse_timestamp("Thu, 18 Jul 2024 13:49:00 -0300"),
+        "Dates of the `Date` header should be converted to UTC"
+    );
+    assert_eq!(None, parse_timestamp(""));
+    assert_eq!(None, parse_timestamp("yesterday"));
+}
+
+#[test]
+fn should_format_ages_in_their_largest_unit() {
+    assert_eq!("now", format_age(TimeDelta::seconds(59)));
+    assert_eq!("now", format_age(TimeDelta::seconds(-3600)));
+    assert_eq!("5m", format_age(TimeDelta::minutes(5)));
+    assert_eq!("3h", format_age(TimeDelta::minutes(200)));
+    assert_eq!("2d", format_age(TimeDelta::hours(50)));
+    assert_eq!("2w", format_age(TimeDelta::days(15)));
+    assert_eq!("1mo", format_age(TimeDelta::days(30)));
+    assert_eq!("2y", format_age(TimeDelta::days(800)));
+}
+
+#[test]
+fn should_format_timestamps() {
+    let now = parse_timestamp("2024-07-18T16:49:00Z").unwrap();
+    let timestamp = parse_timestamp("2024-07-18T13:40:00Z");
+
+    assert_eq!("3h ago", TimestampFormat::Relative.format(timestamp, now));
+    assert_eq!("now", TimestampFormat::Relative.format(Some(now), now));
+    assert_eq!(
+        timestamp
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        TimestampFormat::Absolute.format(timestamp, now),
+        "Absolute timestamps should be in the local timezone"
+    );
+    assert_eq!("unknown", TimestampFormat::Absolute.format(None, now));
+}
@@ -0,0 +1,180 @@
+use std::{collections::BTreeMap, fmt::Display, sync::LazyLock};
+
+use derive_getters::Getters;
+use regex::Regex;
+
+use super::{
+    lore_session::{is_patch_subject, select_patchset_messages},
+    patch::Patch,
+    thread::Thread,
+};
+
+#[cfg(test)]
+mod tests;
+
+static RE_TRAILER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?im)^[ \t]*(Reviewed-by|Acked-by|Tested-by|Nacked-by|Reported-by):[ \t]*(\S.*?)[ \t]*$",
+    )
+    .unwrap()
+});
+
+/// Code-review trailers given in replies to patches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrailerKind {
+    ReviewedBy,
+    AckedBy,
+    TestedBy,
+    NackedBy,
+    ReportedBy,
+}
+
+impl TrailerKind {
+    pub const ALL: [TrailerKind; 5] = [
+        TrailerKind::ReviewedBy,
+        TrailerKind::AckedBy,
+        TrailerKind::TestedBy,
+        TrailerKind::NackedBy,
+        TrailerKind::ReportedBy,
+    ];
+
+    /// Single letter identifying the trailer in the compact summaries
+    pub fn initial(&self) -> char {
+        match self {
+            TrailerKind::ReviewedBy => 'R',
+            TrailerKind::AckedBy => 'A',
+            TrailerKind::TestedBy => 'T',
+            TrailerKind::NackedBy => 'N',
+            TrailerKind::ReportedBy => 'r',
+        }
+    }
+
+    fn from_name(name: &str) -> Option<TrailerKind> {
+        TrailerKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Display for TrailerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrailerKind::ReviewedBy => write!(f, "Reviewed-by"),
+            TrailerKind::AckedBy => write!(f, "Acked-by"),
+            TrailerKind::TestedBy => write!(f, "Tested-by"),
+            TrailerKind::NackedBy => write!(f, "Nacked-by"),
+            TrailerKind::ReportedBy => write!(f, "Reported-by"),
+        }
+    }
+}
+
+/// A trailer, like `Reviewed-by: Foo Bar <foo@bar.foo.bar>`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Trailer {
+    pub kind: TrailerKind,
+    /// Who gave the trailer, as written after the colon
+    pub person: String,
+}
+
+/// Trailers given, in the replies of the thread of a patchset, to each of its
+/// patches. Trailers given in replies to the cover letter apply to the whole
+/// series, as `b4` takes them.
+#[derive(Getters, Debug, Clone, Default, PartialEq)]
+pub struct PatchsetTrailers {
+    /// Trailers given in replies to the cover letter
+    cover_letter: Vec<Trailer>,
+    /// Trailers given in replies to each patch, by its number in the series
+    patches: BTreeMap<usize, Vec<Trailer>>,
+}
+
+impl PatchsetTrailers {
+    /// Collects the trailers given in the replies to the patches of the
+    /// patchset of `patch` in `thread`. Quoted lines and replies that are
+    /// patches themselves, like the ones of a newer version sent in reply
+    /// to the patchset, are left out with their own replies, and each person
+    /// counts once per trailer kind and patch.
+    pub fn harvest(thread: &Thread, patch: &Patch) -> PatchsetTrailers {
+        let (cover_letter, patches) = select_patchset_messages(thread, patch);
+        let index_of = |message_id: &str| {
+            thread
+                .messages()
+                .iter()
+                .position(|message| message.message_id() == message_id)
+        };
+        let harvest_replies = |message_id: &str| {
+            let mut trailers = Vec::new();
+            let Some(index) = index_of(message_id) else {
+                return trailers;
+            };
+            let mut pending: Vec<usize> = thread.children(index).to_vec();
+            while let Some(reply) = pending.pop() {
+                if is_patch_subject(thread.messages()[reply].subject()) {
+                    continue;
+                }
+                for trailer in extract_trailers(thread.messages()[reply].body()) {
+                    if !trailers.contains(&trailer) {
+                        trailers.push(trailer);
+                    }
+                }
+                pending.extend_from_slice(thread.children(reply));
+            }
+            trailers.sort();
+            trailers
+        };
+
+        PatchsetTrailers {
+            cover_letter: cover_letter
+                .map(|cover_letter| harvest_replies(cover_letter.message_id()))
+                .unwrap_or_default(),
+            patches: patches
+                .iter()
+                .map(|(&number, message)| (number, harvest_replies(message.message_id())))
+                .collect(),
+        }
+    }
+
+    /// Trailers that apply to the patch numbered `number` in the series,
+    /// including the ones given to the whole series.
+    pub fn of_patch(&self, number: usize) -> Vec<&Trailer> {
+        let mut trailers: Vec<&Trailer> = self.cover_letter.iter().collect();
+        for trailer in self.patches.get(&number).into_iter().flatten() {
+            if !trailers.contains(&trailer) {
+                trailers.push(trailer);
+            }
+        }
+        trailers
+    }
+
+    /// How many people gave a trailer of `kind` to any part of the series.
+    pub fn count(&self, kind: TrailerKind) -> usize {
+        let mut people: Vec<&str> = self
+            .cover_letter
+            .iter()
+            .chain(self.patches.values().flatten())
+            .filter(|trailer| trailer.kind == kind)
+            .map(|trailer| trailer.person.as_str())
+            .collect();
+        people.sort_unstable();
+        people.dedup();
+        people.len()
+    }
+}
+
+/// Extracts the trailers of a reply, skipping the quoted lines.
+pub fn extract_trailers(body: &str) -> Vec<Trailer> {
+    let unquoted: String = body
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('>'))
+        .map(|line| format!("{line}\n"))
+        .collect();
+
+    RE_TRAILER
+        .captures_iter(&unquoted)
+        .filter_map(|capture| {
+            Some(Trailer {
+                kind: TrailerKind::from_name(&capture[1])?,
+                person: capture[2].to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+use std::fs;
+
+use super::*;
+use crate::lore::patch::{Author, MessageID, PatchRegex};
+
+fn trailer(kind: TrailerKind, person: &str) -> Trailer {
+    Trailer {
+        kind,
+        person: person.to_string(),
+    }
+}
+
+fn harvest_sample() -> PatchsetTrailers {
+    let mbox = fs::read_to_string("src/test_samples/trailers/thread_with_trailers.mbx").unwrap();
+    let mut patch = Patch::new(
+        "[PATCH v2 0/2] some/subsystem: Do this and that".to_string(),
+        Author {
+            name: "John Johnson".to_string(),
+            email: "john@johnson.com".to_string(),
+        },
+        MessageID {
+            href: "https://lore.kernel.org/some-list/1234.567-0-john@johnson.com/".to_string(),
+        },
+        None,
+        "2024-06-24T19:15:48Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+
+    PatchsetTrailers::harvest(&Thread::from_mbox(&mbox), &patch)
+}
+
+#[test]
+fn should_harvest_trailers_of_replies_per_patch() {
+    let patchset_trailers = harvest_sample();
+
+    assert_eq!(
+        &vec![trailer(TrailerKind::AckedBy, "Foo Bar <foo@bar.foo.bar>")],
+        patchset_trailers.cover_letter(),
+        "Quoted trailers and trailers of the cover letter itself should be left out"
+    );
+    assert_eq!(
+        &vec![
+            trailer(TrailerKind::ReviewedBy, "Lima Luma <lima@luma.rs>"),
+            trailer(TrailerKind::TestedBy, "Lima Luma <lima@luma.rs>"),
+        ],
+        patchset_trailers.patches().get(&1).unwrap(),
+        "Repeated trailers should count once"
+    );
+    assert_eq!(
+        &vec![trailer(TrailerKind::NackedBy, "Nay Sayer <nay@sayer.org>")],
+        patchset_trailers.patches().get(&2).unwrap()
+    );
+}
+
+#[test]
+fn should_leave_out_patches_of_newer_versions() {
+    let mbox =
+        fs::read_to_string("src/test_samples/trailers/thread_with_newer_version.mbx").unwrap();
+    let mut patch = Patch::new(
+        "[PATCH] some/subsystem: Do this".to_string(),
+        Author {
+            name: "John Johnson".to_string(),
+            email: "john@johnson.com".to_string(),
+        },
+        MessageID {
+            href: "https://lore.kernel.org/some-list/1234.567-v1-john@johnson.com/".to_string(),
+        },
+        None,
+        "2024-06-24T19:15:48Z".to_string(),
+    );
+    patch.update_patch_metadata(&PatchRegex::new());
+
+    let patchset_trailers = PatchsetTrailers::harvest(&Thread::from_mbox(&mbox), &patch);
+
+    assert_eq!(
+        &vec![trailer(TrailerKind::AckedBy, "Foo Bar <foo@bar.foo.bar>")],
+        patchset_trailers.patches().get(&1).unwrap(),
+        "Trailers of the v2 patch and of its replies shouldn't count for v1"
+    );
+}
+
+#[test]
+fn should_count_trailers_of_series() {
+    let patchset_trailers = harvest_sample();
+
+    assert_eq!(
+        vec![
+            &trailer(TrailerKind::AckedBy, "Foo Bar <foo@bar.foo.bar>"),
+            &trailer(TrailerKind::NackedBy, "Nay Sayer <nay@sayer.org>"),
+        ],
+        patchset_trailers.of_patch(2),
+        "Trailers given to the series should apply to every patch"
+    );
+    assert_eq!(1, patchset_trailers.count(TrailerKind::ReviewedBy));
+    assert_eq!(1, patchset_trailers.count(TrailerKind::AckedBy));
+    assert_eq!(1, patchset_trailers.count(TrailerKind::NackedBy));
+    assert_eq!(0, patchset_trailers.count(TrailerKind::ReportedBy));
+}
+
+#[test]
+fn should_extract_trailers_case_insensitively() {
+    assert_eq!(
+        vec![trailer(
+            TrailerKind::ReviewedBy,
+            "Foo Bar <foo@bar.foo.bar>"
+        )],
+        extract_trailers("LGTM\n\n  reviewed-by:   Foo Bar <foo@bar.foo.bar>  \n")
+    );
+}
@@ -0,0 +1,119 @@
+use std::{fs::File, io};
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    json_file,
+    lore_api_client::{ClientError, PatchFeedRequest},
+    lore_session::{LoreSession, LoreSessionError},
+    query::LoreQuery,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How many patchsets are fetched at a time while looking for the last seen
+/// one of a watch
+const COUNT_STEP: usize = 20;
+
+/// A named search, like "patches touching drivers/gpu/drm/vkms", whose new
+/// patchsets are tracked across sessions
+#[derive(Getters, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Watch {
+    name: String,
+    target_list: String,
+    /// Name of the public-inbox instance searched
+    instance: String,
+    query: LoreQuery,
+    /// Message-id of the newest patchset when the watch was last visited, if
+    /// it ever was
+    #[serde(default)]
+    last_seen: Option<String>,
+}
+
+impl Watch {
+    pub fn new(name: &str, target_list: &str, instance: &str, query: LoreQuery) -> Self {
+        Watch {
+            name: name.to_string(),
+            target_list: target_list.to_string(),
+            instance: instance.to_string(),
+            query,
+            last_seen: None,
+        }
+    }
+
+    /// Records `newest` as the newest patchset seen, if any. Watches without
+    /// results keep the last one seen.
+    pub fn mark_seen(&mut self, newest: Option<String>) {
+        if newest.is_some() {
+            self.last_seen = newest;
+        }
+    }
+}
+
+/// Patchsets of the feed of a [Watch] sent after the last visit
+#[derive(Getters, Debug, Clone, PartialEq)]
+pub struct NewPatchsets {
+    /// How many were found, up to the maximum looked for
+    count: usize,
+    /// Whether there may be more than `count` of them
+    is_capped: bool,
+    /// Message-id of the newest patchset of the feed, if any
+    newest: Option<String>,
+}
+
+/// Counts the patchsets of the feed of `watch` that come before the last one
+/// seen, looking at most at `max_count` of them. If the last one seen isn't
+/// found, like for watches never visited, every patchset looked at is new.
+pub fn count_new_patchsets<T: PatchFeedRequest>(
+    lore_api_client: &T,
+    watch: &Watch,
+    max_count: usize,
+) -> Result<NewPatchsets, LoreSessionError> {
+    let mut lore_session = LoreSession::with_query(watch.target_list.clone(), watch.query.clone());
+    let mut patchsets_count = 0;
+    let mut is_feed_exhausted = false;
+
+    loop {
+        let last_seen_index = lore_session
+            .representative_patches_ids()
+            .iter()
+            .position(|message_id| Some(message_id) == watch.last_seen.as_ref());
+        let fetched_count = lore_session.representative_patches_ids().len();
+
+        if let Some(count) = last_seen_index.filter(|&count| count <= max_count) {
+            return Ok(NewPatchsets {
+                count,
+                is_capped: false,
+                newest: lore_session.representative_patches_ids().first().cloned(),
+            });
+        }
+        if is_feed_exhausted || fetched_count > max_count {
+            return Ok(NewPatchsets {
+                count: fetched_count.min(max_count),
+                is_capped: fetched_count > max_count,
+                newest: lore_session.representative_patches_ids().first().cloned(),
+            });
+        }
+
+        patchsets_count += COUNT_STEP;
+        match lore_session.process_n_representative_patches(lore_api_client, patchsets_count) {
+            Ok(()) => {}
+            Err(LoreSessionError::FromLoreAPIClient(ClientError::EndOfFeed)) => {
+                is_feed_exhausted = true;
+            }
+            Err(lore_session_error) => return Err(lore_session_error),
+        }
+    }
+}
+
+pub fn save_watches(watches: &Vec<Watch>, filepath: &str) -> io::Result<()> {
+    json_file::save_json_atomically(filepath, watches)
+}
+
+pub fn load_watches(filepath: &str) -> io::Result<Vec<Watch>> {
+    let watches_file = File::open(filepath)?;
+    let watches = serde_json::from_reader(watches_file)?;
+    Ok(watches)
+}
@@ -0,0 +1,84 @@
+use super::*;
+use crate::lore::{
+    atom::{self, FeedEntry},
+    lore_api_client::MockPatchFeedRequest,
+};
+
+fn message_id(id: usize) -> String {
+    format!("https://lore.kernel.org/dri-devel/patch-{id}@foo.bar/")
+}
+
+/// Feed of `count` single patches, the newest first
+fn feed(count: usize) -> String {
+    let entries: Vec<FeedEntry> = (0..count)
+        .map(|id| FeedEntry {
+            title: format!("[PATCH] drm/vkms: Change {id}"),
+            author_name: "Foo Bar".to_string(),
+            author_email: "foo@bar.com".to_string(),
+            updated: "2024-07-18T16:49:00Z".to_string(),
+            href: message_id(id),
+            in_reply_to_href: None,
+        })
+        .collect();
+    atom::render_feed(&entries)
+}
+
+fn lore_api_client(count: usize) -> MockPatchFeedRequest {
+    let mut lore_api_client = MockPatchFeedRequest::new();
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index == 0)
+        .returning(move |_, _, _| Ok(feed(count)));
+    lore_api_client
+        .expect_request_patch_feed()
+        .withf(|_, _, min_index| *min_index > 0)
+        .returning(|_, _, _| Err(ClientError::EndOfFeed));
+    lore_api_client
+}
+
+fn watch() -> Watch {
+    Watch::new(
+        "vkms",
+        "dri-devel",
+        "lore",
+        LoreQuery {
+            touched_file: Some("drivers/gpu/drm/vkms".to_string()),
+            ..LoreQuery::default()
+        },
+    )
+}
+
+#[test]
+fn should_count_patchsets_newer_than_last_seen() {
+    let mut watch = watch();
+    watch.mark_seen(Some(message_id(3)));
+
+    let new_patchsets = count_new_patchsets(&lore_api_client(30), &watch, 50).unwrap();
+
+    assert_eq!(3, new_patchsets.count());
+    assert!(!new_patchsets.is_capped());
+    assert_eq!(&Some(message_id(0)), new_patchsets.newest());
+}
+
+#[test]
+fn should_count_every_patchset_of_watches_never_visited() {
+    let new_patchsets = count_new_patchsets(&lore_api_client(30), &watch(), 50).unwrap();
+    assert_eq!(30, new_patchsets.count());
+    assert!(!new_patchsets.is_capped());
+
+    let new_patchsets = count_new_patchsets(&lore_api_client(30), &watch(), 10).unwrap();
+    assert_eq!(10, new_patchsets.count());
+    assert!(
+        new_patchsets.is_capped(),
+        "Counts past the maximum should be capped"
+    );
+}
+
+#[test]
+fn should_keep_last_seen_of_watches_without_results() {
+    let mut watch = watch();
+    watch.mark_seen(Some(message_id(3)));
+    watch.mark_seen(None);
+
+    assert_eq!(&Some(message_id(3)), watch.last_seen());
+}
@@ -23,6 +23,7 @@ fn main() -> color_eyre::Result<()> {
         ControlFlow::Break(b) => return b,
         ControlFlow::Continue(t) => terminal = t,
     }
+    app.open_last_used_list();
 
     run_app(terminal, app)?;
     utils::restore()?;
@@ -1,8 +1,14 @@
 use crate::app::{screens::CurrentScreen, App};
+use chrono::{Local, TimeZone};
+use patch_hub::lore::{
+    patch::{Patch, PatchTag},
+    series::SeriesParts,
+    trailers::{PatchsetTrailers, TrailerKind},
+};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Text,
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
@@ -15,6 +21,9 @@ pub mod loading_screen;
 mod mail_list;
 mod navigation_bar;
 pub mod popup;
+mod search;
+mod thread;
+mod watches;
 
 pub fn draw_ui(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -26,16 +35,17 @@ pub fn draw_ui(f: &mut Frame, app: &App) {
         ])
         .split(f.area());
 
-    render_title(f, chunks[0]);
+    render_title(f, app, chunks[0]);
 
     match app.current_screen {
         CurrentScreen::MailingListSelection => mail_list::render_main(f, app, chunks[1]),
-        CurrentScreen::BookmarkedPatchsets => {
-            bookmarked::render_main(f, &app.bookmarked_patchsets, chunks[1])
-        }
+        CurrentScreen::BookmarkedPatchsets => bookmarked::render_main(f, app, chunks[1]),
         CurrentScreen::LatestPatchsets => latest::render_main(f, app, chunks[1]),
         CurrentScreen::PatchsetDetails => details_actions::render_main(f, app, chunks[1]),
         CurrentScreen::EditConfig => edit_config::render_main(f, app, chunks[1]),
+        CurrentScreen::Search => search::render_main(f, app, chunks[1]),
+        CurrentScreen::PatchsetThread => thread::render_main(f, app, chunks[1]),
+        CurrentScreen::Watches => watches::render_main(f, app, chunks[1]),
     }
 
     navigation_bar::render(f, app, chunks[2]);
@@ -47,7 +57,7 @@ pub fn draw_ui(f: &mut Frame, app: &App) {
     });
 }
 
-fn render_title(f: &mut Frame, chunk: Rect) {
+fn render_title(f: &mut Frame, app: &App, chunk: Rect) {
     let title_block = Block::default()
         .borders(Borders::ALL)
         .style(Style::default())
@@ -55,18 +65,98 @@ fn render_title(f: &mut Frame, chunk: Rect) {
 
     let title_content: String = "Patch Hub".to_string();
 
-    let title = Paragraph::new(Text::styled(
+    let mut title_spans = vec![Span::styled(
         title_content,
         Style::default()
             .fg(Color::Green)
             .add_modifier(Modifier::ITALIC),
-    ))
-    .centered()
-    .block(title_block);
+    )];
+    if let Some(stale_status) = stale_status(app) {
+        title_spans.push(Span::styled(
+            format!(" [{stale_status}]"),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let title = Paragraph::new(Line::from(title_spans))
+        .centered()
+        .block(title_block);
 
     f.render_widget(title, chunk);
 }
 
+/// Describes how stale the data shown is, if the app is offline, including
+/// when the listed patchsets were saved, if they came from a saved feed.
+fn stale_status(app: &App) -> Option<String> {
+    if !app.is_offline {
+        return None;
+    }
+
+    let stale_since = match app.current_screen {
+        CurrentScreen::LatestPatchsets => app
+            .latest_patchsets
+            .as_ref()
+            .and_then(|latest_patchsets| *latest_patchsets.stale_since()),
+        _ => None,
+    };
+    let saved_at = stale_since
+        .and_then(|stale_since| Local.timestamp_opt(stale_since as i64, 0).single())
+        .map(|saved_at| saved_at.format("%Y-%m-%d %H:%M"));
+
+    match saved_at {
+        Some(saved_at) => Some(format!("offline, feed saved at {saved_at}")),
+        None => Some("offline".to_string()),
+    }
+}
+
+/// Compact summary of how many trailers of each kind were given, like
+/// `R2 A1`, leaving out the kinds nobody gave.
+fn format_trailer_counts(count: impl Fn(TrailerKind) -> usize) -> String {
+    TrailerKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let count = count(kind);
+            (count > 0).then(|| format!("{}{count}", kind.initial()))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trailer counts column of the listings of patchsets, with `?` for
+/// patchsets whose thread wasn't loaded yet.
+fn trailer_counts_column(trailers: Option<&PatchsetTrailers>) -> String {
+    let trailer_counts = match trailers {
+        Some(trailers) => format_trailer_counts(|kind| trailers.count(kind)),
+        None => "?".to_string(),
+    };
+    format!("{:11.11}", trailer_counts)
+}
+
+/// Tags column of the listings of patchsets, like `RFC net-next`
+fn tag_column(tag: &PatchTag) -> String {
+    format!("{:14.14}", tag.to_string())
+}
+
+/// Marks, in the listings of patchsets, the series known to be missing parts
+/// or to have duplicated ones.
+fn series_parts_marker(series_parts: Option<&SeriesParts>) -> char {
+    match series_parts {
+        Some(series_parts) if !series_parts.is_intact() => '!',
+        _ => ' ',
+    }
+}
+
+/// Style of the rows of the listings of patchsets, in bold for the ones not
+/// opened yet.
+fn patchset_row_style(app: &App, patch: &Patch) -> Style {
+    let style = Style::default().fg(Color::Yellow);
+    if app.seen_patchsets.is_seen(patch) {
+        style
+    } else {
+        style.add_modifier(Modifier::BOLD)
+    }
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     // Cut the given rectangle into three vertical pieces
@@ -1,5 +1,7 @@
-use crate::app;
-use app::screens::bookmarked::BookmarkedPatchsets;
+use super::{patchset_row_style, series_parts_marker, tag_column, trailer_counts_column};
+use crate::app::App;
+use chrono::Utc;
+use patch_hub::lore::{patch::PatchOrder, timestamp};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -8,26 +10,40 @@ use ratatui::{
     Frame,
 };
 
-pub fn render_main(f: &mut Frame, bookmarked_patchsets: &BookmarkedPatchsets, chunk: Rect) {
+pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
+    let bookmarked_patchsets = &app.bookmarked_patchsets;
     let patchset_index = bookmarked_patchsets.patchset_index;
     let mut list_items = Vec::<ListItem>::new();
+    let now = Utc::now();
 
-    for (index, patch) in bookmarked_patchsets.bookmarked_patchsets.iter().enumerate() {
+    for (index, patch) in bookmarked_patchsets
+        .listed_patchsets()
+        .into_iter()
+        .enumerate()
+    {
         let patch_title = format!("{:width$}", patch.title(), width = 70);
         let patch_title = format!("{:.width$}", patch_title, width = 70);
         let patch_author = format!("{:width$}", patch.author().name, width = 30);
         let patch_author = format!("{:.width$}", patch_author, width = 30);
+        let patch_age = patch.updated().map_or_else(
+            || "?".to_string(),
+            |updated| timestamp::format_age(now - updated),
+        );
         list_items.push(ListItem::new(
             Line::from(Span::styled(
                 format!(
-                    "{:03}. V{:02} | #{:02} | {} | {}",
+                    "{:03}. V{:02} | #{:02}{} | {:>4} | {} | {} | {} | {}",
                     index,
                     patch.version(),
                     patch.total_in_series(),
+                    series_parts_marker(app.series_parts_of(&patch.message_id().href)),
+                    patch_age,
+                    tag_column(patch.tag()),
                     patch_title,
-                    patch_author
+                    patch_author,
+                    trailer_counts_column(app.patchset_trailers.get(&patch.message_id().href))
                 ),
-                Style::default().fg(Color::Yellow),
+                patchset_row_style(app, patch),
             ))
             .centered(),
         ));
@@ -55,11 +71,26 @@ pub fn render_main(f: &mut Frame, bookmarked_patchsets: &BookmarkedPatchsets, ch
     f.render_stateful_widget(list, chunk, &mut list_state);
 }
 
-pub fn mode_footer_text() -> Vec<Span<'static>> {
-    vec![Span::styled(
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
+    let mut mode_footer_text = vec![Span::styled(
         "Bookmarked Patchsets",
         Style::default().fg(Color::Green),
-    )]
+    )];
+    let patch_filter = &app.bookmarked_patchsets.patch_filter;
+    if patch_filter.is_active() {
+        mode_footer_text.push(Span::styled(
+            format!(" [{patch_filter}]"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let order = app.bookmarked_patchsets.order;
+    if order != PatchOrder::Unsorted {
+        mode_footer_text.push(Span::styled(
+            format!(" ({order})"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    mode_footer_text
 }
 
 pub fn keys_hint() -> Span<'static> {
@@ -1,3 +1,4 @@
+use chrono::Utc;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,13 +7,34 @@ use ratatui::{
     Frame,
 };
 
+use super::format_trailer_counts;
 use crate::app::{screens::details_actions::PatchsetAction, App};
 
 fn render_details_and_actions(f: &mut Frame, app: &App, details_chunk: Rect, actions_chunk: Rect) {
     let patchset_details_and_actions = app.details_actions.as_ref().unwrap();
 
     let patchset_details = &patchset_details_and_actions.representative_patch;
-    let patchset_details = vec![
+    let mut versions = vec![Span::styled("Versions: ", Style::default().fg(Color::Cyan))];
+    match &patchset_details_and_actions.series_history {
+        Some(series_history) => {
+            for version in series_history.versions() {
+                let style = if version.message_id() == patchset_details.message_id() {
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                versions.push(Span::styled(format!("v{}", version.version()), style));
+                versions.push(Span::raw(" "));
+            }
+        }
+        None => versions.push(Span::styled(
+            "looking up...",
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+    let mut patchset_details = vec![
         Line::from(vec![
             Span::styled(r#"  Title: "#, Style::default().fg(Color::Cyan)),
             Span::styled(
@@ -44,11 +66,101 @@ fn render_details_and_actions(f: &mut Frame, app: &App, details_chunk: Rect, act
         Line::from(vec![
             Span::styled("Last updated: ", Style::default().fg(Color::Cyan)),
             Span::styled(
-                patchset_details.updated().to_string(),
+                app.config
+                    .timestamp_format()
+                    .format(*patchset_details.updated(), Utc::now()),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Tags: ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                patchset_details.tag().to_string(),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Subsystem: ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                patchset_details
+                    .tag()
+                    .subsystem()
+                    .clone()
+                    .unwrap_or_default(),
                 Style::default().fg(Color::White),
             ),
         ]),
+        Line::from(versions),
+        Line::from(vec![
+            Span::styled("Parts: ", Style::default().fg(Color::Cyan)),
+            match patchset_details_and_actions.series_parts.problems() {
+                Some(problems) => Span::styled(problems, Style::default().fg(Color::Red)),
+                None => Span::styled("complete", Style::default().fg(Color::Green)),
+            },
+        ]),
     ];
+    let series_metadata = &patchset_details_and_actions.series_metadata;
+    for (name, value) in [
+        ("Base commit: ", series_metadata.base_commit()),
+        ("Change-id: ", series_metadata.change_id()),
+    ] {
+        patchset_details.push(Line::from(vec![
+            Span::styled(name, Style::default().fg(Color::Cyan)),
+            match value {
+                Some(value) => Span::styled(value.clone(), Style::default().fg(Color::White)),
+                None => Span::styled("unknown", Style::default().fg(Color::DarkGray)),
+            },
+        ]));
+    }
+    if !series_metadata.prerequisites().is_empty() {
+        patchset_details.push(Line::from(Span::styled(
+            "Prerequisites:",
+            Style::default().fg(Color::Cyan),
+        )));
+        for prerequisite in series_metadata.prerequisites() {
+            patchset_details.push(Line::from(Span::styled(
+                format!("  {prerequisite}"),
+                Style::default().fg(Color::White),
+            )));
+        }
+    }
+    patchset_details.push(Line::from(Span::styled(
+        "Trailers:",
+        Style::default().fg(Color::Cyan),
+    )));
+    match &patchset_details_and_actions.trailers {
+        Some(trailers) => {
+            for number in trailers.patches().keys() {
+                let trailer_counts = format_trailer_counts(|kind| {
+                    trailers
+                        .of_patch(*number)
+                        .iter()
+                        .filter(|trailer| trailer.kind == kind)
+                        .count()
+                });
+                patchset_details.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "  {number}/{}: ",
+                            patchset_details_and_actions
+                                .representative_patch
+                                .total_in_series()
+                        ),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    if trailer_counts.is_empty() {
+                        Span::styled("none", Style::default().fg(Color::DarkGray))
+                    } else {
+                        Span::styled(trailer_counts, Style::default().fg(Color::White))
+                    },
+                ]));
+            }
+        }
+        None => patchset_details.push(Line::from(Span::styled(
+            "  unknown",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
 
     let patchset_details = Paragraph::new(patchset_details)
         .block(
@@ -98,6 +210,21 @@ fn render_details_and_actions(f: &mut Frame, app: &App, details_chunk: Rect, act
             ),
             Span::styled("eviewed-by", Style::default().fg(Color::Cyan)),
         ]),
+        Line::from(vec![
+            if *patchset_actions.get(&PatchsetAction::Apply).unwrap() {
+                Span::styled("[x] ", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("[ ] ", Style::default().fg(Color::Cyan))
+            },
+            Span::styled(
+                "a",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::UNDERLINED)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("pply", Style::default().fg(Color::Cyan)),
+        ]),
     ];
     let patchset_actions = Paragraph::new(patchset_actions)
         .block(
@@ -122,7 +249,14 @@ fn render_preview(f: &mut Frame, app: &App, chunk: Rect) {
         .message_id()
         .href;
     let mut preview_title = String::from(" Preview ");
-    if let Some(successful_indexes) = app.reviewed_patchsets.get(representative_patch_message_id) {
+    if let Some(range_diff) = &patchset_details_and_actions.range_diff {
+        preview_title = format!(
+            " Range-diff v{}..v{} ",
+            range_diff.old_version, range_diff.new_version
+        );
+    } else if let Some(successful_indexes) =
+        app.reviewed_patchsets.get(representative_patch_message_id)
+    {
         if successful_indexes.contains(&preview_index) {
             preview_title = " Preview [REVIEWED] ".to_string();
         }
@@ -130,7 +264,10 @@ fn render_preview(f: &mut Frame, app: &App, chunk: Rect) {
 
     let preview_offset = patchset_details_and_actions.preview_scroll_offset;
     let preview_pan = patchset_details_and_actions.preview_pan;
-    let patch_preview = patchset_details_and_actions.patches_preview[preview_index].clone();
+    let patch_preview = patchset_details_and_actions
+        .current_preview()
+        .cloned()
+        .unwrap_or_default();
 
     let patch_preview = Paragraph::new(patch_preview)
         .block(
@@ -183,7 +320,7 @@ pub fn mode_footer_text() -> Vec<Span<'static>> {
 
 pub fn keys_hint() -> Span<'static> {
     Span::styled(
-        "(ESC) to return | (ENTER) run actions | (?) help",
+        "(ESC) to return | (ENTER) run actions | (t) thread | (?) help",
         Style::default().fg(Color::Red),
     )
 }
@@ -1,5 +1,6 @@
+use super::{patchset_row_style, series_parts_marker, tag_column, trailer_counts_column};
 use crate::app::App;
-use patch_hub::lore::patch::Patch;
+use patch_hub::lore::patch::{Patch, PatchOrder};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -9,8 +10,9 @@ use ratatui::{
 };
 
 pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
-    let page_number = app.latest_patchsets.as_ref().unwrap().page_number();
-    let patchset_index = app.latest_patchsets.as_ref().unwrap().patchset_index();
+    let latest_patchsets = app.latest_patchsets.as_ref().unwrap();
+    let page_number = latest_patchsets.page_number();
+    let patchset_index = latest_patchsets.patchset_index();
     let mut list_items = Vec::<ListItem>::new();
 
     let patch_feed_page: Vec<&Patch> = app
@@ -18,35 +20,43 @@ pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
         .as_ref()
         .unwrap()
         .get_current_patch_feed_page()
-        .unwrap();
+        .unwrap_or_default();
 
-    let mut index: usize = (page_number - 1) * app.config.page_size();
-    for patch in patch_feed_page {
+    let first_index: usize = (page_number - 1) * app.config.page_size();
+    for (index, patch) in (first_index..).zip(patch_feed_page) {
         let patch_title = format!("{:width$}", patch.title(), width = 70);
         let patch_title = format!("{:.width$}", patch_title, width = 70);
         let patch_author = format!("{:width$}", patch.author().name, width = 30);
         let patch_author = format!("{:.width$}", patch_author, width = 30);
+        let mut row = format!(
+            "{:03}. V{:02} | #{:02}{} | {} | {} | {} | {}",
+            index,
+            patch.version(),
+            patch.total_in_series(),
+            series_parts_marker(app.series_parts_of(&patch.message_id().href)),
+            tag_column(patch.tag()),
+            patch_title,
+            patch_author,
+            trailer_counts_column(app.patchset_trailers.get(&patch.message_id().href))
+        );
+        if let Some(lists) = latest_patchsets.merged_lists_of(&patch.message_id().href) {
+            let lists = format!("{:width$}", lists.join(","), width = 25);
+            row.push_str(&format!(" | {:.width$}", lists, width = 25));
+        }
         list_items.push(ListItem::new(
-            Line::from(Span::styled(
-                format!(
-                    "{:03}. V{:02} | #{:02} | {} | {}",
-                    index,
-                    patch.version(),
-                    patch.total_in_series(),
-                    patch_title,
-                    patch_author
-                ),
-                Style::default().fg(Color::Yellow),
-            ))
-            .centered(),
+            Line::from(Span::styled(row, patchset_row_style(app, patch))).centered(),
         ));
-        index += 1;
     }
 
-    let list_block = Block::default()
+    let mut list_block = Block::default()
         .borders(Borders::ALL)
         .border_type(ratatui::widgets::BorderType::Double)
         .style(Style::default());
+    if list_items.is_empty() && app.latest_patchsets.as_ref().unwrap().fetch_job().is_some() {
+        list_block = list_block.title(
+            Line::styled(" Fetching patchsets... ", Style::default().fg(Color::Green)).centered(),
+        );
+    }
 
     let list = List::new(list_items)
         .block(list_block)
@@ -67,15 +77,37 @@ pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
     f.render_stateful_widget(list, chunk, &mut list_state);
 }
 
-pub fn mode_footer_text(app: &App) -> Vec<Span> {
-    vec![Span::styled(
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
+    let latest_patchsets = app.latest_patchsets.as_ref().unwrap();
+    let mode_text = if latest_patchsets.query().is_empty() {
         format!(
             "Latest Patchsets from {} (page {})",
-            &app.latest_patchsets.as_ref().unwrap().target_list(),
-            &app.latest_patchsets.as_ref().unwrap().page_number()
-        ),
-        Style::default().fg(Color::Green),
-    )]
+            latest_patchsets.target_list(),
+            latest_patchsets.page_number()
+        )
+    } else {
+        format!(
+            "Search `{}` in {} (page {})",
+            latest_patchsets.query(),
+            latest_patchsets.target_list(),
+            latest_patchsets.page_number()
+        )
+    };
+
+    let mut mode_footer_text = vec![Span::styled(mode_text, Style::default().fg(Color::Green))];
+    if latest_patchsets.patch_filter().is_active() {
+        mode_footer_text.push(Span::styled(
+            format!(" [{}]", latest_patchsets.patch_filter()),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if latest_patchsets.order() != &PatchOrder::Unsorted {
+        mode_footer_text.push(Span::styled(
+            format!(" ({})", latest_patchsets.order()),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    mode_footer_text
 }
 
 pub fn keys_hint() -> Span<'static> {
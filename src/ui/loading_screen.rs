@@ -33,7 +33,7 @@ pub fn render<B: Backend>(mut terminal: Terminal<B>, title: impl Display) -> Ter
 }
 
 /// Gets the current spinner state and updates the tick.
-fn spinner() -> char {
+pub fn spinner() -> char {
     let char_to_ret = SPINNER[unsafe { SPINNER_TICK }];
     unsafe {
         SPINNER_TICK = (SPINNER_TICK + 1) % 8;
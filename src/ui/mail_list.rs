@@ -11,21 +11,48 @@ use crate::app::App;
 pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
     let highlighted_list_index = app.mailing_list_selection.highlighted_list_index;
     let mut list_items = Vec::<ListItem>::new();
+    // Lists are only told apart by instance when there is more than one
+    let show_instances = app.config.instances().len() > 1;
 
-    for mailing_list in &app.mailing_list_selection.possible_mailing_lists {
-        list_items.push(ListItem::new(
-            Line::from(vec![
-                Span::styled(
-                    mailing_list.name().to_string(),
-                    Style::default().fg(Color::Magenta),
-                ),
-                Span::styled(
-                    format!(" - {}", mailing_list.description()),
-                    Style::default().fg(Color::White),
-                ),
-            ])
-            .centered(),
-        ))
+    let mailing_list_selection = &app.mailing_list_selection;
+    for (index, mailing_list) in mailing_list_selection
+        .possible_mailing_lists
+        .iter()
+        .enumerate()
+    {
+        let list_match = mailing_list_selection
+            .possible_mailing_list_matches
+            .get(index)
+            .cloned()
+            .unwrap_or_default();
+        let mut spans = Vec::new();
+        let list_preferences = &mailing_list_selection.list_preferences;
+        if mailing_list_selection.is_marked(mailing_list) {
+            spans.push(Span::styled("+ ", Style::default().fg(Color::Green)));
+        }
+        if list_preferences.is_favorite(mailing_list) {
+            spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+        } else if list_preferences.is_recent(mailing_list) {
+            spans.push(Span::styled("↺ ", Style::default().fg(Color::DarkGray)));
+        }
+        if show_instances {
+            spans.push(Span::styled(
+                format!("[{}] ", mailing_list.instance()),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        spans.extend(highlight_matches(
+            mailing_list.name(),
+            list_match.name_indices(),
+            Style::default().fg(Color::Magenta),
+        ));
+        spans.push(Span::styled(" - ", Style::default().fg(Color::White)));
+        spans.extend(highlight_matches(
+            mailing_list.description(),
+            list_match.description_indices(),
+            Style::default().fg(Color::White),
+        ));
+        list_items.push(ListItem::new(Line::from(spans).centered()))
     }
 
     let list_block = Block::default()
@@ -50,7 +77,40 @@ pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
     f.render_stateful_widget(list, chunk, &mut list_state);
 }
 
-pub fn mode_footer_text(app: &App) -> Vec<Span> {
+/// Splits `text` into spans in `style`, with the characters at `indices`
+/// underlined in yellow.
+fn highlight_matches(text: &str, indices: &[usize], style: Style) -> Vec<Span<'static>> {
+    let matched_style = style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut chunk = String::new();
+    let mut is_chunk_matched = false;
+
+    for (index, ch) in text.chars().enumerate() {
+        let is_matched = indices.binary_search(&index).is_ok();
+        if is_matched != is_chunk_matched && !chunk.is_empty() {
+            let chunk_style = if is_chunk_matched {
+                matched_style
+            } else {
+                style
+            };
+            spans.push(Span::styled(std::mem::take(&mut chunk), chunk_style));
+        }
+        is_chunk_matched = is_matched;
+        chunk.push(ch);
+    }
+    if !chunk.is_empty() {
+        let chunk_style = if is_chunk_matched {
+            matched_style
+        } else {
+            style
+        };
+        spans.push(Span::styled(chunk, chunk_style));
+    }
+
+    spans
+}
+
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
     let mut text_area = Span::default();
 
     if app.mailing_list_selection.target_list.is_empty() {
@@ -66,16 +126,16 @@ pub fn mode_footer_text(app: &App) -> Vec<Span> {
                     Style::default().fg(Color::Green),
                 );
                 break;
-            } else if mailing_list
-                .name()
-                .starts_with(&app.mailing_list_selection.target_list)
-            {
-                text_area = Span::styled(
-                    &app.mailing_list_selection.target_list,
-                    Style::default().fg(Color::LightCyan),
-                );
             }
         }
+        if text_area.content.is_empty()
+            && !app.mailing_list_selection.possible_mailing_lists.is_empty()
+        {
+            text_area = Span::styled(
+                &app.mailing_list_selection.target_list,
+                Style::default().fg(Color::LightCyan),
+            );
+        }
         if text_area.content.is_empty() {
             text_area = Span::styled(
                 &app.mailing_list_selection.target_list,
@@ -84,15 +144,27 @@ pub fn mode_footer_text(app: &App) -> Vec<Span> {
         }
     }
 
-    vec![
+    let mut mode_footer_text = vec![
         Span::styled("Target List: ", Style::default().fg(Color::Green)),
         text_area,
-    ]
+    ];
+    let marked_lists = &app.mailing_list_selection.marked_lists;
+    if !marked_lists.is_empty() {
+        let marked_names: Vec<&str> = marked_lists
+            .iter()
+            .map(|marked_list| marked_list.name().as_str())
+            .collect();
+        mode_footer_text.push(Span::styled(
+            format!(" | Merging: {}", marked_names.join("+")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    mode_footer_text
 }
 
 pub fn keys_hint() -> Span<'static> {
     Span::styled(
-        "(ESC) to quit | (ENTER) to confirm | (?) help",
+        "(ESC) to quit | (ENTER) to confirm | (TAB) merge | (F3) search | (F4) pin | (F6) watches | (?) help",
         Style::default().fg(Color::Red),
     )
 }
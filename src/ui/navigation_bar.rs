@@ -1,9 +1,13 @@
-use super::{bookmarked, details_actions, edit_config, latest, mail_list};
+use super::{
+    bookmarked, details_actions, edit_config, latest, loading_screen, mail_list, search, thread,
+    watches,
+};
 use crate::app::{self, App};
 use app::screens::CurrentScreen;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    text::Line,
+    style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
@@ -11,10 +15,13 @@ use ratatui::{
 pub fn render(f: &mut Frame, app: &App, chunk: Rect) {
     let mode_footer_text = match app.current_screen {
         CurrentScreen::MailingListSelection => mail_list::mode_footer_text(app),
-        CurrentScreen::BookmarkedPatchsets => bookmarked::mode_footer_text(),
+        CurrentScreen::BookmarkedPatchsets => bookmarked::mode_footer_text(app),
         CurrentScreen::LatestPatchsets => latest::mode_footer_text(app),
         CurrentScreen::PatchsetDetails => details_actions::mode_footer_text(),
         CurrentScreen::EditConfig => edit_config::mode_footer_text(app),
+        CurrentScreen::Search => search::mode_footer_text(app),
+        CurrentScreen::PatchsetThread => thread::mode_footer_text(app),
+        CurrentScreen::Watches => watches::mode_footer_text(app),
     };
     let mode_footer = Paragraph::new(Line::from(mode_footer_text))
         .block(Block::default().borders(Borders::ALL))
@@ -27,6 +34,9 @@ pub fn render(f: &mut Frame, app: &App, chunk: Rect) {
             CurrentScreen::LatestPatchsets => latest::keys_hint(),
             CurrentScreen::PatchsetDetails => details_actions::keys_hint(),
             CurrentScreen::EditConfig => edit_config::keys_hint(app),
+            CurrentScreen::Search => search::keys_hint(app),
+            CurrentScreen::PatchsetThread => thread::keys_hint(),
+            CurrentScreen::Watches => watches::keys_hint(),
         }
     };
 
@@ -34,11 +44,52 @@ pub fn render(f: &mut Frame, app: &App, chunk: Rect) {
         .block(Block::default().borders(Borders::ALL))
         .centered();
 
+    let Some(jobs_status) = jobs_status(app) else {
+        let footer_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(80)])
+            .split(chunk);
+
+        f.render_widget(mode_footer, footer_chunks[0]);
+        f.render_widget(keys_hint_footer, footer_chunks[1]);
+        return;
+    };
+
+    let jobs_footer = Paragraph::new(jobs_status)
+        .block(Block::default().borders(Borders::ALL))
+        .centered();
+
     let footer_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(80)])
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+            Constraint::Percentage(45),
+        ])
         .split(chunk);
 
     f.render_widget(mode_footer, footer_chunks[0]);
-    f.render_widget(keys_hint_footer, footer_chunks[1]);
+    f.render_widget(jobs_footer, footer_chunks[1]);
+    f.render_widget(keys_hint_footer, footer_chunks[2]);
+}
+
+/// Describes the oldest background job running, if any, and how many others
+/// are running along with it.
+fn jobs_status(app: &App) -> Option<Line<'_>> {
+    let mut running_jobs = app.jobs.running_jobs();
+    let oldest_job = running_jobs.next()?;
+    let other_jobs_count = running_jobs.count();
+
+    let mut status = format!("{} {}", loading_screen::spinner(), oldest_job.description());
+    if let Some(progress) = oldest_job.progress() {
+        status.push_str(&format!(" ({progress})"));
+    }
+    if other_jobs_count > 0 {
+        status.push_str(&format!(" +{other_jobs_count}"));
+    }
+
+    Some(Line::from(Span::styled(
+        status,
+        Style::default().fg(Color::Yellow),
+    )))
 }
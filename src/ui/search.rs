@@ -0,0 +1,82 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
+    let search = app.search.as_ref().unwrap();
+    let mut constraints = Vec::new();
+
+    for _ in 0..(chunk.height / 3) {
+        constraints.push(Constraint::Length(3));
+    }
+
+    let field_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(chunk);
+
+    let highlighted_entry = search.highlighted();
+    for i in 0..search.field_count() {
+        if i + 1 > field_chunks.len() {
+            break;
+        }
+
+        let (field, value) = search.field(i);
+        let value = Line::from(if search.is_editing() && i == highlighted_entry {
+            vec![
+                Span::styled(search.curr_edit().to_string(), Style::default()),
+                Span::styled(" ", Style::default().bg(Color::White)),
+            ]
+        } else {
+            vec![Span::from(value)]
+        });
+
+        let field_entry = Paragraph::new(value)
+            .centered()
+            .block(Block::default().borders(Borders::ALL).title(field))
+            .style(if i == highlighted_entry && search.is_editing() {
+                Style::default()
+                    .fg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if i == highlighted_entry {
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        f.render_widget(field_entry, field_chunks[i]);
+    }
+}
+
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
+    let search_state = app.search.as_ref().unwrap();
+    vec![if search_state.is_editing() {
+        Span::styled("Editing...", Style::default().fg(Color::LightYellow))
+    } else if let Some(status) = search_state.status() {
+        Span::styled(status, Style::default().fg(Color::Red))
+    } else {
+        Span::styled("Search Patchsets", Style::default().fg(Color::Green))
+    }]
+}
+
+pub fn keys_hint(app: &App) -> Span<'_> {
+    let search_state = app.search.as_ref().unwrap();
+    match search_state.is_editing() {
+        true => Span::styled(
+            "(ESC) cancel | (ENTER) confirm",
+            Style::default().fg(Color::Red),
+        ),
+        false => Span::styled(
+            "(ESC) return | (ENTER) search | (e) edit | (w) watch | (?) help",
+            Style::default().fg(Color::Red),
+        ),
+    }
+}
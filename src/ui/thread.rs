@@ -0,0 +1,132 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+fn render_tree(f: &mut Frame, app: &App, chunk: Rect) {
+    let thread_view = app.thread_view.as_ref().unwrap();
+    let mut list_items = Vec::<ListItem>::new();
+
+    for &(index, depth) in &thread_view.flattened_messages {
+        let message = &thread_view.thread.messages()[index];
+        let branch = if depth == 0 {
+            String::new()
+        } else {
+            format!("{}└─", "  ".repeat(depth - 1))
+        };
+
+        list_items.push(ListItem::new(Line::from(vec![
+            Span::styled(branch, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                message.author().to_string(),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(
+                format!(" - {}", message.subject()),
+                Style::default().fg(Color::Yellow),
+            ),
+        ])));
+    }
+
+    let list_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Double)
+        .title(Line::styled(" Thread ", Style::default().fg(Color::Green)).left_aligned());
+
+    let list = List::new(list_items)
+        .block(list_block)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED)
+                .fg(Color::Cyan),
+        )
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(thread_view.selected_index));
+
+    f.render_stateful_widget(list, chunk, &mut list_state);
+}
+
+fn render_message(f: &mut Frame, app: &App, chunk: Rect) {
+    let thread_view = app.thread_view.as_ref().unwrap();
+    let Some(message) = thread_view.selected_message() else {
+        return;
+    };
+
+    let mut message_lines = vec![
+        Line::from(vec![
+            Span::styled("From: ", Style::default().fg(Color::Cyan)),
+            Span::styled(message.author().to_string(), Style::default()),
+        ]),
+        Line::from(vec![
+            Span::styled("Date: ", Style::default().fg(Color::Cyan)),
+            Span::styled(message.date().to_string(), Style::default()),
+        ]),
+        Line::from(""),
+    ];
+
+    for line in thread_view.selected_message_lines() {
+        let style = if line.starts_with('>') {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+        message_lines.push(Line::styled(line, style));
+    }
+
+    let message_paragraph = Paragraph::new(message_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(ratatui::widgets::BorderType::Double)
+                .title(
+                    Line::styled(
+                        format!(" {} ", message.subject()),
+                        Style::default().fg(Color::Green),
+                    )
+                    .left_aligned(),
+                )
+                .padding(Padding::horizontal(1)),
+        )
+        .left_aligned()
+        .scroll((thread_view.body_scroll_offset as u16, 0));
+
+    f.render_widget(message_paragraph, chunk);
+}
+
+pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(chunk);
+
+    render_tree(f, app, chunks[0]);
+    render_message(f, app, chunks[1]);
+}
+
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
+    let thread_view = app.thread_view.as_ref().unwrap();
+    vec![Span::styled(
+        format!(
+            "Patchset Thread ({}/{} messages)",
+            thread_view.selected_index + 1,
+            thread_view.flattened_messages.len()
+        ),
+        Style::default().fg(Color::Green),
+    )]
+}
+
+pub fn keys_hint() -> Span<'static> {
+    Span::styled(
+        "(ESC) to return | (n/p) next/previous message | (z) fold quotes | (?) help",
+        Style::default().fg(Color::Red),
+    )
+}
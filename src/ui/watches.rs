@@ -0,0 +1,102 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, HighlightSpacing, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn render_main(f: &mut Frame, app: &App, chunk: Rect) {
+    let watches = &app.watches;
+    let mut list_items = Vec::<ListItem>::new();
+
+    for watch in &watches.watches {
+        let watch_name = format!("{:width$}", watch.name(), width = 40);
+        let watch_name = format!("{:.width$}", watch_name, width = 40);
+        let target_list = format!("{:width$}", watch.target_list(), width = 20);
+        let target_list = format!("{:.width$}", target_list, width = 20);
+        let (new_count, new_count_style) = match watches.new_patchsets.get(watch.name()) {
+            Some(new_patchsets) if new_patchsets.count() == 0 => {
+                ("no new".to_string(), Style::default().fg(Color::DarkGray))
+            }
+            Some(new_patchsets) => (
+                format!(
+                    "{}{} new",
+                    new_patchsets.count(),
+                    if new_patchsets.is_capped() { "+" } else { "" }
+                ),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => ("?".to_string(), Style::default().fg(Color::DarkGray)),
+        };
+        list_items.push(ListItem::new(
+            Line::from(vec![
+                Span::styled(
+                    format!("{watch_name} | {target_list} | "),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(format!("{new_count:>7}"), new_count_style),
+            ])
+            .centered(),
+        ));
+    }
+
+    let mut list_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Double)
+        .style(Style::default());
+    if list_items.is_empty() {
+        list_block = list_block.title(
+            Line::styled(
+                " No watches yet, save one from the search screen (F3) ",
+                Style::default().fg(Color::DarkGray),
+            )
+            .centered(),
+        );
+    } else if watches.count_job.is_some() {
+        list_block = list_block.title(
+            Line::styled(
+                " Counting new patchsets... ",
+                Style::default().fg(Color::Green),
+            )
+            .centered(),
+        );
+    }
+
+    let list = List::new(list_items)
+        .block(list_block)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED)
+                .fg(Color::Cyan),
+        )
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(watches.watch_index));
+
+    f.render_stateful_widget(list, chunk, &mut list_state);
+}
+
+pub fn mode_footer_text(app: &App) -> Vec<Span<'_>> {
+    let mut mode_footer_text = vec![Span::styled("Watches", Style::default().fg(Color::Green))];
+    if let Some(watch) = app.watches.selected_watch() {
+        mode_footer_text.push(Span::styled(
+            format!(" `{}`", watch.query()),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    mode_footer_text
+}
+
+pub fn keys_hint() -> Span<'static> {
+    Span::styled(
+        "(ESC) to return | (ENTER) to open | (d) delete | (?) help",
+        Style::default().fg(Color::Red),
+    )
+}